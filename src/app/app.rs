@@ -1,27 +1,46 @@
-use std::io::Stdout;
-use std::time::{Duration, Instant};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::error::Error;
 
-use ratatui::buffer::Buffer;
-use ratatui::crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, MouseEventKind};
-use ratatui::crossterm::execute;
-use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
-use ratatui::layout::Rect;
-use ratatui::prelude::CrosstermBackend;
-use ratatui::style::{Color, Style};
-use ratatui::widgets::Widget;
-use ratatui::{Frame, Terminal};
-
-use crate::game_core::GameCoreError;
-use crate::game_core::GameCore;
+use crate::game_core::{Choice, ChoiceOption, GameCoreError};
+use crate::game_core::{AiConfig, EconomyBot, GameCore, GameDataPaths, PlanetStatus};
+use crate::game_core::{Profile, ProfileError};
+use crate::game_core::{Replay, ReplayError};
+use crate::game_core::{format_playtime, get_todays_date};
 
+use super::frame_stats::FrameStats;
+use super::game_worker::{CommandOutcome, GameWorker, InputInfo, WorkerEvent};
+#[cfg(feature = "terminal")]
+use super::frontend::CrosstermFrontend;
+use super::frontend::{Frontend, InputEvent, KeyCode, KeyEvent, KeyModifiers};
+use super::history::CommandHistory;
+#[cfg(feature = "http-api")]
+use super::http_api::HttpApi;
+use super::keymap::{KeyAction, KeyBinding, Keymap, KeymapError};
 use super::log::LogMessage;
-use super::ui::UI;
+use super::pause_menu::{PauseAction, PauseMenu};
+use super::pedia_view::PediaView;
+use super::replay_view::{self, ReplayView};
+use super::session_timer::SessionTimer;
+use super::turn_progress::TurnProgress;
+use super::catch_up::CatchUp;
+use super::crash_report::{self, CrashReport};
+use super::settings::{self, Settings, SettingsError};
+use super::stats_export::StatsExport;
+use super::ui::{PediaState, PlaytimeState, ReplayState, StatusSection, UiState, UI};
 
 #[derive(Debug)]
 pub enum AppError {
     Io(std::io::Error),
     GameCoreError(GameCoreError),
+    KeymapError(KeymapError),
+    SettingsError(SettingsError),
+    ProfileError(ProfileError),
+    ReplayError(ReplayError),
+    // With the panic message
+    Crashed(String),
 }
 
 impl std::fmt::Display for AppError {
@@ -29,6 +48,11 @@ impl std::fmt::Display for AppError {
         match self {
             AppError::Io(err) => write!(f, "IO error: {}", err),
             AppError::GameCoreError(err) => write!(f, "GameCore error: {}", err),
+            AppError::KeymapError(err) => write!(f, "Keymap error: {}", err),
+            AppError::SettingsError(err) => write!(f, "Settings error: {}", err),
+            AppError::ProfileError(err) => write!(f, "Profile error: {}", err),
+            AppError::ReplayError(err) => write!(f, "Replay error: {}", err),
+            AppError::Crashed(message) => write!(f, "The game crashed: {}", message),
         }
     }
 }
@@ -38,6 +62,11 @@ impl Error for AppError {
         match self {
             AppError::Io(err) => Some(err),
             AppError::GameCoreError(err) => Some(err),
+            AppError::KeymapError(err) => Some(err),
+            AppError::SettingsError(err) => Some(err),
+            AppError::ProfileError(err) => Some(err),
+            AppError::ReplayError(err) => Some(err),
+            AppError::Crashed(_) => None,
         }
     }
 }
@@ -54,8 +83,40 @@ impl From<GameCoreError> for AppError {
     }
 }
 
+impl From<KeymapError> for AppError {
+    fn from(err: KeymapError) -> Self {
+        AppError::KeymapError(err)
+    }
+}
+
+impl From<SettingsError> for AppError {
+    fn from(err: SettingsError) -> Self {
+        AppError::SettingsError(err)
+    }
+}
+
+impl From<ProfileError> for AppError {
+    fn from(err: ProfileError) -> Self {
+        AppError::ProfileError(err)
+    }
+}
+
+impl From<ReplayError> for AppError {
+    fn from(err: ReplayError) -> Self {
+        AppError::ReplayError(err)
+    }
+}
+
 // =================================================================================================
 
+const MAX_LOGS: usize = 100; // TODO: Make this configurable
+
+// Energy, Minerals and Gas in the status pane
+const RESOURCE_ROWS: usize = 3;
+
+// Extra option of confirmation dialogs for flagged commands
+const DONT_ASK_AGAIN: &str = "Yes, and don't ask again this session";
+
 #[derive(PartialEq, Eq)]
 pub enum FocusedPane {
     Status,
@@ -65,188 +126,1399 @@ pub enum FocusedPane {
 
 pub struct App {
     ui: UI,
-    game_core: GameCore,
+    // Drawing only reads the snapshots the game sends back
+    game: GameWorker,
+    // For the typed command line, see draw
+    input_info: Option<InputInfo>,
+    keymap: Keymap,
+    settings: Settings,
     input_buffer: String,
     exit: bool,
     show_cursor: bool,
     focused_pane: FocusedPane,
     current_planet_idx: usize,
+    // 0 is the planet's surface, higher values its sub-locations
+    current_site_idx: usize,
+    status_section: StatusSection,
     logs: Vec<LogMessage>,
+    history: CommandHistory,
+    // Open while the Colonypedia pane is shown
+    pedia_view: Option<PediaView>,
+    show_missions: bool,
+    show_alerts: bool,
+    // Set while the log shows only the AI's thoughts
+    show_thoughts: bool,
+    // Explains every turn of the player when AI thoughts are enabled in the settings
+    thinking_bot: Option<EconomyBot>,
+    // Id of the decision dialog the player put off with Esc
+    hidden_choice: Option<String>,
+    // Commands flagged to ask for confirmation that are confirmed right away for the rest of
+    // the session
+    unasked_commands: Vec<String>,
+    // Set when watching a finished game instead of playing
+    replay_view: Option<ReplayView>,
+    pause_menu: Option<PauseMenu>,
+    session_timer: SessionTimer,
+    last_input: Instant,
+    // When the turn ends on its own, set after a command left nothing to do
+    auto_end_turn_at: Option<Instant>,
+    // When the turn ends on its own in auto-advance mode, restarted with every turn
+    auto_advance_at: Option<Instant>,
+    // Set while a command line ending the turn runs, keys wait until it is done
+    turn_progress: Option<TurnProgress>,
+    // Turn of the game saved when the game last crashed, offered on startup until the player
+    // restores or discards it
+    recovery_offer: Option<u32>,
+    // A loaded game whose commands turned out differently than when they were played, with how
+    // many did, offered until the player reconciles or keeps it
+    reconcile_offer: Option<(Replay, usize)>,
+    // Offered after an offline game was loaded, until the player accepts or skips it
+    catch_up_offer: Option<CatchUp>,
+    // Set while the accepted catch-up runs, reported once it is done
+    catch_up: Option<CatchUp>,
+    // Set when something shown changed since the last frame
+    needs_redraw: bool,
+    frame_stats: FrameStats,
+    // Set while a command runs, for the crash report
+    running_command: Option<String>,
+    // Set when the turns are exported to a statistics file
+    stats_export: Option<StatsExport>,
+    #[cfg(feature = "http-api")]
+    http_api: Option<HttpApi>,
 }
 
 impl App {
-    pub fn new() -> Result<Self, AppError> {
-        Ok(
-            App {
-                ui: UI::new(),
-                game_core: GameCore::new(None, None)?,
-                input_buffer: String::new(),
-                exit: false,
-                show_cursor: true,
-                focused_pane: FocusedPane::CommandInput,
-                current_planet_idx: 0,
-                logs: Vec::new(),
-            }
-        )
+    // Plays with the files of the named profile, or the shared ones in user/ when none is given
+    pub fn new(profile: Option<&str>) -> Result<Self, AppError> {
+        Self::new_game(profile, true)
+    }
+
+    // Starts the daily challenge of the date, today's when not given, in a new game even in
+    // offline mode
+    pub fn new_challenge(profile: Option<&str>, date: Option<&str>) -> Result<Self, AppError> {
+        let mut app = Self::new_game(profile, false)?;
+        let date = date.map_or_else(get_todays_date, str::to_string);
+        app.add_log(LogMessage::info(&format!("> challenge {}", date)));
+        app.execute_command(&format!("challenge {}", date));
+        Ok(app)
+    }
+
+    // Resuming offers the game saved by the last crash, or loads the offline game when there is none
+    fn new_game(profile_name: Option<&str>, resume: bool) -> Result<Self, AppError> {
+        let settings = Settings::load_for_profile(profile_name)?;
+        let profile_directory = profile_name.map(settings::get_profile_directory).transpose()?;
+
+        let mut logs = Vec::new();
+        if let Some(profile_name) = profile_name {
+            logs.push(LogMessage::info(&format!("Playing as profile '{}'.", profile_name)));
+        }
+        let history_settings = &settings.history;
+        let history = CommandHistory::load(&history_settings.file, history_settings.max_entries)
+            .unwrap_or_else(|err| {
+                logs.push(LogMessage::error(&format!("Failed to load command history: {}", err)));
+                CommandHistory::new(history_settings.max_entries)
+            });
+
+        // TODO: Show the profile on the main menu once it exists
+        let profile = Profile::load(&settings.profile.file).unwrap_or_else(|err| {
+            logs.push(LogMessage::error(&format!("Failed to load profile: {}", err)));
+            Profile::default()
+        });
+        logs.push(LogMessage::info(&format!("Profile - {}", profile.get_summary())));
+        if profile.get_games_played() == 0 {
+            logs.push(LogMessage::info("New here? Type 'tutorial' for a guided first game."));
+        }
+
+        #[cfg(feature = "http-api")]
+        let http_api = match HttpApi::bind(&settings.http_api.address) {
+            Ok(http_api) => {
+                let address = http_api
+                    .get_address()
+                    .map_or(settings.http_api.address.clone(), |address| address.to_string());
+                logs.push(LogMessage::info(&format!("Game state is served at http://{}/state", address)));
+                Some(http_api)
+            }
+            Err(err) => {
+                logs.push(LogMessage::error(&format!("Failed to start the HTTP API: {}", err)));
+                None
+            }
+        };
+
+        let thinking_bot = settings.ai_thoughts.enabled
+            .then(|| Self::load_thinking_bot(settings.ai_thoughts.personality.as_deref()))
+            .and_then(|thinking_bot| {
+                thinking_bot.inspect_err(|err| logs.push(LogMessage::error(&format!("Failed to load the AI: {}", err)))).ok()
+            });
+
+        let keymap = Keymap::load(&settings::get_profile_data_file(profile_directory.as_deref(), "data/keymap.toml"))?;
+        // Vim mode starts in normal mode, where the status pane has the focus
+        let focused_pane = if keymap.is_vim_mode() { FocusedPane::Status } else { FocusedPane::CommandInput };
+
+        // The seed is saved with the game, loading it brings back the same names
+        let name_seed = settings.game.random_names.then(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos() as u64)
+        });
+        let mut game = GameWorker::spawn(move || {
+            let paths = GameDataPaths::default();
+            match name_seed {
+                Some(name_seed) => GameCore::new_with_name_seed(&paths, name_seed),
+                None => GameCore::new(&paths),
+            }
+        })?;
+        let hint_settings = settings.hints.clone();
+        let stats_export = StatsExport::new(&settings.stats_export);
+        let recording_turns = stats_export.is_some();
+        let speed = settings.game.speed.clone();
+        // Set through commands so the replay and saved games keep them
+        let setup_commands = [
+            settings.player.name.as_ref().map(|name| format!("player name {}", name)),
+            settings.player.color.as_ref().map(|color| format!("player color {}", color)),
+            settings.rules.builder_mode.then(|| "rules builder_mode on".to_string()),
+            settings.rules.double_production.then(|| "rules double_production on".to_string()),
+            settings.rules.expensive_storage.then(|| "rules expensive_storage on".to_string()),
+            settings.rules.event_frequency.as_ref().map(|frequency| format!("rules event_frequency {}", frequency)),
+        ];
+        logs.extend(game.call(move |game_core| {
+            game_core.set_profile(profile);
+            game_core.set_hint_settings(hint_settings);
+            game_core.set_recording_turns(recording_turns);
+            let speed_command = speed
+                .filter(|speed| speed != game_core.get_speed())
+                .map(|speed| format!("speed {}", speed));
+
+            let mut logs = Vec::new();
+            for command in [speed_command].into_iter().chain(setup_commands).flatten() {
+                match game_core.execute_command(&command) {
+                    Ok(Some(message)) => logs.push(LogMessage::info(&message)),
+                    Ok(None) => {}
+                    Err(err) => logs.push(LogMessage::error(&format!("Failed to set up the game ({}): {}", command, err))),
+                }
+            }
+            logs
+        }));
+
+        let mut app = App {
+            ui: UI::new(),
+            game,
+            input_info: None,
+            keymap,
+            settings,
+            input_buffer: String::new(),
+            exit: false,
+            show_cursor: true,
+            focused_pane,
+            current_planet_idx: 0,
+            current_site_idx: 0,
+            status_section: StatusSection::Planet,
+            logs,
+            history,
+            pedia_view: None,
+            show_missions: false,
+            show_alerts: false,
+            show_thoughts: false,
+            thinking_bot,
+            hidden_choice: None,
+            unasked_commands: Vec::new(),
+            replay_view: None,
+            pause_menu: None,
+            session_timer: SessionTimer::new(1),
+            last_input: Instant::now(),
+            auto_end_turn_at: None,
+            auto_advance_at: None,
+            turn_progress: None,
+            recovery_offer: None,
+            reconcile_offer: None,
+            catch_up_offer: None,
+            catch_up: None,
+            needs_redraw: true,
+            frame_stats: FrameStats::new(),
+            running_command: None,
+            stats_export,
+            #[cfg(feature = "http-api")]
+            http_api,
+        };
+        if resume && app.settings.save.recovery_file.exists() {
+            app.offer_recovery();
+        } else if resume && app.settings.offline.enabled {
+            app.resume_offline_game();
+        }
+        Ok(app)
+    }
+
+    // Opens a recorded game for watching, nothing is saved when the viewer closes
+    pub fn new_replay(profile: Option<&str>, path: &Path) -> Result<Self, AppError> {
+        let replay_view = ReplayView::new(Replay::load(path)?)?;
+        let mut app = Self::new(profile)?;
+        let replay = replay_view.get_replay().clone();
+        let hint_settings = app.settings.hints.clone();
+        let shown_replay_view = replay_view.clone();
+        let (logs, warnings) = app.game.call(move |game_core| {
+            let (replayed_game, logs) = shown_replay_view.build_game(&hint_settings)?;
+            game_core.replace(replayed_game);
+            Ok::<_, GameCoreError>((logs, replay.get_compatibility_warnings(game_core.get_replay())))
+        })?;
+        app.logs = logs;
+        app.replay_view = Some(replay_view);
+        for warning in warnings {
+            app.add_log(LogMessage::error(&warning));
+        }
+        app.recovery_offer = None;
+        app.reconcile_offer = None;
+        app.catch_up_offer = None;
+        Ok(app)
     }
     
+    // Plays in the terminal the game was started from
+    #[cfg(feature = "terminal")]
     pub fn run(&mut self) -> Result<(), AppError> {
-        // Initialize terminal
-        let mut terminal = Self::init_terminal()?;
+        let frontend = CrosstermFrontend::new().inspect_err(|err| eprintln!("Failed to set up the terminal: {}", err))?;
+        self.run_with(frontend)
+    }
+
+    // Plays on the given frontend until the player quits, then gives it back in its first state
+    pub fn run_with(&mut self, mut frontend: impl Frontend) -> Result<(), AppError> {
+        crash_report::install_panic_hook();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.run_on(&mut frontend)));
+        let error = match result {
+            Ok(Ok(())) => None,
+            Ok(Err(err)) => Some(err),
+            Err(_) => Some(AppError::Crashed(crash_report::take_panic_message().unwrap_or_default())),
+        };
+        // A crash of the game leaves it on the worker, so it can still be saved
+        let replay = error.as_ref().map(|_| self.game.query(|game_core| game_core.get_replay().clone()));
+        // Saved before the terminal is touched again, in case restoring it fails as well
+        let recovery_save = match (&replay, &self.replay_view) {
+            (Some(replay), None) => Some(crash_report::write_recovery_save(replay, &self.settings.save.recovery_file)),
+            _ => None,
+        };
+        crash_report::remove_panic_hook();
+        let restored = frontend.restore();
+
+        // Panics and fatal errors leave a report behind, its path is printed once the terminal is
+        // usable again
+        if let (Some(error), Some(replay)) = (error, replay) {
+            if let Err(err) = restored {
+                eprintln!("Failed to restore the terminal: {}", err);
+            }
+            match recovery_save {
+                Some(Ok(())) => eprintln!(
+                    "Your game was saved to {} and will be offered to be restored on the next start.",
+                    self.settings.save.recovery_file.display(),
+                ),
+                Some(Err(err)) => eprintln!("Failed to save your game: {}", err),
+                None => {}
+            }
+            let crash_report = CrashReport {
+                reason: error.to_string(),
+                command: self.running_command.clone(),
+                turn: self.game.get_snapshot().current_turn,
+                logs: &self.logs,
+                replay: &replay,
+            };
+            match crash_report.write(&self.settings.crash_reports) {
+                Ok(directory) => eprintln!(
+                    "{}\nA crash report was written to {}, please attach it to your bug report.",
+                    error,
+                    directory.display(),
+                ),
+                Err(err) => eprintln!("{}\nFailed to write a crash report: {}", error, err),
+            }
+            return Err(error);
+        }
+
+        // Without a crash report the error is printed on its own
+        let finished = restored.map_err(AppError::from).and_then(|()| self.finish());
+        if let Err(err) = &finished {
+            eprintln!("{}", err);
+        }
+        finished
+    }
+
+    // Saves the game and the profile after the player quit
+    fn finish(&mut self) -> Result<(), AppError> {
+        if self.replay_view.is_some() {
+            return Ok(());
+        }
+        // There is no end-of-game screen yet, the statistics are left in the terminal instead
+        let defeat_report = self.game.query(|game_core| game_core.is_defeated().then(|| game_core.get_defeat_report()));
+        if let Some(defeat_report) = defeat_report {
+            println!("{}", defeat_report);
+        }
+        let replay_file = self.settings.replay.file.clone();
+        self.game.query(move |game_core| game_core.get_replay().save(&replay_file))?;
+        // Picked up again on the next start, a finished game starts over instead
+        if self.settings.offline.enabled && self.game.get_snapshot().is_running {
+            let save_file = self.settings.save.file.clone();
+            self.game.query(move |game_core| game_core.get_replay().save(&save_file))?;
+        }
+
+        // Turns ended by the last commands before quitting
+        for event in self.game.poll() {
+            if let (WorkerEvent::TurnRecords(turn_records), Some(stats_export)) = (event, &self.stats_export) {
+                stats_export.append(&turn_records)?;
+            }
+        }
+
+        let profile_file = self.settings.profile.file.clone();
+        self.game.call(move |game_core| {
+            game_core.finish_game();
+            game_core.get_profile().save(&profile_file)
+        })?;
+        Ok(())
+    }
 
+    // The first personality stands in when none is named or the named one is unknown
+    fn load_thinking_bot(personality: Option<&str>) -> Result<EconomyBot, AppError> {
+        let ai_config = AiConfig::load(GameDataPaths::get_or(&GameDataPaths::default().ai, "data/ai.toml"))
+            .map_err(GameCoreError::from)?;
+        let personality = personality
+            .and_then(|id| ai_config.find_personality(id))
+            .unwrap_or_else(|| ai_config.get_personality_for_seat(0));
+        Ok(EconomyBot::new(&ai_config, personality).with_thoughts())
+    }
+
+    // Logs what the AI would do in the player's place this turn and why
+    fn log_ai_thoughts(&mut self) {
+        let Some(mut bot) = self.thinking_bot.take() else {
+            return;
+        };
+        let (bot, thoughts) = self.game.query(move |game_core| {
+            let thoughts = bot.think_about(game_core);
+            (bot, thoughts)
+        });
+        self.thinking_bot = Some(bot);
+        for thought in thoughts {
+            self.add_log(LogMessage::thought(&thought));
+        }
+    }
+
+    // The main loop, runs until the player quits
+    fn run_on(&mut self, frontend: &mut impl Frontend) -> Result<(), AppError> {
         let mut last_blink = Instant::now();
         let blink_interval = Duration::from_millis(500);
+        let mut last_turn = self.game.get_snapshot().current_turn;
+        let mut last_draw = Instant::now();
+        self.needs_redraw = true;
+        if self.replay_view.is_none() {
+            self.log_ai_thoughts();
+        }
 
         while !self.exit {
-            if !self.game_core.is_running() {
+            // A replayed game may end with quitting, the viewer stays open anyway
+            if !self.game.get_snapshot().is_running && self.replay_view.is_none() {
                 self.exit = true;
                 break;
             }
 
-            if last_blink.elapsed() >= blink_interval {
-                self.show_cursor = !self.show_cursor;
-                last_blink = Instant::now();
+            if self.replay_view.as_mut().is_some_and(|replay_view| replay_view.tick()) {
+                self.rebuild_replay()?;
+                self.needs_redraw = true;
             }
 
-            let current_turn = self.game_core.get_current_turn();
+            for event in self.game.poll() {
+                self.handle_worker_event(event);
+            }
 
-            let player_name = self.game_core.get_current_player_name();
+            #[cfg(feature = "http-api")]
+            if let Some(http_api) = &mut self.http_api {
+                http_api.poll(self.game.get_snapshot(), &self.logs);
+            }
 
-            let planet_names = self.game_core.get_current_player_planet_names();
-            if self.current_planet_idx >= planet_names.len() {
-                self.current_planet_idx = 0;
+            // Watching a replay isn't playing
+            if self.replay_view.is_none() {
+                let turn_secs = self.session_timer.get_turn_secs();
+                self.session_timer.tick(&mut self.game, self.pause_menu.is_some());
+                self.needs_redraw |= self.session_timer.get_turn_secs() != turn_secs;
             }
-            let planet_name = &planet_names[self.current_planet_idx];
-            let planet_status = self
-                .game_core
-                .get_current_player_planet_status(planet_name);
 
+            if self.auto_end_turn_at.is_some_and(|deadline| Instant::now() >= deadline) {
+                self.auto_end_turn_at = None;
+                self.add_log(LogMessage::info("> endturn"));
+                self.turn_progress = Some(TurnProgress::new("endturn", self.game.get_snapshot().current_turn));
+            }
 
-            let command_focused = self.focused_pane == FocusedPane::CommandInput;
-            let status_focused = self.focused_pane == FocusedPane::Status;
+            self.tick_auto_advance();
 
-            terminal.draw(|f| {
-                self.ui.draw(
-                    f,
-                    &self.input_buffer,
-                    self.show_cursor && command_focused,
-                    command_focused,
-                    status_focused,
-                    current_turn,
-                    &player_name,
-                    planet_status.as_ref(),
-                    &self.logs
-                );
-            })?;
+            self.run_turn_progress();
+            // The countdown and the progress indicator change on their own
+            self.needs_redraw |= self.auto_end_turn_at.is_some() || self.turn_progress.is_some();
 
-            // TODO: Maybe poll will not be necessary, game is static most of the time
-            if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key_event) = event::read()? {
-                    // Only process key presses, not releases
-                    if key_event.kind == KeyEventKind::Press {
-                        self.handle_key_event(key_event)?;
-                    }
+            if last_blink.elapsed() >= blink_interval {
+                self.show_cursor = !self.show_cursor;
+                last_blink = Instant::now();
+                self.needs_redraw = true;
+            }
+
+            let current_turn = self.game.get_snapshot().current_turn;
+            // TODO: Signal as well when AI or network players finish their turns once there are any
+            if current_turn != last_turn {
+                last_turn = current_turn;
+                self.needs_redraw = true;
+                if self.replay_view.is_none() {
+                    self.log_ai_thoughts();
+                    self.signal_attention(frontend, &format!("Turn {} has started.", current_turn))?;
                 }
-                // TODO: handle other events like Mouse or Resize here if needed
             }
+
+            let frame_interval = Duration::from_secs_f64(1.0 / self.settings.render.max_fps.max(1) as f64);
+            let mut timeout = Duration::from_millis(self.settings.render.tick_ms);
+            if self.needs_redraw {
+                let since_draw = last_draw.elapsed();
+                if since_draw >= frame_interval {
+                    let frame_start = Instant::now();
+                    self.draw(frontend, current_turn)?;
+                    self.frame_stats.record_frame(frame_start.elapsed());
+                    self.needs_redraw = false;
+                    last_draw = Instant::now();
+                } else {
+                    timeout = timeout.min(frame_interval - since_draw);
+                }
+            }
+
+            match frontend.poll_event(timeout)? {
+                Some(InputEvent::Key(key_event)) => {
+                    self.last_input = Instant::now();
+                    self.handle_key_event(key_event)?;
+                }
+                Some(InputEvent::Paste(text)) => {
+                    self.last_input = Instant::now();
+                    self.handle_paste(&text);
+                }
+                Some(InputEvent::Resize) => {}
+                None => continue,
+            }
+            self.needs_redraw = true;
         }
 
-        // Restore terminal
-        Self::restore(&mut terminal)?;
         Ok(())
     }
 
-    fn init_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>, AppError> {
-        enable_raw_mode()?;
-        let mut stdout = std::io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        Terminal::new(backend).map_err(AppError::Io)
+    // Gathers everything shown from the game, only called when something changed
+    fn draw(&mut self, frontend: &mut impl Frontend, current_turn: u32) -> Result<(), AppError> {
+        // Waiting for the game is only fine while it has nothing else to do, until then the
+        // command line keeps what was worked out before
+        let input_info_outdated = self.input_info
+            .as_ref()
+            .is_none_or(|input_info| !input_info.is_current(&self.input_buffer, self.game.get_snapshot()));
+        if input_info_outdated && !self.game.is_busy() {
+            let input = self.input_buffer.clone();
+            let version = self.game.get_snapshot().version;
+            self.input_info = Some(self.game.query(move |game_core| InputInfo::new(game_core, input, version)));
+        }
+
+        let snapshot = self.game.get_snapshot();
+        if self.current_planet_idx >= snapshot.planet_names.len() {
+            self.current_planet_idx = 0;
+        }
+        let planet_name = &snapshot.planet_names[self.current_planet_idx];
+        let planet_status = snapshot.get_planet_status(planet_name);
+        let site_count = planet_status.as_ref().map_or(1, |status| status.sub_locations.len() + 1);
+        if self.current_site_idx >= site_count {
+            self.current_site_idx = 0;
+        }
+        // Rows may be gone after a planet switch or a finished construction
+        self.status_section = self.clamp_status_section(self.status_section);
+
+        // Decisions in a replay were already made
+        let choice = self.get_shown_choice().filter(|_| self.replay_view.is_none());
+
+        let offer_prompt = self.get_offer_prompt();
+        let command_preview = match (offer_prompt, &self.turn_progress, self.auto_end_turn_at) {
+            (Some(offer_prompt), _, _) => Some(offer_prompt),
+            (None, Some(turn_progress), _) => Some(turn_progress.get_label(current_turn)),
+            (None, None, Some(deadline)) => Some(format!(
+                "Ending the turn in {}s, press any key to cancel",
+                deadline.saturating_duration_since(Instant::now()).as_secs() + 1,
+            )),
+            (None, None, None) => self.input_info.as_ref().and_then(|input_info| input_info.preview.clone()),
+        };
+        let command_focused = self.focused_pane == FocusedPane::CommandInput;
+        let arg_hints = self.input_info.as_ref().and_then(|input_info| input_info.arg_hints.as_deref());
+        // The title is taken while any of these is shown
+        let offered = self.recovery_offer.is_some() || self.reconcile_offer.is_some() || self.catch_up_offer.is_some();
+        let input_check = match (offered, &self.turn_progress, self.auto_end_turn_at) {
+            (false, None, None) => self.input_info.as_ref().and_then(|input_info| input_info.check.as_ref()),
+            _ => None,
+        };
+        let status_focused = self.focused_pane == FocusedPane::Status;
+        let snapshot = self.game.get_snapshot();
+
+        let ui_state = UiState {
+            command_input: &self.input_buffer,
+            command_preview: command_preview.as_deref(),
+            input_check,
+            arg_hints,
+            show_cursor: self.show_cursor && command_focused,
+            command_input_focused: command_focused,
+            status_focused,
+            current_turn,
+            player_name: &snapshot.player_name,
+            player_color: snapshot.player_color,
+            credits: snapshot.credits,
+            credits_income: snapshot.credits_income,
+            artifacts: &snapshot.artifacts,
+            planet_status: planet_status.as_deref(),
+            selected_site: self.current_site_idx,
+            status_section: self.status_section,
+            logs: &self.logs,
+            // Hotkeys running commands are disabled while watching a replay
+            key_hints: if self.replay_view.is_some() {
+                &[]
+            } else if self.is_normal_mode() {
+                self.keymap.get_normal_hints()
+            } else {
+                self.keymap.get_hints()
+            },
+            tutorial_status: snapshot.tutorial_status.as_ref(),
+            missions: self.show_missions.then_some(snapshot.missions.as_slice()),
+            alerts: &snapshot.alerts,
+            show_alerts: self.show_alerts,
+            show_thoughts: self.show_thoughts,
+            colonypedia: self.pedia_view.as_ref().map(|view| PediaState {
+                entries: snapshot.colonypedia.get_entries(),
+                selected: view.get_selected(),
+            }),
+            choice: choice.as_ref(),
+            pause_menu: self.pause_menu.as_ref().map(PauseMenu::get_selected),
+            playtime: self.replay_view.is_none().then(|| PlaytimeState {
+                session_secs: self.session_timer.get_session_secs(),
+                total_secs: snapshot.playtime_secs,
+                turn_secs: self.session_timer.get_turn_secs(),
+            }),
+            debug_overlay: self.settings.render.debug_overlay.then(|| self.frame_stats.get_label()),
+            replay: self.replay_view.as_ref().map(|replay_view| ReplayState {
+                turn: replay_view.get_turn(),
+                last_turn: replay_view.get_last_turn(),
+                playing: replay_view.is_playing(),
+            }),
+        };
+
+        frontend.draw(|f| self.ui.draw(f, &ui_state))?;
+        if let Some(turn_progress) = &mut self.turn_progress {
+            turn_progress.set_shown();
+        }
+        Ok(())
     }
 
-    fn restore(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<(), AppError> {
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
+    // Only while the player is away, someone at the keyboard sees the game anyway
+    fn signal_attention(&self, frontend: &mut impl Frontend, message: &str) -> Result<(), AppError> {
+        let settings = &self.settings.attention;
+        if self.last_input.elapsed() < Duration::from_secs(settings.idle_secs) {
+            return Ok(());
+        }
 
+        if settings.bell {
+            frontend.ring_bell()?;
+        }
+        if settings.desktop_notification {
+            frontend.notify(&format!("TerminalColony: {}", message))?;
+        }
         Ok(())
     }
 
     fn add_log(&mut self, message: LogMessage) {
-        const MAX_LOGS: usize = 100; // TODO: Make this configurable
+        self.needs_redraw = true;
         if self.logs.len() >= MAX_LOGS {
             self.logs.remove(0);
         }
         self.logs.push(message);
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), AppError> {
-        match key_event.code {
+    fn rebuild_replay(&mut self) -> Result<(), AppError> {
+        let Some(replay_view) = &self.replay_view else {
+            return Ok(());
+        };
+
+        let replay_view = replay_view.clone();
+        let hint_settings = self.settings.hints.clone();
+        let mut logs = self.game.call(move |game_core| {
+            let (replayed_game, logs) = replay_view.build_game(&hint_settings)?;
+            game_core.replace(replayed_game);
+            Ok::<_, GameCoreError>(logs)
+        })?;
+        logs.drain(..logs.len().saturating_sub(MAX_LOGS));
+        self.logs = logs;
+        self.hidden_choice = None;
+        Ok(())
+    }
+
+    fn handle_replay_key_event(&mut self, key_event: KeyEvent) -> Result<(), AppError> {
+        let Some(replay_view) = &mut self.replay_view else {
+            return Ok(());
+        };
+
+        let changed = match key_event.code {
+            KeyCode::Char(' ') => replay_view.toggle_playing(),
+            KeyCode::Right => replay_view.step_forward(),
+            KeyCode::Left => replay_view.step_back(),
+            KeyCode::Home => replay_view.seek(1),
+            KeyCode::End => replay_view.seek_end(),
             KeyCode::Up => {
-                if self.focused_pane == FocusedPane::CommandInput {
-                   self.focused_pane = FocusedPane::Status;
-                }
+                let planet_count = self.game.get_snapshot().planet_count.max(1);
+                self.current_planet_idx = (self.current_planet_idx + 1) % planet_count;
+                self.current_site_idx = 0;
+                false
             }
             KeyCode::Down => {
-                if self.focused_pane == FocusedPane::Status {
-                    self.focused_pane = FocusedPane::CommandInput;
+                self.current_site_idx = (self.current_site_idx + 1) % self.get_current_site_count();
+                false
+            }
+            KeyCode::Esc => {
+                self.exit = true;
+                false
+            }
+            _ => false,
+        };
+
+        if changed {
+            self.rebuild_replay()?;
+        }
+        Ok(())
+    }
+
+    fn submit_command(&mut self, input: &str) {
+        let command = match self.history.expand(input) {
+            Ok(command) => command,
+            Err(err) => {
+                self.add_log(LogMessage::error(&err));
+                return;
+            }
+        };
+        if command != input {
+            self.add_log(LogMessage::info(&format!("> {}", command)));
+        }
+        self.history.push(&command);
+        // Saved right away, so a crash or Ctrl+C doesn't lose it
+        if let Err(err) = self.history.save(&self.settings.history.file) {
+            self.add_log(LogMessage::error(&format!("Failed to save command history: {}", err)));
+        }
+
+        let turn = self.game.get_snapshot().current_turn;
+        // Several commands can be batched on one line, separated by semicolons
+        let parts: Vec<String> = command
+            .split(';')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(str::to_string)
+            .collect();
+        let checked_parts = parts.clone();
+        let ends_turn = self.game.query(move |game_core| {
+            checked_parts.iter().any(|part| game_core.get_turns_ended_by(part) > 0)
+        });
+        if ends_turn {
+            self.turn_progress = Some(TurnProgress::new(&command, turn));
+            return;
+        }
+        for part in parts {
+            self.execute_command(&part);
+        }
+        self.schedule_auto_end_turn();
+    }
+
+    // Runs the commands of a line ending the turn on the worker one after another, the interface
+    // keeps drawing meanwhile
+    fn run_turn_progress(&mut self) {
+        if self.game.is_busy() {
+            return;
+        }
+        let Some(turn_progress) = &mut self.turn_progress else {
+            return;
+        };
+        if let Some(command) = turn_progress.take_next_command() {
+            self.running_command = Some(command.clone());
+            self.game.execute(&command);
+            return;
+        }
+
+        let Some(turn_progress) = self.turn_progress.take_if(|turn_progress| turn_progress.is_finished()) else {
+            return;
+        };
+        if let Some(catch_up) = self.catch_up.take() {
+            self.add_log(LogMessage::success(&catch_up.get_report(self.game.get_snapshot())));
+            return;
+        }
+        // A command that ended the turn leaves the new one to the player
+        if self.game.get_snapshot().current_turn == turn_progress.get_start_turn() {
+            self.schedule_auto_end_turn();
+        }
+    }
+
+    fn handle_worker_event(&mut self, event: WorkerEvent) {
+        match event {
+            WorkerEvent::Executed(outcome) => {
+                self.running_command = None;
+                self.log_command_outcome(outcome);
+                if self.is_confirmation_unasked() {
+                    self.add_log(LogMessage::info("> confirm"));
+                    self.running_command = Some("confirm".to_string());
+                    self.game.execute("confirm");
                 }
             }
-            KeyCode::Left => {
-                if self.focused_pane == FocusedPane::Status {
-                    self.current_planet_idx = (self.current_planet_idx + 1) % self.game_core.get_planet_count();
+            WorkerEvent::Notifications(notifications) => {
+                for notification in notifications {
+                    self.add_log(LogMessage::info(&notification));
                 }
             }
-            KeyCode::Right => {
-                if self.focused_pane == FocusedPane::Status {
-                    self.current_planet_idx = (
-                        self.current_planet_idx + self.game_core.get_planet_count() - 1
-                    ) % self.game_core.get_planet_count();
+            WorkerEvent::TurnRecords(turn_records) => {
+                if let Some(stats_export) = &self.stats_export
+                    && let Err(err) = stats_export.append(&turn_records)
+                {
+                    self.add_log(LogMessage::error(&format!("Failed to export statistics: {}", err)));
                 }
             }
-            KeyCode::Tab => {
-                if self.focused_pane == FocusedPane::CommandInput {
-                    self.focused_pane = FocusedPane::Status;
-                } else {
-                    self.focused_pane = FocusedPane::CommandInput;
+        }
+    }
+
+    // Ends the turn on a real-time interval, waiting while the game is paused or a decision is open
+    fn tick_auto_advance(&mut self) {
+        let settings = &self.settings.auto_advance;
+        if !settings.enabled || self.replay_view.is_some() {
+            return;
+        }
+        let interval = Duration::from_secs(settings.interval_secs.max(1));
+        let Some(deadline) = self.auto_advance_at else {
+            self.auto_advance_at = Some(Instant::now() + interval);
+            return;
+        };
+
+        let blocked = self.pause_menu.is_some()
+            || self.turn_progress.is_some()
+            || self.recovery_offer.is_some()
+            || self.reconcile_offer.is_some()
+            || self.catch_up_offer.is_some()
+            || self.get_shown_choice().is_some();
+        if Instant::now() < deadline || blocked {
+            return;
+        }
+        self.auto_advance_at = Some(Instant::now() + interval);
+        self.add_log(LogMessage::info("> endturn"));
+        self.turn_progress = Some(TurnProgress::new("endturn", self.game.get_snapshot().current_turn));
+    }
+
+    fn schedule_auto_end_turn(&mut self) {
+        let settings = &self.settings.auto_end_turn;
+        if !settings.enabled
+            || !self.game.get_snapshot().is_running
+            || self.game.query(|game_core| game_core.has_available_actions())
+        {
+            return;
+        }
+
+        self.auto_end_turn_at = Some(Instant::now() + Duration::from_secs(settings.countdown_secs));
+        let message = format!(
+            "Nothing left to do this turn, ending it in {}s. Press any key to cancel.",
+            settings.countdown_secs,
+        );
+        self.add_log(LogMessage::info(&message));
+    }
+
+    fn execute_command(&mut self, command: &str) {
+        self.run_game_command(command);
+
+        // Through the game, so the replay holds the confirmation like any other
+        if self.is_confirmation_unasked() {
+            self.add_log(LogMessage::info("> confirm"));
+            self.run_game_command("confirm");
+        }
+    }
+
+    // Whether the command waiting for confirmation is one the player chose not to be asked about
+    fn is_confirmation_unasked(&self) -> bool {
+        self.game
+            .get_snapshot()
+            .confirmation_command
+            .as_ref()
+            .is_some_and(|confirmed| self.unasked_commands.contains(confirmed))
+    }
+
+    fn run_game_command(&mut self, command: &str) {
+        self.running_command = Some(command.to_string());
+        let command = command.to_string();
+        let outcome = self.game.call(move |game_core| CommandOutcome::run(game_core, &command));
+        self.running_command = None;
+        self.log_command_outcome(outcome);
+    }
+
+    fn log_command_outcome(&mut self, outcome: CommandOutcome) {
+        match outcome.result {
+            Ok(Some(help)) if outcome.is_help => {
+                self.add_log(LogMessage::help(&help));
+            }
+            Ok(Some(success_msg)) => {
+                self.add_log(LogMessage::success(&success_msg));
+            }
+            Ok(None) => {
+                self.add_log(LogMessage::success("Command executed successfully."));
+            }
+            Err(err) => {
+                self.add_log(LogMessage::error(&err.to_string()));
+            }
+        }
+    }
+
+    // Every entry closes the menu, Help by opening the Colonypedia in its place
+    fn run_pause_action(&mut self, action: PauseAction) {
+        self.pause_menu = None;
+        match action {
+            PauseAction::Resume => {}
+            PauseAction::Save => {
+                let file = self.settings.save.file.clone();
+                let saved_file = file.clone();
+                let message = match self.game.query(move |game_core| game_core.get_replay().save(&saved_file)) {
+                    Ok(()) => LogMessage::success(&format!("Game saved to {}.", file.display())),
+                    Err(err) => LogMessage::error(&format!("Failed to save the game: {}", err)),
+                };
+                self.add_log(message);
+            }
+            PauseAction::Load => {
+                if let Err(err) = self.load_game() {
+                    self.add_log(LogMessage::error(&format!("Failed to load the game: {}", err)));
                 }
             }
-            KeyCode::Esc => {
+            // TODO: Edit the settings in game, they are only read on startup so far
+            PauseAction::Settings => {
+                self.add_log(LogMessage::info("Settings are read from data/settings.toml when the game starts."));
+            }
+            PauseAction::Help => self.pedia_view = Some(PediaView::new()),
+            PauseAction::Quit => {
                 self.add_log(LogMessage::info("Quit requested."));
                 self.exit = true;
             }
-            KeyCode::Enter => {
-                if self.focused_pane == FocusedPane::CommandInput {
-                    let input = self.input_buffer.trim().to_string();
-                    if !input.is_empty() {
-                        match self.game_core.execute_command(&input) {
-                            Ok(Some(success_msg)) => {
-                                self.add_log(LogMessage::success(&success_msg));
-                            }
-                            Ok(None) => {
-                                self.add_log(LogMessage::success("Command executed successfully."));
-                            }
-                            Err(err) => {
-                                self.add_log(LogMessage::error(&err.to_string()));
-                            }
+        }
+    }
+
+    // Loads the game saved when quitting and offers to simulate the turns missed since
+    fn resume_offline_game(&mut self) {
+        if !self.settings.save.file.exists() {
+            return;
+        }
+        let replay = match self.load_game() {
+            Ok(replay) => replay,
+            Err(err) => {
+                self.add_log(LogMessage::error(&format!("Failed to load the offline game: {}", err)));
+                return;
+            }
+        };
+        let Some(away_secs) = replay.get_secs_since_saved() else {
+            return;
+        };
+
+        let settings = &self.settings.offline;
+        let turns = (away_secs / settings.secs_per_turn.max(1)).min(settings.max_turns as u64) as u32;
+        self.add_log(LogMessage::info(&format!(
+            "You were away for {}, worth {} turn(s) (at most {}).",
+            format_playtime(away_secs),
+            turns,
+            settings.max_turns,
+        )));
+        if turns > 0 {
+            self.catch_up_offer = Some(CatchUp::new(self.game.get_snapshot(), turns));
+        }
+    }
+
+    // Checks the game saved by the last crash can be read before offering it
+    fn offer_recovery(&mut self) {
+        match Replay::load(&self.settings.save.recovery_file) {
+            Ok(replay) => {
+                self.recovery_offer = Some(replay.get_entries().last().map_or(1, |entry| entry.turn));
+            }
+            Err(err) => self.add_log(LogMessage::error(&format!("Failed to read the game saved by the last crash: {}", err))),
+        }
+    }
+
+    // The file is kept when restoring fails, so it can still be loaded by hand
+    fn answer_recovery_offer(&mut self, accepted: bool) {
+        if self.recovery_offer.take().is_none() {
+            return;
+        }
+        let file = self.settings.save.recovery_file.clone();
+        if accepted {
+            if let Err(err) = self.load_game_from(&file) {
+                self.add_log(LogMessage::error(&format!("Failed to restore the game: {}", err)));
+                return;
+            }
+        } else {
+            self.add_log(LogMessage::info("The game saved by the last crash was discarded."));
+        }
+        if let Err(err) = std::fs::remove_file(&file) {
+            self.add_log(LogMessage::error(&format!("Failed to remove {}: {}", file.display(), err)));
+        }
+    }
+
+    fn answer_reconcile_offer(&mut self, accepted: bool) {
+        let Some((replay, _)) = self.reconcile_offer.take() else {
+            return;
+        };
+        if !accepted {
+            self.add_log(LogMessage::info("The game was kept as loaded."));
+            return;
+        }
+        // Restoring drops the offers, catching up still applies to the reconciled colony
+        let catch_up_turns = self.catch_up_offer.take().map(|catch_up| catch_up.get_turns());
+        match self.restore_game(&replay, true) {
+            Ok(()) => {
+                let failed = replay.get_entries().iter().filter(|entry| entry.failed).count();
+                self.add_log(LogMessage::success(&format!(
+                    "Game reconciled, the {} command(s) that failed when they were played were left out.",
+                    failed,
+                )));
+            }
+            Err(err) => self.add_log(LogMessage::error(&format!("Failed to reconcile the game: {}", err))),
+        }
+        self.catch_up_offer = catch_up_turns.map(|turns| CatchUp::new(self.game.get_snapshot(), turns));
+    }
+
+    // Only one offer is shown at a time, in the order they are answered
+    fn get_offer_prompt(&self) -> Option<String> {
+        if let Some(turn) = self.recovery_offer {
+            return Some(format!(
+                "The game crashed last time, restore it from turn {}? Y/Enter to restore, N/Esc to discard",
+                turn,
+            ));
+        }
+        if let Some((_, desynced)) = &self.reconcile_offer {
+            return Some(format!(
+                "{} command(s) turned out differently with the current configs. Y/Enter to replay only those that worked back then, N/Esc to keep the game",
+                desynced,
+            ));
+        }
+        self.catch_up_offer.as_ref().map(CatchUp::get_prompt)
+    }
+
+    fn answer_catch_up_offer(&mut self, accepted: bool) {
+        let Some(catch_up) = self.catch_up_offer.take() else {
+            return;
+        };
+        if !accepted {
+            self.add_log(LogMessage::info("Catch-up skipped."));
+            return;
+        }
+        self.add_log(LogMessage::info("Catching up on the time away..."));
+        self.turn_progress = Some(TurnProgress::new(&catch_up.get_command(), self.game.get_snapshot().current_turn));
+        self.catch_up = Some(catch_up);
+    }
+
+    fn load_game(&mut self) -> Result<Replay, AppError> {
+        let file = self.settings.save.file.clone();
+        self.load_game_from(&file)
+    }
+
+    // Replaces the running game with the saved one, the profile carries over
+    fn load_game_from(&mut self, file: &Path) -> Result<Replay, AppError> {
+        let replay = Replay::load(file)?;
+        self.restore_game(&replay, false)?;
+        self.add_log(LogMessage::success(&format!("Game loaded from {}.", file.display())));
+
+        // Saves from other versions or configs are loaded anyway, with reconciling on offer once
+        // their commands turned out differently
+        let loaded_replay = replay.clone();
+        let (warnings, desynced) = self.game.query(move |game_core| {
+            let played_replay = game_core.get_replay();
+            (loaded_replay.get_compatibility_warnings(played_replay), loaded_replay.count_desynced(played_replay))
+        });
+        for warning in warnings {
+            self.add_log(LogMessage::error(&warning));
+        }
+        if desynced > 0 {
+            self.reconcile_offer = Some((replay.clone(), desynced));
+        }
+        Ok(replay)
+    }
+
+    fn restore_game(&mut self, replay: &Replay, reconcile: bool) -> Result<(), AppError> {
+        let replay = replay.clone();
+        let hint_settings = self.settings.hints.clone();
+        let recording_turns = self.stats_export.is_some();
+        let mut logs = self.game.call(move |game_core| {
+            let (mut restored_game, logs) = replay_view::rebuild_game(
+                replay.get_entries().iter(),
+                replay.get_name_seed(),
+                &hint_settings,
+                reconcile,
+            )?;
+            restored_game.set_profile(game_core.get_profile().clone());
+            restored_game.add_playtime(replay.get_playtime_secs());
+            // Turns before the save were exported while they were played
+            restored_game.set_recording_turns(recording_turns);
+            game_core.replace(restored_game);
+            Ok::<_, GameCoreError>(logs)
+        })?;
+        self.session_timer.restart_turn(self.game.get_snapshot().current_turn);
+
+        logs.drain(..logs.len().saturating_sub(MAX_LOGS - 1));
+        self.logs = logs;
+        self.current_planet_idx = 0;
+        self.current_site_idx = 0;
+        self.hidden_choice = None;
+        self.auto_end_turn_at = None;
+        self.reconcile_offer = None;
+        self.catch_up_offer = None;
+        Ok(())
+    }
+
+    fn run_key_binding(&mut self, binding: KeyBinding) {
+        match binding {
+            KeyBinding::Command(command) => self.submit_command(&command),
+            KeyBinding::Action(KeyAction::Colonypedia) => {
+                self.pedia_view = match self.pedia_view {
+                    Some(_) => None,
+                    None => Some(PediaView::new()),
+                };
+            }
+            KeyBinding::Action(KeyAction::Missions) => self.show_missions = !self.show_missions,
+            KeyBinding::Action(KeyAction::Alerts) => self.show_alerts = !self.show_alerts,
+            KeyBinding::Action(KeyAction::AiThoughts) => {
+                self.show_thoughts = !self.show_thoughts;
+                if self.show_thoughts && self.thinking_bot.is_none() {
+                    self.add_log(LogMessage::info("AI thoughts are off, enable them under [ai_thoughts] in data/settings.toml."));
+                }
+            }
+            KeyBinding::Action(KeyAction::PreviousPlanet) => self.select_previous_planet(),
+            KeyBinding::Action(KeyAction::NextPlanet) => self.select_next_planet(),
+            KeyBinding::Action(KeyAction::PreviousSite) => self.select_previous_site(),
+            KeyBinding::Action(KeyAction::NextSite) => self.select_next_site(),
+            KeyBinding::Action(KeyAction::InsertMode) => self.focused_pane = FocusedPane::CommandInput,
+            KeyBinding::Action(KeyAction::ComparePlanets) => {
+                let planet_names = &self.game.get_snapshot().planet_names;
+                if planet_names.len() < 2 {
+                    self.add_log(LogMessage::error("There is no other planet to compare with."));
+                    return;
+                }
+                let first = &planet_names[self.current_planet_idx % planet_names.len()];
+                let second = &planet_names[(self.current_planet_idx + 1) % planet_names.len()];
+                self.submit_command(&format!("compare {} {}", first, second));
+            }
+        }
+    }
+
+    // In vim mode the status pane having the focus is normal mode, the command input insert mode
+    fn is_normal_mode(&self) -> bool {
+        self.keymap.is_vim_mode() && self.focused_pane == FocusedPane::Status
+    }
+
+    // Shows the planet on its surface in the status pane
+    fn select_planet(&mut self, planet_name: &str) {
+        let planet_names = &self.game.get_snapshot().planet_names;
+        if let Some(idx) = planet_names.iter().position(|name| name == planet_name) {
+            self.current_planet_idx = idx;
+            self.current_site_idx = 0;
+            self.status_section = StatusSection::Planet;
+        }
+    }
+
+    fn select_next_planet(&mut self) {
+        self.current_planet_idx = (self.current_planet_idx + 1) % self.game.get_snapshot().planet_count.max(1);
+        self.current_site_idx = 0;
+    }
+
+    fn select_previous_planet(&mut self) {
+        let planet_count = self.game.get_snapshot().planet_count.max(1);
+        self.current_planet_idx = (self.current_planet_idx + planet_count - 1) % planet_count;
+        self.current_site_idx = 0;
+    }
+
+    fn select_next_site(&mut self) {
+        self.current_site_idx = (self.current_site_idx + 1) % self.get_current_site_count();
+    }
+
+    fn select_previous_site(&mut self) {
+        let site_count = self.get_current_site_count();
+        self.current_site_idx = (self.current_site_idx + site_count - 1) % site_count;
+    }
+
+    // The pending decision, unless the player put it off
+    fn get_shown_choice(&self) -> Option<Choice> {
+        let snapshot = self.game.get_snapshot();
+        let mut choice = snapshot.pending_choice
+            .clone()
+            .filter(|choice| self.hidden_choice.as_ref() != Some(&choice.id))?;
+        if snapshot.confirmation_command.is_some() {
+            choice.options.push(ChoiceOption::new(DONT_ASK_AGAIN, "confirm"));
+        }
+        Some(choice)
+    }
+
+    fn get_current_planet_status(&self) -> Option<Arc<PlanetStatus>> {
+        let snapshot = self.game.get_snapshot();
+        snapshot.planet_names
+            .get(self.current_planet_idx)
+            .and_then(|planet_name| snapshot.get_planet_status(planet_name))
+    }
+
+    // The surface plus the sub-locations of the planet shown in the status pane
+    fn get_current_site_count(&self) -> usize {
+        self.get_current_planet_status().map_or(1, |status| status.sub_locations.len() + 1)
+    }
+
+    // Buildings of the selected site followed by the planet's construction queue
+    fn get_building_row_count(&self) -> usize {
+        self.get_current_planet_status().map_or(0, |status| {
+            let buildings = match self.current_site_idx.checked_sub(1) {
+                Some(idx) => status.sub_locations.get(idx).map_or(0, |sub_location| sub_location.buildings.len()),
+                None => status.buildings.len(),
+            };
+            buildings + status.construction_queue.len()
+        })
+    }
+
+    // Moves the construction selected in the status pane one position up or down its queue. Goes
+    // through the queue command so replays keep the order.
+    fn move_selected_construction(&mut self, down: bool) {
+        let (StatusSection::Building(row), Some(status)) = (self.status_section, self.get_current_planet_status()) else {
+            return;
+        };
+        let buildings = match self.current_site_idx.checked_sub(1) {
+            Some(idx) => status.sub_locations.get(idx).map_or(0, |sub_location| sub_location.buildings.len()),
+            None => status.buildings.len(),
+        };
+        let Some(idx) = row.checked_sub(buildings).filter(|idx| *idx < status.construction_queue.len()) else {
+            return;
+        };
+        let new_idx = if down { idx + 1 } else { idx.wrapping_sub(1) };
+        if new_idx >= status.construction_queue.len() {
+            return;
+        }
+
+        let construction = status.construction_queue[idx].clone();
+        self.submit_command(&format!("queue move {} {} {}", status.planet_name, idx + 1, new_idx + 1));
+        // The selection follows the construction if it moved
+        let has_moved = self.get_current_planet_status()
+            .is_some_and(|status| status.construction_queue.get(new_idx) == Some(&construction));
+        if has_moved {
+            self.status_section = StatusSection::Building(buildings + new_idx);
+        }
+    }
+
+    // The sections of the status pane from top to bottom, wrapping around at the ends
+    fn move_status_section(&mut self, down: bool) {
+        let mut sections = vec![StatusSection::Planet];
+        if self.get_current_site_count() > 1 {
+            sections.push(StatusSection::Site);
+        }
+        sections.extend((0..self.get_building_row_count()).map(StatusSection::Building));
+        sections.extend((0..RESOURCE_ROWS).map(StatusSection::Resource));
+
+        let idx = sections.iter().position(|section| *section == self.status_section).unwrap_or(0);
+        let idx = if down { (idx + 1) % sections.len() } else { (idx + sections.len() - 1) % sections.len() };
+        self.status_section = sections[idx];
+    }
+
+    fn clamp_status_section(&self, section: StatusSection) -> StatusSection {
+        match section {
+            StatusSection::Site if self.get_current_site_count() <= 1 => StatusSection::Planet,
+            StatusSection::Building(row) => match self.get_building_row_count() {
+                0 => StatusSection::Planet,
+                row_count => StatusSection::Building(row.min(row_count - 1)),
+            },
+            section => section,
+        }
+    }
+
+    fn handle_paste(&mut self, text: &str) {
+        if self.focused_pane != FocusedPane::CommandInput {
+            return;
+        }
+
+        // Multi-line pastes become a semicolon batch so the lines run in order on Enter
+        let lines: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        if lines.len() > 1 && !self.input_buffer.trim().is_empty() {
+            self.input_buffer.push_str("; ");
+        }
+
+        self.history.reset_recall();
+        self.input_buffer.push_str(&lines.join("; "));
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<(), AppError> {
+        if self.replay_view.is_some() {
+            return self.handle_replay_key_event(key_event);
+        }
+
+        if self.turn_progress.is_some() {
+            return Ok(());
+        }
+
+        // The key still does its usual job, so typing the next command isn't lost, except Esc
+        if self.auto_end_turn_at.take().is_some() {
+            self.add_log(LogMessage::info("Auto end-turn cancelled."));
+            if key_event.code == KeyCode::Esc {
+                return Ok(());
+            }
+        }
+
+        if self.recovery_offer.is_some() {
+            match key_event.code {
+                KeyCode::Char('y' | 'Y') | KeyCode::Enter => self.answer_recovery_offer(true),
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => self.answer_recovery_offer(false),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.reconcile_offer.is_some() {
+            match key_event.code {
+                KeyCode::Char('y' | 'Y') | KeyCode::Enter => self.answer_reconcile_offer(true),
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => self.answer_reconcile_offer(false),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.catch_up_offer.is_some() {
+            match key_event.code {
+                KeyCode::Char('y' | 'Y') | KeyCode::Enter => self.answer_catch_up_offer(true),
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => self.answer_catch_up_offer(false),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // The pause menu blocks everything else, hotkeys included
+        if let Some(pause_menu) = &mut self.pause_menu {
+            match key_event.code {
+                KeyCode::Up => pause_menu.select_previous(),
+                KeyCode::Down => pause_menu.select_next(),
+                KeyCode::Enter => {
+                    let action = pause_menu.get_selected_action();
+                    self.run_pause_action(action);
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    if let Some(action) = PauseAction::from_number(c.to_digit(10).unwrap_or(0) as usize) {
+                        self.run_pause_action(action);
+                    }
+                }
+                KeyCode::Esc => self.pause_menu = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // Hotkeys take precedence over typing and work regardless of the focused pane, the keymap
+        // keeps them off keys that type
+        if let Some(binding) = self.keymap.get_binding(&key_event).cloned() {
+            self.run_key_binding(binding);
+            return Ok(());
+        }
+
+        // An open dialog takes all other keys until it is answered or put off
+        if let Some(choice) = self.get_shown_choice() {
+            match key_event.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    let idx = c.to_digit(10).unwrap_or(0) as usize;
+                    if let Some(option) = idx.checked_sub(1).and_then(|idx| choice.options.get(idx)) {
+                        if option.label == DONT_ASK_AGAIN
+                            && let Some(confirmed) = self.game.get_snapshot().confirmation_command.clone()
+                        {
+                            self.unasked_commands.push(confirmed);
                         }
+                        self.add_log(LogMessage::info(&format!("> {}", option.command)));
+                        self.execute_command(&option.command);
+                        self.schedule_auto_end_turn();
+                    }
+                }
+                KeyCode::Esc => self.hidden_choice = Some(choice.id),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if let Some(pedia_view) = &mut self.pedia_view {
+            let colonypedia = &self.game.get_snapshot().colonypedia;
+            match key_event.code {
+                KeyCode::Up => pedia_view.select_previous(colonypedia),
+                KeyCode::Down => pedia_view.select_next(colonypedia),
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    pedia_view.follow_link(colonypedia, c.to_digit(10).unwrap_or(0) as usize);
+                }
+                KeyCode::Backspace => pedia_view.go_back(),
+                KeyCode::Esc => self.pedia_view = None,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.show_alerts {
+            match key_event.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    let idx = c.to_digit(10).unwrap_or(0) as usize;
+                    let alerts = &self.game.get_snapshot().alerts;
+                    if let Some(planet) = idx.checked_sub(1).and_then(|idx| alerts.get(idx)).map(|alert| alert.planet.clone()) {
+                        self.select_planet(&planet);
+                        self.show_alerts = false;
                     }
-                    self.input_buffer.clear(); // Clear buffer after processing
+                }
+                KeyCode::Esc => self.show_alerts = false,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.is_normal_mode()
+            && let Some(binding) = self.keymap.get_normal_binding(&key_event).cloned()
+        {
+            self.run_key_binding(binding);
+            return Ok(());
+        }
+
+        match key_event.code {
+            // Past the oldest entry, or without any, Up moves the focus to the status pane
+            KeyCode::Up if self.focused_pane == FocusedPane::CommandInput => {
+                match self.history.recall_previous(&self.input_buffer) {
+                    Some(entry) => self.input_buffer = entry.to_string(),
+                    None => self.focused_pane = FocusedPane::Status,
+                }
+            }
+            KeyCode::Down if self.focused_pane == FocusedPane::CommandInput => {
+                if let Some(entry) = self.history.recall_next() {
+                    self.input_buffer = entry.to_string();
                 }
             }
-            KeyCode::Char(c) => {
+            // Shift with Up or Down moves the selected construction in its planet's queue
+            KeyCode::Up if self.focused_pane == FocusedPane::Status && key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.move_selected_construction(false);
+            }
+            KeyCode::Down if self.focused_pane == FocusedPane::Status && key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.move_selected_construction(true);
+            }
+            // Up and Down move through the sections of the status pane, Left and Right pick the
+            // surface or a sub-location on the site selector and the planet anywhere else
+            KeyCode::Up if self.focused_pane == FocusedPane::Status => self.move_status_section(false),
+            KeyCode::Down if self.focused_pane == FocusedPane::Status => self.move_status_section(true),
+            KeyCode::Left if self.focused_pane == FocusedPane::Status => match self.status_section {
+                StatusSection::Site => self.select_previous_site(),
+                _ => self.select_next_planet(),
+            },
+            KeyCode::Right if self.focused_pane == FocusedPane::Status => match self.status_section {
+                StatusSection::Site => self.select_next_site(),
+                _ => self.select_previous_planet(),
+            },
+            KeyCode::Tab => {
                 if self.focused_pane == FocusedPane::CommandInput {
-                    self.input_buffer.push(c);
+                    self.focused_pane = FocusedPane::Status;
+                } else {
+                    self.focused_pane = FocusedPane::CommandInput;
                 }
             }
-            KeyCode::Backspace => {
-                if self.focused_pane == FocusedPane::CommandInput && !self.input_buffer.is_empty() {
-                    self.input_buffer.pop();
+            KeyCode::Esc if self.show_missions => self.show_missions = false,
+            // Back to normal mode, Esc in normal mode opens the pause menu
+            KeyCode::Esc if self.keymap.is_vim_mode() && self.focused_pane == FocusedPane::CommandInput => {
+                self.focused_pane = FocusedPane::Status;
+            }
+            KeyCode::Esc => self.pause_menu = Some(PauseMenu::new()),
+            KeyCode::Enter if self.focused_pane == FocusedPane::CommandInput => {
+                let input = self.input_buffer.trim().to_string();
+                if !input.is_empty() {
+                    self.submit_command(&input);
                 }
+                self.input_buffer.clear(); // Clear buffer after processing
+            }
+            KeyCode::Char(c) if self.focused_pane == FocusedPane::CommandInput => {
+                self.history.reset_recall();
+                self.input_buffer.push(c);
+            }
+            KeyCode::Backspace if self.focused_pane == FocusedPane::CommandInput => {
+                self.history.reset_recall();
+                self.input_buffer.pop();
             }
             _ => {}
         }
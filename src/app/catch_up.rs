@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::game_core::{Resource, ResourceAmount};
+
+use super::game_worker::GameSnapshot;
+
+// Turns simulated for the time the game was closed in offline mode, remembers the colony from
+// before so the report can tell what changed
+#[derive(Debug)]
+pub struct CatchUp {
+    turns: u32,
+    start_turn: u32,
+    start_credits: ResourceAmount,
+    start_storage: HashMap<Resource, ResourceAmount>,
+}
+
+impl CatchUp {
+    pub fn new(snapshot: &GameSnapshot, turns: u32) -> Self {
+        CatchUp {
+            turns,
+            start_turn: snapshot.current_turn,
+            start_credits: snapshot.credits,
+            start_storage: get_storage(snapshot),
+        }
+    }
+
+    pub fn get_turns(&self) -> u32 {
+        self.turns
+    }
+
+    pub fn get_prompt(&self) -> String {
+        format!(
+            "Simulate the {} turn(s) that passed while you were away? Y/Enter to catch up, N/Esc to skip",
+            self.turns,
+        )
+    }
+
+    // One end turn per turn away, Wait would stop at the first notification
+    pub fn get_command(&self) -> String {
+        vec!["endturn"; self.turns as usize].join("; ")
+    }
+
+    pub fn get_report(&self, snapshot: &GameSnapshot) -> String {
+        let storage = get_storage(snapshot);
+        let resources: Vec<String> = Resource::all()
+            .iter()
+            .filter_map(|resource| {
+                let amount = *storage.get(resource)?;
+                let start = self.start_storage.get(resource).copied().unwrap_or_default();
+                let change = i64::from(amount) - i64::from(start);
+                Some(format!("{} {:+}", resource, change))
+            })
+            .collect();
+
+        format!(
+            "While you were away: {} of {} turn(s) passed (turn {} to {}). Credits {:+}. Stored {}.",
+            snapshot.current_turn - self.start_turn,
+            self.turns,
+            self.start_turn,
+            snapshot.current_turn,
+            i64::from(snapshot.credits) - i64::from(self.start_credits),
+            resources.join(", "),
+        )
+    }
+}
+
+// Stored resources summed over the current player's planets
+fn get_storage(snapshot: &GameSnapshot) -> HashMap<Resource, ResourceAmount> {
+    let mut storage: HashMap<Resource, ResourceAmount> = HashMap::new();
+    for status in snapshot.planets.values() {
+        for (resource, (amount, _)) in &status.storage {
+            *storage.entry(*resource).or_default() += *amount;
+        }
+    }
+    storage
+}
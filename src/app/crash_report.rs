@@ -0,0 +1,95 @@
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+use crate::game_core::{Replay, ReplayError};
+
+use super::log::LogMessage;
+use super::settings::CrashReportSettings;
+
+// Message and location of the last panic, the hook keeps it for the report instead of printing it
+// into the interface. Shared by all threads, the game panics on its worker's.
+static PANIC_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+
+// Keeps panics quiet until the terminal is restored, see take_panic_message
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info: &PanicHookInfo| {
+        let message = info.payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let message = match info.location() {
+            Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+            None => message,
+        };
+        if let Ok(mut panic_message) = PANIC_MESSAGE.lock() {
+            *panic_message = Some(message);
+        }
+    }));
+}
+
+pub fn remove_panic_hook() {
+    let _ = panic::take_hook();
+}
+
+pub fn take_panic_message() -> Option<String> {
+    PANIC_MESSAGE.lock().ok().and_then(|mut panic_message| panic_message.take())
+}
+
+// What went wrong and what the game looked like at the time, written to a directory of its own
+// so bug reports can simply attach it
+pub struct CrashReport<'a> {
+    pub reason: String,
+    // The command that was running when the game crashed, if any
+    pub command: Option<String>,
+    pub turn: u32,
+    pub logs: &'a [LogMessage],
+    pub replay: &'a Replay,
+}
+
+impl CrashReport<'_> {
+    // Returns the directory the report was written to
+    pub fn write(&self, settings: &CrashReportSettings) -> io::Result<PathBuf> {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+        let directory = settings.directory.join(format!("crash-{}", secs));
+        fs::create_dir_all(&directory)?;
+
+        let mut lines = vec![
+            format!("TerminalColony {}", env!("CARGO_PKG_VERSION")),
+            format!("Reason: {}", self.reason),
+            format!("Command: {}", self.command.as_deref().unwrap_or("none")),
+            format!("Turn: {}", self.turn),
+            format!("Name seed: {}", self.replay.get_name_seed().map_or("none".to_string(), |seed| seed.to_string())),
+            String::new(),
+            format!("Last {} log lines:", settings.log_lines),
+        ];
+        let skipped = self.logs.len().saturating_sub(settings.log_lines);
+        lines.extend(self.logs[skipped..].iter().map(|log| format!("  {}", log.text)));
+        fs::write(directory.join("report.txt"), lines.join("\n") + "\n")?;
+
+        // Loads like any saved game once copied over the save file
+        if settings.emergency_save {
+            Self::save_replay(self.replay, &directory.join("emergency_save.replay"))?;
+        }
+        Ok(directory)
+    }
+
+    fn save_replay(replay: &Replay, path: &Path) -> io::Result<()> {
+        replay.save(path).map_err(|err| match err {
+            ReplayError::Io(err) => err,
+            err => io::Error::other(err.to_string()),
+        })
+    }
+}
+
+// Saves the game to be offered on the next start. It is written next to the file first, so a save
+// failing halfway never leaves a broken one behind, and a panic while saving counts as an error.
+pub fn write_recovery_save(replay: &Replay, path: &Path) -> io::Result<()> {
+    let partial = path.with_extension("partial");
+    panic::catch_unwind(AssertUnwindSafe(|| CrashReport::save_replay(replay, &partial)))
+        .unwrap_or_else(|_| Err(io::Error::other(take_panic_message().unwrap_or_default())))?;
+    fs::rename(&partial, path)
+}
@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+// Drawing statistics for the debug overlay, averaged over the last second
+#[derive(Debug)]
+pub struct FrameStats {
+    window_start: Instant,
+    frames: u32,
+    busy_time: Duration,
+    fps: u32,
+    busy_percent: u32,
+    last_frame_time: Duration,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        FrameStats {
+            window_start: Instant::now(),
+            frames: 0,
+            busy_time: Duration::ZERO,
+            fps: 0,
+            busy_percent: 0,
+            last_frame_time: Duration::ZERO,
+        }
+    }
+
+    // Time spent gathering the state for a frame and drawing it
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        self.frames += 1;
+        self.busy_time += frame_time;
+        self.last_frame_time = frame_time;
+
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= WINDOW {
+            self.fps = (self.frames as f64 / elapsed.as_secs_f64()).round() as u32;
+            self.busy_percent = (self.busy_time.as_secs_f64() / elapsed.as_secs_f64() * 100.0).round() as u32;
+            self.window_start = Instant::now();
+            self.frames = 0;
+            self.busy_time = Duration::ZERO;
+        }
+    }
+
+    pub fn get_label(&self) -> String {
+        format!(
+            "FPS {} | Frame {:.1}ms | Busy {}%",
+            self.fps,
+            self.last_frame_time.as_secs_f64() * 1000.0,
+            self.busy_percent,
+        )
+    }
+}
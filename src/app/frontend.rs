@@ -0,0 +1,208 @@
+use std::io;
+#[cfg(feature = "terminal")]
+use std::io::{Stdout, Write};
+use std::ops::{BitAnd, BitOr, BitOrAssign, SubAssign};
+use std::time::Duration;
+
+#[cfg(feature = "terminal")]
+use ratatui::crossterm::event::{
+    self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyEventKind
+};
+#[cfg(feature = "terminal")]
+use ratatui::crossterm::execute;
+#[cfg(feature = "terminal")]
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+#[cfg(feature = "terminal")]
+use ratatui::prelude::CrosstermBackend;
+use ratatui::Frame;
+#[cfg(feature = "terminal")]
+use ratatui::Terminal;
+
+// Keys the app knows, a frontend maps its own onto these and drops the rest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    F(u8),
+    Backspace,
+    Enter,
+    Esc,
+    Tab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers(0);
+    pub const SHIFT: KeyModifiers = KeyModifiers(1);
+    pub const CONTROL: KeyModifiers = KeyModifiers(1 << 1);
+    pub const ALT: KeyModifiers = KeyModifiers(1 << 2);
+
+    pub fn contains(self, other: KeyModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(self, other: KeyModifiers) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for KeyModifiers {
+    type Output = KeyModifiers;
+
+    fn bitor(self, other: KeyModifiers) -> KeyModifiers {
+        KeyModifiers(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for KeyModifiers {
+    fn bitor_assign(&mut self, other: KeyModifiers) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for KeyModifiers {
+    type Output = KeyModifiers;
+
+    fn bitand(self, other: KeyModifiers) -> KeyModifiers {
+        KeyModifiers(self.0 & other.0)
+    }
+}
+
+impl SubAssign for KeyModifiers {
+    fn sub_assign(&mut self, other: KeyModifiers) {
+        self.0 &= !other.0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+// Input the app reacts to, whatever frontend it came from
+#[derive(Debug, Clone)]
+pub enum InputEvent {
+    Key(KeyEvent),
+    Paste(String),
+    // The screen has to be redrawn at the new size
+    Resize,
+}
+
+// Where the interface is drawn and input comes from. The app only talks to the terminal through
+// this, so another frontend can take its place without touching game code. The game still runs
+// on its own thread and reads the system clock, so targets without them (e.g. WASM) are not
+// supported yet.
+pub trait Frontend {
+    fn draw<F: FnOnce(&mut Frame)>(&mut self, render: F) -> io::Result<()>;
+    // Waits up to the timeout for input, key releases are not reported
+    fn poll_event(&mut self, timeout: Duration) -> io::Result<Option<InputEvent>>;
+    // Gives the terminal back in the state it was found in
+    fn restore(&mut self) -> io::Result<()>;
+    // Draws the player's attention back to the game, e.g. while they are in another window
+    fn ring_bell(&mut self) -> io::Result<()>;
+    fn notify(&mut self, message: &str) -> io::Result<()>;
+}
+
+// The terminal the game runs in, only built with the `terminal` feature
+#[cfg(feature = "terminal")]
+pub struct CrosstermFrontend {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+#[cfg(feature = "terminal")]
+impl CrosstermFrontend {
+    pub fn new() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        Ok(CrosstermFrontend { terminal })
+    }
+
+    fn to_key_event(key_event: event::KeyEvent) -> Option<KeyEvent> {
+        let code = match key_event.code {
+            event::KeyCode::Char(c) => KeyCode::Char(c),
+            event::KeyCode::F(number) => KeyCode::F(number),
+            event::KeyCode::Backspace => KeyCode::Backspace,
+            event::KeyCode::Enter => KeyCode::Enter,
+            event::KeyCode::Esc => KeyCode::Esc,
+            event::KeyCode::Tab => KeyCode::Tab,
+            event::KeyCode::Up => KeyCode::Up,
+            event::KeyCode::Down => KeyCode::Down,
+            event::KeyCode::Left => KeyCode::Left,
+            event::KeyCode::Right => KeyCode::Right,
+            event::KeyCode::Home => KeyCode::Home,
+            event::KeyCode::End => KeyCode::End,
+            _ => return None,
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for (modifier, own) in [
+            (event::KeyModifiers::SHIFT, KeyModifiers::SHIFT),
+            (event::KeyModifiers::CONTROL, KeyModifiers::CONTROL),
+            (event::KeyModifiers::ALT, KeyModifiers::ALT),
+        ] {
+            if key_event.modifiers.contains(modifier) {
+                modifiers |= own;
+            }
+        }
+        Some(KeyEvent { code, modifiers })
+    }
+}
+
+#[cfg(feature = "terminal")]
+impl Frontend for CrosstermFrontend {
+    fn draw<F: FnOnce(&mut Frame)>(&mut self, render: F) -> io::Result<()> {
+        self.terminal.draw(render)?;
+        Ok(())
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> io::Result<Option<InputEvent>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+
+        match event::read()? {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                Ok(Self::to_key_event(key_event).map(InputEvent::Key))
+            }
+            Event::Paste(text) => Ok(Some(InputEvent::Paste(text))),
+            Event::Resize(_, _) => Ok(Some(InputEvent::Resize)),
+            // TODO: handle other events like Mouse here if needed
+            _ => Ok(None),
+        }
+    }
+
+    fn restore(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        execute!(
+            self.terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste
+        )?;
+        self.terminal.show_cursor()
+    }
+
+    fn ring_bell(&mut self) -> io::Result<()> {
+        let backend = self.terminal.backend_mut();
+        backend.write_all(b"\x07")?;
+        backend.flush()
+    }
+
+    // OSC 9 is understood by e.g. iTerm2, Windows Terminal and WezTerm, others ignore it
+    fn notify(&mut self, message: &str) -> io::Result<()> {
+        let message: String = message.chars().filter(|c| !c.is_control()).collect();
+        let backend = self.terminal.backend_mut();
+        write!(backend, "\x1b]9;{}\x07", message)?;
+        backend.flush()
+    }
+}
@@ -0,0 +1,365 @@
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+use crate::game_core::{
+    Alert, Choice, Colonypedia, GameCore, GameCoreError, InputCheck, MissionStatus, PlanetStatus, PlayerColor,
+    ResourceAmount, TurnRecord, TutorialStatus
+};
+
+// What the interface shows of the game, sent by the worker whenever the game's revision changed,
+// so drawing never has to wait for the game
+#[derive(Debug, Clone)]
+pub struct GameSnapshot {
+    // Counts the snapshots sent, anything worked out from an older one is outdated. The playtime
+    // is updated on its own and leaves it as it is.
+    pub version: u64,
+    pub current_turn: u32,
+    pub is_running: bool,
+    pub player_name: String,
+    pub player_color: PlayerColor,
+    pub credits: ResourceAmount,
+    pub credits_income: i64,
+    pub planet_names: Vec<String>,
+    pub planet_count: usize,
+    // Statuses of the current player's planets by name
+    pub planets: HashMap<String, Arc<PlanetStatus>>,
+    pub artifacts: Vec<String>,
+    pub tutorial_status: Option<TutorialStatus>,
+    pub missions: Vec<MissionStatus>,
+    pub alerts: Vec<Alert>,
+    pub pending_choice: Option<Choice>,
+    // The flagged command waiting for `confirm`, if any
+    pub confirmation_command: Option<String>,
+    pub playtime_secs: u64,
+    // Only built from the configs, so every snapshot shares it
+    pub colonypedia: Arc<Colonypedia>,
+}
+
+impl GameSnapshot {
+    fn new(game_core: &GameCore, version: u64, colonypedia: &Arc<Colonypedia>) -> Self {
+        let planet_names = game_core.get_current_player_planet_names();
+        let planets = planet_names
+            .iter()
+            .filter_map(|planet_name| {
+                let status = game_core.get_current_player_planet_status(planet_name)?;
+                Some((planet_name.clone(), Arc::new(status)))
+            })
+            .collect();
+
+        GameSnapshot {
+            version,
+            current_turn: game_core.get_current_turn(),
+            is_running: game_core.is_running(),
+            player_name: game_core.get_current_player_name(),
+            player_color: game_core.get_current_player_color(),
+            credits: game_core.get_current_player_credits(),
+            credits_income: game_core.get_current_player_credits_income(),
+            planet_names,
+            planet_count: game_core.get_planet_count(),
+            planets,
+            artifacts: game_core.get_current_player_artifact_names(),
+            tutorial_status: game_core.get_tutorial_status(),
+            missions: game_core.get_mission_statuses(),
+            alerts: game_core.get_alerts(),
+            pending_choice: game_core.get_pending_choice(),
+            confirmation_command: game_core.get_confirmation_command(),
+            playtime_secs: game_core.get_playtime_secs(),
+            colonypedia: Arc::clone(colonypedia),
+        }
+    }
+
+    pub fn get_planet_status(&self, planet_name: &str) -> Option<Arc<PlanetStatus>> {
+        self.planets.get(planet_name).cloned()
+    }
+}
+
+// A command run on the game and how it went
+#[derive(Debug)]
+pub struct CommandOutcome {
+    pub result: Result<Option<String>, GameCoreError>,
+    pub is_help: bool,
+}
+
+impl CommandOutcome {
+    pub fn run(game_core: &mut GameCore, command: &str) -> Self {
+        CommandOutcome {
+            result: game_core.execute_command(command),
+            is_help: game_core.is_help_command(command),
+        }
+    }
+}
+
+// What the game makes of the typed command line, worked out again once the input or the game
+// changed
+#[derive(Debug)]
+pub struct InputInfo {
+    input: String,
+    // Of the snapshot it was worked out with
+    version: u64,
+    pub preview: Option<String>,
+    pub arg_hints: Option<String>,
+    pub check: Option<InputCheck>,
+}
+
+impl InputInfo {
+    pub fn new(game_core: &GameCore, input: String, version: u64) -> Self {
+        InputInfo {
+            preview: game_core.get_command_preview(&input),
+            arg_hints: game_core.get_missing_arg_hints(&input),
+            check: game_core.check_input(&input),
+            input,
+            version,
+        }
+    }
+
+    pub fn is_current(&self, input: &str, snapshot: &GameSnapshot) -> bool {
+        self.input == input && self.version == snapshot.version
+    }
+}
+
+// What the game reports on its own, taken with GameWorker::poll
+#[derive(Debug)]
+pub enum WorkerEvent {
+    // Result of a command sent with GameWorker::execute
+    Executed(CommandOutcome),
+    Notifications(Vec<String>),
+    TurnRecords(Vec<TurnRecord>),
+}
+
+type Task = Box<dyn FnOnce(&mut GameCore) -> Box<dyn Any + Send> + Send>;
+
+enum Request {
+    Execute(String),
+    // Answered with Message::Returned
+    Call(Task),
+    // Like a call, but the game is only read
+    Query(Task),
+    // Like a call, but nobody waits for it
+    Run(Box<dyn FnOnce(&mut GameCore) + Send>),
+}
+
+enum Message {
+    Snapshot(Box<GameSnapshot>),
+    // The playtime counted up while nothing else changed
+    Playtime(u64),
+    Returned(Box<dyn Any + Send>),
+    Event(WorkerEvent),
+    // A request panicked, the game stays on the worker so it can still be saved
+    Crashed,
+}
+
+// Owns the game on a thread of its own, so slow turns never block rendering or input. Requests go
+// in over one channel, snapshots and events come back over another. The game is built on that
+// thread and never leaves it, neither it nor its script engine is Send.
+// Calls and queries wait for the worker, the app only makes them while no command is executing.
+pub struct GameWorker {
+    requests: Option<Sender<Request>>,
+    messages: Receiver<Message>,
+    thread: Option<JoinHandle<()>>,
+    snapshot: GameSnapshot,
+    // Events that came in while waiting for a call to return
+    events: VecDeque<WorkerEvent>,
+    // Commands sent with `execute` whose outcome hasn't come back yet
+    executing: usize,
+}
+
+impl GameWorker {
+    // Returns once the game is built and its first snapshot arrived
+    pub fn spawn(
+        build: impl FnOnce() -> Result<GameCore, GameCoreError> + Send + 'static,
+    ) -> Result<Self, GameCoreError> {
+        let (request_sender, request_receiver) = mpsc::channel();
+        let (message_sender, message_receiver) = mpsc::channel();
+        let (built_sender, built_receiver) = mpsc::channel();
+        let thread = thread::spawn(move || match build() {
+            Ok(game_core) => {
+                let _ = built_sender.send(Ok(()));
+                work(game_core, request_receiver, message_sender);
+            }
+            Err(err) => {
+                let _ = built_sender.send(Err(err));
+            }
+        });
+
+        match built_receiver.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => return Err(err),
+            Err(_) => resume_worker_panic(Some(thread)),
+        }
+        let mut thread = Some(thread);
+        let snapshot = match message_receiver.recv() {
+            Ok(Message::Snapshot(snapshot)) => *snapshot,
+            _ => resume_worker_panic(thread.take()),
+        };
+
+        Ok(GameWorker {
+            requests: Some(request_sender),
+            messages: message_receiver,
+            thread,
+            snapshot,
+            events: VecDeque::new(),
+            executing: 0,
+        })
+    }
+
+    pub fn get_snapshot(&self) -> &GameSnapshot {
+        &self.snapshot
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.executing > 0
+    }
+
+    // Runs the command without waiting for it, its outcome comes back as WorkerEvent::Executed
+    pub fn execute(&mut self, command: &str) {
+        self.executing += 1;
+        self.send(Request::Execute(command.to_string()));
+    }
+
+    // Runs the task on the game and waits for its result and any snapshot after it
+    pub fn call<R: Send + 'static>(&mut self, task: impl FnOnce(&mut GameCore) -> R + Send + 'static) -> R {
+        self.send(Request::Call(Box::new(move |game_core| Box::new(task(game_core)))));
+        self.wait_for_return()
+    }
+
+    pub fn query<R: Send + 'static>(&mut self, task: impl FnOnce(&GameCore) -> R + Send + 'static) -> R {
+        self.send(Request::Query(Box::new(move |game_core| Box::new(task(game_core)))));
+        self.wait_for_return()
+    }
+
+    // Runs the task on the game without waiting for it
+    pub fn run(&mut self, task: impl FnOnce(&mut GameCore) + Send + 'static) {
+        self.send(Request::Run(Box::new(task)));
+    }
+
+    // Takes the newest snapshot and the events that came in since the last poll
+    pub fn poll(&mut self) -> Vec<WorkerEvent> {
+        loop {
+            match self.messages.try_recv() {
+                Ok(message) => {
+                    self.handle(message);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => resume_worker_panic(self.thread.take()),
+            }
+        }
+        self.events.drain(..).collect()
+    }
+
+    fn send(&mut self, request: Request) {
+        let sent = self.requests.as_ref().is_some_and(|requests| requests.send(request).is_ok());
+        if !sent {
+            resume_worker_panic(self.thread.take());
+        }
+    }
+
+    fn wait_for_return<R: 'static>(&mut self) -> R {
+        loop {
+            let Ok(message) = self.messages.recv() else {
+                resume_worker_panic(self.thread.take());
+            };
+            if let Some(value) = self.handle(message) {
+                match value.downcast::<R>() {
+                    Ok(value) => return *value,
+                    Err(_) => unreachable!("a call returns what its task returned"),
+                }
+            }
+        }
+    }
+
+    // Returns what a call returned, keeps anything else for poll. A panic on the worker carries
+    // on here, so it ends up in the crash report like one of the interface.
+    fn handle(&mut self, message: Message) -> Option<Box<dyn Any + Send>> {
+        match message {
+            Message::Snapshot(snapshot) => self.snapshot = *snapshot,
+            Message::Playtime(secs) => self.snapshot.playtime_secs = secs,
+            Message::Returned(value) => return Some(value),
+            Message::Event(event) => {
+                if let WorkerEvent::Executed(_) = event {
+                    self.executing -= 1;
+                }
+                self.events.push_back(event);
+            }
+            Message::Crashed => {
+                self.executing = 0;
+                panic::resume_unwind(Box::new("The game crashed"));
+            }
+        }
+        None
+    }
+}
+
+impl Drop for GameWorker {
+    // Closing the channel ends the worker once it is done with what it was sent
+    fn drop(&mut self) {
+        self.requests = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn work(mut game_core: GameCore, requests: Receiver<Request>, messages: Sender<Message>) {
+    let colonypedia = Arc::new(game_core.get_colonypedia().clone());
+    let mut version = 0;
+    let mut revision = game_core.get_revision();
+    let mut playtime_secs = game_core.get_playtime_secs();
+    let _ = messages.send(Message::Snapshot(Box::new(GameSnapshot::new(&game_core, version, &colonypedia))));
+
+    for request in requests {
+        let handled = panic::catch_unwind(AssertUnwindSafe(|| {
+            let reply = match request {
+                Request::Execute(command) => {
+                    Some(Message::Event(WorkerEvent::Executed(CommandOutcome::run(&mut game_core, &command))))
+                }
+                Request::Call(task) | Request::Query(task) => Some(Message::Returned(task(&mut game_core))),
+                Request::Run(task) => {
+                    task(&mut game_core);
+                    None
+                }
+            };
+
+            // The snapshot goes first, so whoever gets the reply sees the game it left behind. It
+            // is only rebuilt once the game changed, e.g. not for the playtime added every second.
+            let mut replies = Vec::new();
+            if game_core.get_revision() != revision {
+                revision = game_core.get_revision();
+                playtime_secs = game_core.get_playtime_secs();
+                version += 1;
+                replies.push(Message::Snapshot(Box::new(GameSnapshot::new(&game_core, version, &colonypedia))));
+            } else if game_core.get_playtime_secs() != playtime_secs {
+                playtime_secs = game_core.get_playtime_secs();
+                replies.push(Message::Playtime(playtime_secs));
+            }
+            replies.extend(reply);
+            let notifications = game_core.take_notifications();
+            if !notifications.is_empty() {
+                replies.push(Message::Event(WorkerEvent::Notifications(notifications)));
+            }
+            let turn_records = game_core.take_turn_records();
+            if !turn_records.is_empty() {
+                replies.push(Message::Event(WorkerEvent::TurnRecords(turn_records)));
+            }
+            replies
+        }));
+
+        let replies: Vec<Message> = handled.unwrap_or_else(|_| vec![Message::Crashed]);
+        for reply in replies {
+            if messages.send(reply).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+// Carries the worker's panic on in the interface, which can't go on without its game
+fn resume_worker_panic(thread: Option<JoinHandle<()>>) -> ! {
+    match thread.map(JoinHandle::join) {
+        Some(Err(payload)) => panic::resume_unwind(payload),
+        _ => panic!("The game worker stopped"),
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::{fs, io};
+
+pub struct CommandHistory {
+    entries: VecDeque<String>,
+    max_entries: usize,
+    // Position while browsing with Up/Down, together with the input typed before browsing
+    recall_idx: Option<usize>,
+    draft: String,
+}
+
+impl CommandHistory {
+    pub fn new(max_entries: usize) -> Self {
+        CommandHistory {
+            entries: VecDeque::new(),
+            max_entries,
+            recall_idx: None,
+            draft: String::new(),
+        }
+    }
+
+    // A missing history file is not an error, it simply means a fresh history
+    pub fn load(path: &Path, max_entries: usize) -> io::Result<Self> {
+        let mut history = Self::new(max_entries);
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                    history.push(line);
+                }
+                Ok(history)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(history),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(entry);
+            content.push('\n');
+        }
+        fs::write(path, content)
+    }
+
+    pub fn push(&mut self, command: &str) {
+        self.recall_idx = None;
+        if self.max_entries == 0 {
+            return;
+        }
+
+        if self.entries.len() >= self.max_entries {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(command.to_string());
+    }
+
+    // Steps back to an older entry, remembering the current input so it can be restored. None
+    // when there is no older entry.
+    pub fn recall_previous(&mut self, current_input: &str) -> Option<&str> {
+        let idx = match self.recall_idx {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current_input.to_string();
+                self.entries.len() - 1
+            }
+            Some(0) => return None,
+            Some(idx) => idx - 1,
+        };
+        self.recall_idx = Some(idx);
+        self.entries.get(idx).map(String::as_str)
+    }
+
+    // Steps forward to a newer entry, returning the remembered input past the newest one
+    pub fn recall_next(&mut self) -> Option<&str> {
+        let idx = self.recall_idx?;
+        if idx + 1 < self.entries.len() {
+            self.recall_idx = Some(idx + 1);
+            self.entries.get(idx + 1).map(String::as_str)
+        } else {
+            self.recall_idx = None;
+            Some(&self.draft)
+        }
+    }
+
+    pub fn reset_recall(&mut self) {
+        self.recall_idx = None;
+    }
+
+    pub fn get_last(&self) -> Option<&str> {
+        self.entries.back().map(String::as_str)
+    }
+
+    pub fn get_last_starting_with(&self, prefix: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.starts_with(prefix))
+            .map(String::as_str)
+    }
+
+    // Expands shell-like history references: "!!" re-runs the previous command and
+    // "!prefix" re-runs the most recent command starting with prefix. Any other input
+    // is returned unchanged.
+    pub fn expand(&self, input: &str) -> Result<String, String> {
+        let Some(reference) = input.strip_prefix('!') else {
+            return Ok(input.to_string());
+        };
+
+        if reference == "!" {
+            return self
+                .get_last()
+                .map(str::to_string)
+                .ok_or_else(|| "No previous command to repeat.".to_string());
+        }
+
+        if reference.is_empty() || reference.contains(char::is_whitespace) {
+            return Err(format!("Invalid history reference: '{}'.", input));
+        }
+
+        self.get_last_starting_with(reference)
+            .map(str::to_string)
+            .ok_or_else(|| format!("No previous command starting with '{}'.", reference))
+    }
+}
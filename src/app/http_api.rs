@@ -0,0 +1,304 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::game_core::{PlanetStatus, Resource};
+
+use super::game_worker::GameSnapshot;
+use super::log::{LogLevel, LogMessage};
+
+// A client gets this long to send its request and take the response before it is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(200);
+const MAX_REQUEST_SIZE: usize = 8192;
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Response { status: "200 OK", body }
+    }
+
+    fn error(status: &'static str, message: &str) -> Self {
+        Response { status, body: format!("{{\"error\":{}}}", json_string(message)) }
+    }
+}
+
+// A client being served over several polls, so a slow one never holds up a frame
+struct Connection {
+    stream: TcpStream,
+    accepted_at: Instant,
+    request: Vec<u8>,
+    // Set once the request is complete, with what is left to write of the response
+    response: Option<Vec<u8>>,
+}
+
+impl Connection {
+    // Reads what arrived of the request, returns whether it is complete
+    fn read_request(&mut self) -> io::Result<bool> {
+        let mut buffer = [0u8; 1024];
+        while !self.request.windows(4).any(|window| window == b"\r\n\r\n") && self.request.len() < MAX_REQUEST_SIZE {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Ok(true),
+                Ok(count) => self.request.extend_from_slice(&buffer[..count]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(false),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(true)
+    }
+
+    // Writes what the socket takes of the response, returns whether all of it was written
+    fn write_response(&mut self) -> io::Result<bool> {
+        let Some(response) = &mut self.response else {
+            return Ok(false);
+        };
+        while !response.is_empty() {
+            match self.stream.write(response) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(count) => {
+                    response.drain(..count);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(false),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(true)
+    }
+}
+
+// Read-only JSON view of the running game for dashboards and other tools. It is polled from the
+// app's main loop without ever blocking it, so requests are answered between frames and never
+// touch the terminal.
+// Endpoints:
+//   GET /state                   - turn, current player and their planet names
+//   GET /players/<name>/planets  - status of every planet of a player
+//   GET /log                     - the messages shown in the log pane
+pub struct HttpApi {
+    listener: TcpListener,
+    connections: Vec<Connection>,
+}
+
+impl HttpApi {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(HttpApi { listener, connections: Vec::new() })
+    }
+
+    pub fn get_address(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Takes new clients and goes on with the waiting ones, a failing client never stops the game
+    pub fn poll(&mut self, snapshot: &GameSnapshot, logs: &[LogMessage]) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if stream.set_nonblocking(true).is_ok() {
+                        self.connections.push(Connection {
+                            stream,
+                            accepted_at: Instant::now(),
+                            request: Vec::new(),
+                            response: None,
+                        });
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+
+        self.connections.retain_mut(|connection| {
+            if connection.accepted_at.elapsed() > REQUEST_TIMEOUT {
+                return false;
+            }
+            // Kept until it is answered, dropped when it fails
+            matches!(Self::serve(connection, snapshot, logs), Ok(false))
+        });
+    }
+
+    // Returns whether the connection is done with
+    fn serve(connection: &mut Connection, snapshot: &GameSnapshot, logs: &[LogMessage]) -> io::Result<bool> {
+        if connection.response.is_none() {
+            if !connection.read_request()? {
+                return Ok(false);
+            }
+            let response = Self::respond(&connection.request, snapshot, logs);
+            connection.response = Some(response.into_bytes());
+        }
+        connection.write_response()
+    }
+
+    fn respond(request: &[u8], snapshot: &GameSnapshot, logs: &[LogMessage]) -> String {
+        let request = String::from_utf8_lossy(request);
+        let response = match request.lines().next().unwrap_or_default().split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["GET", target, _] => Self::route(target, snapshot, logs),
+            [_, _, _] => Response::error("405 Method Not Allowed", "Only GET requests are supported."),
+            _ => Response::error("400 Bad Request", "Malformed request."),
+        };
+
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.body.len(),
+            response.body,
+        )
+    }
+
+    fn route(target: &str, snapshot: &GameSnapshot, logs: &[LogMessage]) -> Response {
+        let path = target.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+        match segments.as_slice() {
+            ["state"] => Response::ok(Self::get_state_json(snapshot)),
+            ["players", name, "planets"] => {
+                let name = percent_decode(name);
+                // TODO: Look up any player once the game has several
+                if !name.eq_ignore_ascii_case(&snapshot.player_name) {
+                    return Response::error("404 Not Found", &format!("Player '{}' not found.", name));
+                }
+
+                let planets: Vec<String> = snapshot.planet_names
+                    .iter()
+                    .filter_map(|planet_name| snapshot.get_planet_status(planet_name))
+                    .map(|status| Self::get_planet_json(&status))
+                    .collect();
+                Response::ok(format!("[{}]", planets.join(",")))
+            }
+            ["log"] => {
+                let messages: Vec<String> = logs
+                    .iter()
+                    .map(|message| {
+                        let level = match message.level {
+                            LogLevel::Info => "info",
+                            LogLevel::Error => "error",
+                            LogLevel::Success => "success",
+                            LogLevel::Help => "help",
+                            LogLevel::Thought => "thought",
+                        };
+                        format!("{{\"level\":\"{}\",\"text\":{}}}", level, json_string(&message.text))
+                    })
+                    .collect();
+                Response::ok(format!("[{}]", messages.join(",")))
+            }
+            _ => Response::error("404 Not Found", &format!("No endpoint at '{}'.", path)),
+        }
+    }
+
+    fn get_state_json(snapshot: &GameSnapshot) -> String {
+        format!(
+            "{{\"turn\":{},\"running\":{},\"player\":{{\"name\":{},\"color\":{},\"credits\":{},\"credits_income\":{},\
+            \"planets\":{},\"artifacts\":{}}}}}",
+            snapshot.current_turn,
+            snapshot.is_running,
+            json_string(&snapshot.player_name),
+            json_string(&snapshot.player_color.to_string()),
+            snapshot.credits.get(),
+            snapshot.credits_income,
+            json_string_array(&snapshot.planet_names),
+            json_string_array(&snapshot.artifacts),
+        )
+    }
+
+    fn get_planet_json(status: &PlanetStatus) -> String {
+        let resources: Vec<String> = [Resource::Energy, Resource::Minerals, Resource::Gas]
+            .iter()
+            .map(|resource| {
+                let production = status.production.get(resource).copied().unwrap_or_default();
+                let (amount, capacity) = status.storage.get(resource).copied().unwrap_or_default();
+                format!(
+                    "{}:{{\"production\":{},\"amount\":{},\"capacity\":{},\"reserved\":{}}}",
+                    json_string(&resource.to_string()),
+                    production.get(),
+                    amount.get(),
+                    capacity.get(),
+                    status.reserved.get(resource).copied().unwrap_or_default().get(),
+                )
+            })
+            .collect();
+
+        let buildings: Vec<String> = status.buildings
+            .iter()
+            .filter(|(_, level)| *level > 0)
+            .map(|(name, level)| format!("{}:{}", json_string(name), level))
+            .collect();
+
+        let construction: Vec<String> = status.construction_queue
+            .iter()
+            .map(|construction| {
+                format!(
+                    "{{\"building\":{},\"level\":{},\"location\":{},\"remaining_turns\":{},\"reserved\":{}}}",
+                    json_string(&construction.building.get_name()),
+                    construction.level,
+                    json_string(&construction.get_location(&status.planet_name)),
+                    construction.remaining_turns,
+                    construction.is_reserved,
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"name\":{},\"size\":{},\"building_slots\":{{\"used\":{},\"total\":{}}},\"population\":{},\"housing\":{},\
+            \"tax_rate\":{},\"morale\":{},\"on_strike\":{},\"resources\":{{{}}},\"buildings\":{{{}}},\"construction_queue\":[{}]}}",
+            json_string(&status.planet_name),
+            json_string(&status.size),
+            status.building_slots.0,
+            status.building_slots.1,
+            status.population,
+            status.housing,
+            status.tax_rate,
+            status.morale,
+            status.on_strike,
+            resources.join(","),
+            buildings.join(","),
+            construction.join(","),
+        )
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for char in text.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            char if char.is_control() => json.push_str(&format!("\\u{:04x}", char as u32)),
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_string_array(texts: &[String]) -> String {
+    let items: Vec<String> = texts.iter().map(|text| json_string(text)).collect();
+    format!("[{}]", items.join(","))
+}
+
+// Undoes the %XX escapes of a URL path segment, e.g. the space in "Player%201"
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%'
+            && let Some(hex) = segment.get(idx + 1..idx + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            idx += 3;
+            continue;
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
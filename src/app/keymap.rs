@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::frontend::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug)]
+pub enum KeymapError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    InvalidKey(String),
+    DuplicateKey(String),
+    InvalidBinding(String),
+    TypedKey(String),
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io(e) => write!(f, "Failed to read keymap file: {}", e),
+            KeymapError::Toml(e) => write!(f, "Failed to parse keymap file (TOML): {}", e),
+            KeymapError::InvalidKey(key) => write!(f, "Invalid key in keymap: '{}'", key),
+            KeymapError::DuplicateKey(key) => write!(f, "Key bound more than once in keymap: '{}'", key),
+            KeymapError::InvalidBinding(key) => write!(
+                f, "Key '{}' must be bound to either a command or an action", key
+            ),
+            KeymapError::TypedKey(key) => write!(
+                f, "Key '{}' is needed for typing, bind it with Ctrl+ or Alt+ or as a normal binding", key
+            ),
+        }
+    }
+}
+
+impl Error for KeymapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KeymapError::Io(e) => Some(e),
+            KeymapError::Toml(e) => Some(e),
+            KeymapError::InvalidKey(_) => None,
+            KeymapError::DuplicateKey(_) => None,
+            KeymapError::InvalidBinding(_) => None,
+            KeymapError::TypedKey(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for KeymapError {
+    fn from(err: io::Error) -> Self {
+        KeymapError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for KeymapError {
+    fn from(err: toml::de::Error) -> Self {
+        KeymapError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// Actions of the interface itself, for keys that do something other than run a command
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    Colonypedia,
+    Missions,
+    PreviousPlanet,
+    NextPlanet,
+    PreviousSite,
+    NextSite,
+    // Leaves normal mode for the command input
+    InsertMode,
+    // Compares the planet shown in the status pane with the next one
+    ComparePlanets,
+    // Lists the problems across all planets
+    Alerts,
+    // Shows only the AI's thoughts in the log, see AiThoughtsSettings
+    AiThoughts,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyBinding {
+    Command(String),
+    Action(KeyAction),
+}
+
+#[derive(Deserialize, Debug)]
+struct KeyBindingConfig {
+    key: String,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    action: Option<KeyAction>,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct KeymapConfig {
+    #[serde(default)]
+    vim_mode: bool,
+    #[serde(default)]
+    bindings: Vec<KeyBindingConfig>,
+    // Only apply in normal mode, i.e. while the status pane is focused in vim mode
+    #[serde(default)]
+    normal_bindings: Vec<KeyBindingConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct KeyCombo {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyCombo {
+    // Parses keys like "F5", "Esc" or "Ctrl+E"
+    fn parse(input: &str) -> Result<Self, KeymapError> {
+        let invalid = || KeymapError::InvalidKey(input.to_string());
+
+        let mut parts: Vec<&str> = input.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty()).ok_or_else(invalid)?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let lower_key = key.to_lowercase();
+        let code = match lower_key.as_str() {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "space" => KeyCode::Char(' '),
+            _ if lower_key.starts_with('f') && lower_key.len() > 1 => {
+                let number: u8 = lower_key[1..].parse().map_err(|_| invalid())?;
+                if !(1..=12).contains(&number) {
+                    return Err(invalid());
+                }
+                KeyCode::F(number)
+            }
+            _ => {
+                let mut chars = lower_key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(invalid()),
+                }
+            }
+        };
+
+        Ok(KeyCombo { code, modifiers })
+    }
+
+    fn from_event(key_event: &KeyEvent) -> Self {
+        let code = match key_event.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        let mut modifiers = key_event.modifiers
+            & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        // Symbols like ':' need Shift on most layouts, they are bound without it
+        if let KeyCode::Char(c) = code
+            && !c.is_ascii_alphabetic()
+        {
+            modifiers -= KeyModifiers::SHIFT;
+        }
+
+        KeyCombo { code, modifiers }
+    }
+
+    // Whether the key goes into the command input when it is pressed there
+    fn is_typed(&self) -> bool {
+        let is_text = matches!(self.code, KeyCode::Char(_) | KeyCode::Enter | KeyCode::Tab);
+        is_text && !self.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyHint {
+    pub key: String,
+    pub label: String,
+}
+
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: HashMap<KeyCombo, KeyBinding>,
+    hints: Vec<KeyHint>,
+    vim_mode: bool,
+    normal_bindings: HashMap<KeyCombo, KeyBinding>,
+    normal_hints: Vec<KeyHint>,
+}
+
+impl Keymap {
+    pub fn load(path: &Path) -> Result<Self, KeymapError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: KeymapConfig = toml::from_str(&toml_content)?;
+
+        // Bindings apply while typing too, so they can't take keys the command input needs
+        let (bindings, hints) = Self::parse_bindings(config.bindings, false)?;
+        let (normal_bindings, normal_hints) = Self::parse_bindings(config.normal_bindings, true)?;
+        Ok(Keymap { bindings, hints, vim_mode: config.vim_mode, normal_bindings, normal_hints })
+    }
+
+    fn parse_bindings(
+        binding_configs: Vec<KeyBindingConfig>,
+        allow_typed_keys: bool,
+    ) -> Result<(HashMap<KeyCombo, KeyBinding>, Vec<KeyHint>), KeymapError> {
+        let mut bindings = HashMap::new();
+        let mut hints = Vec::new();
+        for binding in binding_configs {
+            let combo = KeyCombo::parse(&binding.key)?;
+            if !allow_typed_keys && combo.is_typed() {
+                return Err(KeymapError::TypedKey(binding.key));
+            }
+            let key_binding = match (binding.command, binding.action) {
+                (Some(command), None) => KeyBinding::Command(command),
+                (None, Some(action)) => KeyBinding::Action(action),
+                _ => return Err(KeymapError::InvalidBinding(binding.key)),
+            };
+            if bindings.insert(combo, key_binding).is_some() {
+                return Err(KeymapError::DuplicateKey(binding.key));
+            }
+
+            if let Some(label) = binding.label {
+                hints.push(KeyHint { key: binding.key, label });
+            }
+        }
+        Ok((bindings, hints))
+    }
+
+    // Returns what the pressed key is bound to, if anything
+    pub fn get_binding(&self, key_event: &KeyEvent) -> Option<&KeyBinding> {
+        self.bindings.get(&KeyCombo::from_event(key_event))
+    }
+
+    pub fn get_normal_binding(&self, key_event: &KeyEvent) -> Option<&KeyBinding> {
+        self.normal_bindings.get(&KeyCombo::from_event(key_event))
+    }
+
+    pub fn get_hints(&self) -> &[KeyHint] {
+        &self.hints
+    }
+
+    pub fn get_normal_hints(&self) -> &[KeyHint] {
+        &self.normal_hints
+    }
+
+    pub fn is_vim_mode(&self) -> bool {
+        self.vim_mode
+    }
+}
@@ -1,10 +1,12 @@
-use ratatui::style::{Color, Style};
-
 #[derive(Clone, Debug)]
 pub enum LogLevel {
     Info,
     Error,
     Success,
+    // A help table, see CommandRegistry::get_help_lines
+    Help,
+    // Why the AI would make its moves, shown in their own log tab
+    Thought,
 }
 
 #[derive(Clone, Debug)]
@@ -25,4 +27,12 @@ impl LogMessage {
      pub fn success(text: &str) -> Self {
         Self { level: LogLevel::Success, text: text.to_string() }
      }
+
+    pub fn help(text: &str) -> Self {
+        Self { level: LogLevel::Help, text: text.to_string() }
+    }
+
+    pub fn thought(text: &str) -> Self {
+        Self { level: LogLevel::Thought, text: text.to_string() }
+    }
 }
\ No newline at end of file
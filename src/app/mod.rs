@@ -1,7 +1,25 @@
+#[allow(clippy::module_inception)]
 pub mod app;
 mod ui;
 mod log;
+mod keymap;
+mod history;
+mod settings;
+mod pedia_view;
+mod pause_menu;
+mod replay_view;
+mod session_timer;
+mod frame_stats;
+mod turn_progress;
+mod catch_up;
+mod stats_export;
+mod crash_report;
+mod frontend;
+mod game_worker;
+#[cfg(feature = "http-api")]
+mod http_api;
 
 pub use app::App;
-use ui::UI;
-use log::{LogLevel, LogMessage};
\ No newline at end of file
+// For frontends other than the terminal one
+pub use frontend::{Frontend, InputEvent, KeyCode, KeyEvent, KeyModifiers};
+pub use settings::list_profiles;
@@ -0,0 +1,69 @@
+// Entries of the in-game system menu opened with Esc, in the order they are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseAction {
+    Resume,
+    Save,
+    Load,
+    Settings,
+    Help,
+    Quit,
+}
+
+impl PauseAction {
+    pub fn all() -> &'static [PauseAction] {
+        &[
+            PauseAction::Resume,
+            PauseAction::Save,
+            PauseAction::Load,
+            PauseAction::Settings,
+            PauseAction::Help,
+            PauseAction::Quit,
+        ]
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PauseAction::Resume => "Resume",
+            PauseAction::Save => "Save Game",
+            PauseAction::Load => "Load Game",
+            PauseAction::Settings => "Settings",
+            PauseAction::Help => "Help",
+            // TODO: Return to the main menu instead once it exists
+            PauseAction::Quit => "Quit Game",
+        }
+    }
+
+    // Picks the entry with the given number (starting at 1)
+    pub fn from_number(number: usize) -> Option<PauseAction> {
+        number.checked_sub(1).and_then(|idx| Self::all().get(idx)).copied()
+    }
+}
+
+// Selection state of the pause menu, game commands are blocked while it is open
+#[derive(Debug, Default)]
+pub struct PauseMenu {
+    selected: usize,
+}
+
+impl PauseMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn get_selected_action(&self) -> PauseAction {
+        PauseAction::all()[self.selected]
+    }
+
+    pub fn select_previous(&mut self) {
+        let count = PauseAction::all().len();
+        self.selected = (self.selected + count - 1) % count;
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % PauseAction::all().len();
+    }
+}
@@ -0,0 +1,55 @@
+use crate::game_core::Colonypedia;
+
+// Browsing state of the Colonypedia pane
+#[derive(Debug, Default)]
+pub struct PediaView {
+    selected: usize,
+    // Entries visited before following links, for going back
+    back_stack: Vec<usize>,
+}
+
+impl PediaView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select_previous(&mut self, colonypedia: &Colonypedia) {
+        let count = colonypedia.get_entries().len();
+        if count > 0 {
+            self.selected = (self.selected + count - 1) % count;
+        }
+    }
+
+    pub fn select_next(&mut self, colonypedia: &Colonypedia) {
+        let count = colonypedia.get_entries().len();
+        if count > 0 {
+            self.selected = (self.selected + 1) % count;
+        }
+    }
+
+    // Follows the link with the given number (starting at 1) on the selected page
+    pub fn follow_link(&mut self, colonypedia: &Colonypedia, number: usize) {
+        let Some(entry) = colonypedia.get_entries().get(self.selected) else {
+            return;
+        };
+
+        let target = number
+            .checked_sub(1)
+            .and_then(|idx| entry.get_all_links().get(idx).copied())
+            .and_then(|link| colonypedia.find_index(link));
+        if let Some(target) = target {
+            self.back_stack.push(self.selected);
+            self.selected = target;
+        }
+    }
+
+    pub fn go_back(&mut self) {
+        if let Some(previous) = self.back_stack.pop() {
+            self.selected = previous;
+        }
+    }
+}
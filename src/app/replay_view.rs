@@ -0,0 +1,133 @@
+use std::time::{Duration, Instant};
+
+use crate::game_core::{GameCore, GameCoreError, GameDataPaths, HintSettings, Replay, ReplayEntry};
+
+use super::log::LogMessage;
+
+// Time a turn stays on screen while the replay plays
+const TURN_INTERVAL: Duration = Duration::from_millis(1000);
+
+// Playback state of a finished game. The game is rebuilt from its commands whenever the shown
+// turn changes, seeking backwards simply replays fewer of them.
+#[derive(Debug, Clone)]
+pub struct ReplayView {
+    replay: Replay,
+    turn: u32,
+    last_turn: u32,
+    playing: bool,
+    last_step: Instant,
+}
+
+impl ReplayView {
+    pub fn new(replay: Replay) -> Result<Self, GameCoreError> {
+        let mut replay_view = ReplayView {
+            replay,
+            turn: 1,
+            last_turn: 1,
+            playing: false,
+            last_step: Instant::now(),
+        };
+
+        // The turn the game ended on is only known once all of its commands ran again
+        let (game_core, _) = replay_view.build_game(&HintSettings::default())?;
+        replay_view.last_turn = game_core.get_current_turn();
+        Ok(replay_view)
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn get_turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn get_last_turn(&self) -> u32 {
+        self.last_turn
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    // This and the seek methods return true when the shown turn changed
+    pub fn toggle_playing(&mut self) -> bool {
+        // Playing from the end starts over
+        let changed = !self.playing && self.turn == self.last_turn && self.seek(1);
+        self.playing = !self.playing;
+        self.last_step = Instant::now();
+        changed
+    }
+
+    pub fn seek(&mut self, turn: u32) -> bool {
+        let turn = turn.clamp(1, self.last_turn);
+        let changed = turn != self.turn;
+        self.turn = turn;
+        changed
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        self.seek(self.turn + 1)
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        self.seek(self.turn.saturating_sub(1))
+    }
+
+    pub fn seek_end(&mut self) -> bool {
+        self.seek(self.last_turn)
+    }
+
+    // Advances a turn once the interval passed while playing, stopping at the end
+    pub fn tick(&mut self) -> bool {
+        if !self.playing || self.last_step.elapsed() < TURN_INTERVAL {
+            return false;
+        }
+
+        self.last_step = Instant::now();
+        let changed = self.step_forward();
+        if self.turn == self.last_turn {
+            self.playing = false;
+        }
+        changed
+    }
+
+    // Replays the game on a new one up to the start of the shown turn, or to its very end on the
+    // last turn, together with the log the player saw
+    pub fn build_game(&self, hint_settings: &HintSettings) -> Result<(GameCore, Vec<LogMessage>), GameCoreError> {
+        let entries = self.replay.get_entries().iter().filter(|entry| {
+            entry.turn < self.turn || self.turn == self.last_turn
+        });
+        rebuild_game(entries, self.replay.get_name_seed(), hint_settings, false)
+    }
+}
+
+// Runs recorded commands again on a new game, also used to load a saved game. Reconciling leaves
+// out the commands that failed when they were played, so they can't take effect with other configs.
+pub fn rebuild_game<'a>(
+    entries: impl Iterator<Item = &'a ReplayEntry>,
+    name_seed: Option<u64>,
+    hint_settings: &HintSettings,
+    reconcile: bool,
+) -> Result<(GameCore, Vec<LogMessage>), GameCoreError> {
+    let paths = GameDataPaths::default();
+    let mut game_core = match name_seed {
+        Some(name_seed) => GameCore::new_with_name_seed(&paths, name_seed)?,
+        None => GameCore::new(&paths)?,
+    };
+    game_core.set_hint_settings(hint_settings.clone());
+
+    let mut logs = Vec::new();
+    for entry in entries.filter(|entry| !(reconcile && entry.failed)) {
+        logs.extend(game_core.take_notifications().iter().map(|text| LogMessage::info(text)));
+        logs.push(LogMessage::info(&format!("> {}", entry.command)));
+        match game_core.execute_command(&entry.command) {
+            Ok(Some(message)) => logs.push(LogMessage::success(&message)),
+            Ok(None) => logs.push(LogMessage::success("Command executed successfully.")),
+            Err(err) => logs.push(LogMessage::error(&err.to_string())),
+        }
+    }
+    logs.extend(game_core.take_notifications().iter().map(|text| LogMessage::info(text)));
+
+    Ok((game_core, logs))
+}
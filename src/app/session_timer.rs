@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use super::game_worker::GameWorker;
+
+// Measures the real time spent playing and hands it to the game in whole seconds. Time in the
+// pause menu doesn't count.
+#[derive(Debug)]
+pub struct SessionTimer {
+    last_tick: Instant,
+    session_time: Duration,
+    turn: u32,
+    turn_time: Duration,
+    // Played time not yet added to the game
+    uncounted: Duration,
+}
+
+impl SessionTimer {
+    pub fn new(turn: u32) -> Self {
+        SessionTimer {
+            last_tick: Instant::now(),
+            session_time: Duration::ZERO,
+            turn,
+            turn_time: Duration::ZERO,
+            uncounted: Duration::ZERO,
+        }
+    }
+
+    pub fn get_session_secs(&self) -> u64 {
+        self.session_time.as_secs()
+    }
+
+    pub fn get_turn_secs(&self) -> u64 {
+        self.turn_time.as_secs()
+    }
+
+    // Starts timing the turn over without recording the previous one, e.g. after loading a game
+    pub fn restart_turn(&mut self, turn: u32) {
+        self.turn = turn;
+        self.turn_time = Duration::ZERO;
+    }
+
+    pub fn tick(&mut self, game: &mut GameWorker, paused: bool) {
+        let now = Instant::now();
+        let elapsed = now - self.last_tick;
+        self.last_tick = now;
+
+        let turn = game.get_snapshot().current_turn;
+        if turn != self.turn {
+            let (previous_turn, turn_secs) = (self.turn, self.turn_time.as_secs());
+            game.run(move |game_core| game_core.record_turn_time(previous_turn, turn_secs));
+            self.restart_turn(turn);
+        }
+        if paused {
+            return;
+        }
+
+        self.session_time += elapsed;
+        self.turn_time += elapsed;
+        self.uncounted += elapsed;
+        let secs = self.uncounted.as_secs();
+        if secs > 0 {
+            game.run(move |game_core| game_core.add_playtime(secs));
+            self.uncounted -= Duration::from_secs(secs);
+        }
+    }
+}
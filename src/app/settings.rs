@@ -0,0 +1,405 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use crate::game_core::HintSettings;
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    // Profile names become directory names, so they are limited to letters, digits, - and _
+    InvalidProfile(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(e) => write!(f, "Failed to read settings file: {}", e),
+            SettingsError::Toml(e) => write!(f, "Failed to parse settings file (TOML): {}", e),
+            SettingsError::InvalidProfile(name) => write!(
+                f,
+                "Invalid profile name '{}', use only letters, digits, '-' and '_'",
+                name,
+            ),
+        }
+    }
+}
+
+impl Error for SettingsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SettingsError::Io(e) => Some(e),
+            SettingsError::Toml(e) => Some(e),
+            SettingsError::InvalidProfile(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for SettingsError {
+    fn from(err: io::Error) -> Self {
+        SettingsError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for SettingsError {
+    fn from(err: toml::de::Error) -> Self {
+        SettingsError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+const SETTINGS_FILE: &str = "data/settings.toml";
+const PROFILES_DIRECTORY: &str = "user/profiles";
+
+// Every named profile keeps its files in a directory of its own, so players sharing a machine
+// keep their preferences, history, statistics and saves apart
+pub fn get_profile_directory(profile: &str) -> Result<PathBuf, SettingsError> {
+    let valid = !profile.is_empty()
+        && profile.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(SettingsError::InvalidProfile(profile.to_string()));
+    }
+    Ok(Path::new(PROFILES_DIRECTORY).join(profile))
+}
+
+// The profile's own copy of a file in data/, the shared one when it has none
+pub fn get_profile_data_file(profile_directory: Option<&Path>, data_file: &str) -> PathBuf {
+    let shared = PathBuf::from(data_file);
+    let own = profile_directory.zip(shared.file_name()).map(|(directory, name)| directory.join(name));
+    own.filter(|own| own.exists()).unwrap_or(shared)
+}
+
+// Names of the profiles played so far, sorted
+pub fn list_profiles() -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(PROFILES_DIRECTORY) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut profiles = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            profiles.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistorySettings {
+    pub file: PathBuf,
+    pub max_entries: usize,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        HistorySettings {
+            file: PathBuf::from("user/history.txt"),
+            max_entries: 500,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileSettings {
+    pub file: PathBuf,
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        ProfileSettings {
+            file: PathBuf::from("user/profile.toml"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReplaySettings {
+    pub file: PathBuf,
+}
+
+impl Default for ReplaySettings {
+    fn default() -> Self {
+        ReplaySettings {
+            file: PathBuf::from("user/last_game.replay"),
+        }
+    }
+}
+
+// Games are saved as the commands that led to them, loading replays them on a new game
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SaveSettings {
+    pub file: PathBuf,
+    // Written when the game crashes, offered on the next start
+    pub recovery_file: PathBuf,
+}
+
+impl Default for SaveSettings {
+    fn default() -> Self {
+        SaveSettings {
+            file: PathBuf::from("user/savegame.replay"),
+            recovery_file: PathBuf::from("user/recovery.replay"),
+        }
+    }
+}
+
+// Only used when built with the http-api feature
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpApiSettings {
+    pub address: String,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        HttpApiSettings {
+            address: "127.0.0.1:7780".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoEndTurnSettings {
+    pub enabled: bool,
+    pub countdown_secs: u64,
+}
+
+impl Default for AutoEndTurnSettings {
+    fn default() -> Self {
+        AutoEndTurnSettings {
+            enabled: false,
+            countdown_secs: 3,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameSettings {
+    // One of the speeds in data/economy.toml, its default when not set
+    pub speed: Option<String>,
+    // Names the player and their planet from data/names.toml instead of Player 1 on Planet1
+    pub random_names: bool,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            speed: None,
+            random_names: true,
+        }
+    }
+}
+
+// Who the player is in new games, the game's defaults when not set
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerSettings {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+// Rules new games are created with, the standard ones when not set
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesSettings {
+    pub builder_mode: bool,
+    pub double_production: bool,
+    pub expensive_storage: bool,
+    pub event_frequency: Option<String>,
+}
+
+// The built-in AI looks at the player's empire every turn and its reasoning is logged, to debug
+// and tune the personalities in data/ai.toml
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AiThoughtsSettings {
+    pub enabled: bool,
+    // Id of a personality in data/ai.toml, the first one when not set
+    pub personality: Option<String>,
+}
+
+// Ends turns on a real-time interval regardless of what is left to do
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoAdvanceSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for AutoAdvanceSettings {
+    fn default() -> Self {
+        AutoAdvanceSettings {
+            enabled: false,
+            interval_secs: 60,
+        }
+    }
+}
+
+// Idle play, quitting saves the game and the time away is simulated as turns on the next start
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct OfflineSettings {
+    pub enabled: bool,
+    pub secs_per_turn: u64,
+    pub max_turns: u32,
+}
+
+impl Default for OfflineSettings {
+    fn default() -> Self {
+        OfflineSettings {
+            enabled: false,
+            secs_per_turn: 600,
+            max_turns: 24,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttentionSettings {
+    pub bell: bool,
+    pub desktop_notification: bool,
+    // Seconds without a key press after which the player counts as away
+    pub idle_secs: u64,
+}
+
+impl Default for AttentionSettings {
+    fn default() -> Self {
+        AttentionSettings {
+            bell: false,
+            desktop_notification: false,
+            idle_secs: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsFormat {
+    Csv,
+    // One JSON object per line
+    Json,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsExportSettings {
+    pub enabled: bool,
+    pub format: StatsFormat,
+    pub file: PathBuf,
+}
+
+impl Default for StatsExportSettings {
+    fn default() -> Self {
+        StatsExportSettings {
+            enabled: false,
+            format: StatsFormat::Csv,
+            file: PathBuf::from("user/stats.csv"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrashReportSettings {
+    pub directory: PathBuf,
+    pub log_lines: usize,
+    pub emergency_save: bool,
+}
+
+impl Default for CrashReportSettings {
+    fn default() -> Self {
+        CrashReportSettings {
+            directory: PathBuf::from("user/crashes"),
+            log_lines: 50,
+            emergency_save: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderSettings {
+    // How long the interface waits for input before updating timers and countdowns
+    pub tick_ms: u64,
+    // Redraws happen only after something changed, and at most this often
+    pub max_fps: u32,
+    pub debug_overlay: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            tick_ms: 100,
+            max_fps: 30,
+            debug_overlay: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub history: HistorySettings,
+    pub profile: ProfileSettings,
+    pub replay: ReplaySettings,
+    pub save: SaveSettings,
+    pub http_api: HttpApiSettings,
+    pub hints: HintSettings,
+    pub game: GameSettings,
+    pub player: PlayerSettings,
+    pub rules: RulesSettings,
+    pub auto_end_turn: AutoEndTurnSettings,
+    pub auto_advance: AutoAdvanceSettings,
+    pub ai_thoughts: AiThoughtsSettings,
+    pub offline: OfflineSettings,
+    pub attention: AttentionSettings,
+    pub stats_export: StatsExportSettings,
+    pub crash_reports: CrashReportSettings,
+    pub render: RenderSettings,
+}
+
+impl Settings {
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        let toml_content = fs::read_to_string(path)?;
+        let settings: Settings = toml::from_str(&toml_content)?;
+        Ok(settings)
+    }
+
+    // A named profile reads its own settings file when it has one, and everything it writes
+    // under user/ goes to its directory instead
+    pub fn load_for_profile(profile: Option<&str>) -> Result<Self, SettingsError> {
+        let Some(profile) = profile else {
+            return Self::load(Path::new(SETTINGS_FILE));
+        };
+        let directory = get_profile_directory(profile)?;
+        let mut settings = Self::load(&get_profile_data_file(Some(&directory), SETTINGS_FILE))?;
+        let user_files = [
+            &mut settings.history.file,
+            &mut settings.profile.file,
+            &mut settings.replay.file,
+            &mut settings.save.file,
+            &mut settings.save.recovery_file,
+            &mut settings.stats_export.file,
+            &mut settings.crash_reports.directory,
+        ];
+        for path in user_files {
+            if let Ok(relative) = path.strip_prefix("user") {
+                *path = directory.join(relative);
+            }
+        }
+        Ok(settings)
+    }
+}
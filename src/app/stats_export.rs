@@ -0,0 +1,96 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::game_core::{Resource, TurnRecord};
+
+use super::settings::{StatsExportSettings, StatsFormat};
+
+// Appends the empire's statistics of every ended turn to a file, as CSV rows or as one JSON
+// object per line, so games can be charted without replaying them
+#[derive(Debug)]
+pub struct StatsExport {
+    format: StatsFormat,
+    file: PathBuf,
+}
+
+impl StatsExport {
+    pub fn new(settings: &StatsExportSettings) -> Option<Self> {
+        settings.enabled.then(|| StatsExport {
+            format: settings.format,
+            file: settings.file.clone(),
+        })
+    }
+
+    pub fn append(&self, records: &[TurnRecord]) -> io::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut content = String::new();
+        // A CSV file gets its header when it is started
+        let is_new = fs::metadata(&self.file).map_or(true, |metadata| metadata.len() == 0);
+        if self.format == StatsFormat::Csv && is_new {
+            content.push_str(&Self::get_csv_header());
+            content.push('\n');
+        }
+        for record in records {
+            let line = match self.format {
+                StatsFormat::Csv => Self::get_csv_row(record),
+                StatsFormat::Json => Self::get_json_line(record),
+            };
+            content.push_str(&line);
+            content.push('\n');
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.file)?;
+        file.write_all(content.as_bytes())
+    }
+
+    fn get_csv_header() -> String {
+        let mut columns = vec!["turn".to_string(), "player".to_string(), "credits".to_string()];
+        columns.extend(Self::get_stored_resources().map(|resource| resource.to_string().to_lowercase()));
+        columns.extend(Resource::all().iter().map(|resource| format!("{}_production", resource.to_string().to_lowercase())));
+        columns.extend(["score".to_string(), "planets".to_string()]);
+        columns.join(",")
+    }
+
+    // Player names hold no commas or quotes, nothing in a row needs quoting
+    fn get_csv_row(record: &TurnRecord) -> String {
+        let mut values = vec![record.turn.to_string(), record.player.clone(), record.credits.get().to_string()];
+        values.extend(record.resources.iter().map(|(_, amount)| amount.get().to_string()));
+        values.extend(record.production.iter().map(|(_, amount)| amount.to_string()));
+        values.extend([record.score.to_string(), record.planet_count.to_string()]);
+        values.join(",")
+    }
+
+    // Player names are letters, digits, spaces, dashes and underscores, quoted by Debug just as
+    // JSON would
+    fn get_json_line(record: &TurnRecord) -> String {
+        let resources: Vec<String> = record.resources
+            .iter()
+            .map(|(resource, amount)| format!("\"{}\":{}", resource, amount.get()))
+            .collect();
+        let production: Vec<String> = record.production
+            .iter()
+            .map(|(resource, amount)| format!("\"{}\":{}", resource, amount))
+            .collect();
+        format!(
+            "{{\"turn\":{},\"player\":{:?},\"credits\":{},\"resources\":{{{}}},\"production\":{{{}}},\"score\":{},\"planets\":{}}}",
+            record.turn,
+            record.player,
+            record.credits.get(),
+            resources.join(","),
+            production.join(","),
+            record.score,
+            record.planet_count,
+        )
+    }
+
+    fn get_stored_resources() -> impl Iterator<Item = &'static Resource> {
+        Resource::all().iter().filter(|resource| **resource != Resource::Credits)
+    }
+}
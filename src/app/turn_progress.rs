@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+
+// A command line that ends the turn, run one command at a time on the game worker so the interface
+// can show which turn is being processed instead of looking frozen
+// TODO: Report progress for each phase of the turn, sent by the worker like its other events
+#[derive(Debug)]
+pub struct TurnProgress {
+    commands: VecDeque<String>,
+    // Turn the command line was submitted on
+    start_turn: u32,
+    processed: usize,
+    // Set once a frame showing the progress was drawn
+    shown: bool,
+}
+
+impl TurnProgress {
+    pub fn new(command: &str, start_turn: u32) -> Self {
+        TurnProgress {
+            commands: command
+                .split(';')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .map(str::to_string)
+                .collect(),
+            start_turn,
+            processed: 0,
+            shown: false,
+        }
+    }
+
+    pub fn get_start_turn(&self) -> u32 {
+        self.start_turn
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn set_shown(&mut self) {
+        self.shown = true;
+    }
+
+    // The next command, once the progress before it was drawn
+    pub fn take_next_command(&mut self) -> Option<String> {
+        if !self.shown {
+            return None;
+        }
+        self.shown = false;
+        self.processed += 1;
+        self.commands.pop_front()
+    }
+
+    // The spinner moves on with every command run
+    pub fn get_label(&self, current_turn: u32) -> String {
+        const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+        let total = self.processed + self.commands.len();
+        format!(
+            "{} Processing turn {}... command {}/{}, keys wait until it is done",
+            SPINNER[self.processed % SPINNER.len()],
+            current_turn,
+            (self.processed + 1).min(total),
+            total,
+        )
+    }
+}
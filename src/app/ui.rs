@@ -1,14 +1,88 @@
 use ratatui::{
-    widgets::{Block, Borders, Paragraph, List, ListItem},
+    widgets::{Block, Borders, Clear, LineGauge, Paragraph, List, ListItem, ListState, Padding, Wrap},
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     Frame,
     text::{Line, Span, Text},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
 };
 
-use crate::game_core::{PlanetStatus, Resource};
+use crate::game_core::{format_playtime, Alert, AlertKind, Choice, InputCheck, MissionState, MissionStatus, PediaCategory, PediaEntry, PlanetStatus, PlayerColor, Resource, ResourceAmount, TutorialStatus};
 
+use super::keymap::KeyHint;
 use super::log::{LogLevel, LogMessage};
+use super::pause_menu::PauseAction;
+
+pub struct UiState<'a> {
+    pub command_input: &'a str,
+    // Cost of the command being typed, if it has one
+    pub command_preview: Option<&'a str>,
+    // Problem a dry run found with the command being typed, shown instead of its cost
+    pub input_check: Option<&'a InputCheck>,
+    // Arguments the command being typed still needs, shown after the cursor
+    pub arg_hints: Option<&'a str>,
+    pub show_cursor: bool,
+    pub command_input_focused: bool,
+    pub status_focused: bool,
+    pub current_turn: u32,
+    pub player_name: &'a str,
+    pub player_color: PlayerColor,
+    pub credits: ResourceAmount,
+    pub credits_income: i64,
+    pub artifacts: &'a [String],
+    pub planet_status: Option<&'a PlanetStatus>,
+    // 0 is the planet's surface, higher values its sub-locations in order
+    pub selected_site: usize,
+    // Highlighted while the status pane is focused
+    pub status_section: StatusSection,
+    pub logs: &'a [LogMessage],
+    pub key_hints: &'a [KeyHint],
+    pub tutorial_status: Option<&'a TutorialStatus>,
+    // Set while the Missions pane is shown
+    pub missions: Option<&'a [MissionStatus]>,
+    // Counted in the status pane title, listed while the Alerts pane is shown
+    pub alerts: &'a [Alert],
+    pub show_alerts: bool,
+    // The log shows only the AI's thoughts
+    pub show_thoughts: bool,
+    pub colonypedia: Option<PediaState<'a>>,
+    // Set while a decision dialog is shown
+    pub choice: Option<&'a Choice>,
+    // Set while the pause menu is open, the selected entry
+    pub pause_menu: Option<usize>,
+    // Not set while watching a replay
+    pub playtime: Option<PlaytimeState>,
+    // Frame statistics, set when enabled in the settings
+    pub debug_overlay: Option<String>,
+    // Set while watching a replay, it takes the place of the command input
+    pub replay: Option<ReplayState>,
+}
+
+// Part of the status pane the arrow keys are on, building rows include the construction queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSection {
+    Planet,
+    Site,
+    Building(usize),
+    Resource(usize),
+}
+
+// Real time played, in seconds
+pub struct PlaytimeState {
+    pub session_secs: u64,
+    pub total_secs: u64,
+    pub turn_secs: u64,
+}
+
+pub struct ReplayState {
+    pub turn: u32,
+    pub last_turn: u32,
+    pub playing: bool,
+}
+
+pub struct PediaState<'a> {
+    pub entries: &'a [PediaEntry],
+    pub selected: usize,
+}
 
 pub struct UI {}
 
@@ -17,23 +91,13 @@ impl UI {
         UI {}
     }
 
-    pub fn draw(
-        &self,
-        frame: &mut Frame,
-        command_input: &str,
-        show_cursor: bool,
-        command_input_focused: bool,
-        status_focused: bool,
-        current_turn: u32,
-        player_name: &str,
-        planet_status: Option<&PlanetStatus>,
-        logs: &[LogMessage],
-    ) {
+    pub fn draw(&self, frame: &mut Frame, state: &UiState) {
         let main_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(75),
-                Constraint::Percentage(25),
+                Constraint::Min(3),
+                Constraint::Length(1),
             ])
             .split(frame.area());
 
@@ -55,45 +119,70 @@ impl UI {
         // --- Render Widgets ---
 
         // 1. Game Status (Top-Left)
-        self.render_game_status(
-            frame,
-            top_layout[0],
-            status_focused,
-            current_turn,
-            player_name,
-            planet_status,
-        );
+        self.render_game_status(frame, top_layout[0], state);
 
         // 2. Message Log (Top-Left)
-        self.render_log(frame, top_layout[1], logs);
-
-        // 4. Command Input (Bottom)
-        self.render_command_input(
-            frame,
-            bottom_layout[0],
-            command_input,
-            show_cursor,
-            command_input_focused,
-        );
+        self.render_log(frame, top_layout[1], state.logs, state.show_thoughts, state.player_name, state.player_color);
+
+        // 4. Command Input or Replay Controls (Bottom)
+        match &state.replay {
+            Some(replay_state) => self.render_replay_controls(frame, bottom_layout[0], replay_state),
+            None => self.render_command_input(frame, bottom_layout[0], state),
+        }
+
+        // 5. Shortcut Hints (Very Bottom)
+        self.render_key_hints(frame, main_layout[2], state.key_hints);
+
+        // 6. Tutorial (Over the bottom of the log, newest logs stay visible)
+        if let Some(tutorial_status) = state.tutorial_status {
+            self.render_tutorial(frame, top_layout[1], tutorial_status);
+        }
+
+        // 7. Missions or Alerts (Over the whole log)
+        if let Some(missions) = state.missions {
+            self.render_missions(frame, top_layout[1], missions);
+        }
+        if state.show_alerts {
+            self.render_alerts(frame, top_layout[1], state.alerts);
+        }
+
+        // 8. Colonypedia (Over the whole top area)
+        if let Some(pedia_state) = &state.colonypedia {
+            self.render_colonypedia(frame, main_layout[0], pedia_state);
+        }
+
+        // 9. Decision dialog (Centered over everything)
+        if let Some(choice) = state.choice {
+            self.render_choice(frame, frame.area(), choice);
+        }
+
+        // 10. Pause menu (Centered over everything, even a decision)
+        if let Some(selected) = state.pause_menu {
+            self.render_pause_menu(frame, frame.area(), selected);
+        }
+
+        // 11. Debug overlay (Top-Right corner)
+        if let Some(label) = &state.debug_overlay {
+            let area = frame.area();
+            let width = (label.len() as u16).min(area.width);
+            let overlay_area = Rect { x: area.x + area.width - width, y: area.y, width, height: 1.min(area.height) };
+            frame.render_widget(Paragraph::new(label.as_str()).style(Style::default().fg(Color::Black).bg(Color::Yellow)), overlay_area);
+        }
     }
 
-    fn render_game_status(
-        &self,
-        frame: &mut Frame,
-        area: Rect,
-        is_focused: bool,
-        current_turn: u32,
-        player_name: &str,
-        planet_status: Option<&PlanetStatus>,
-    ) {
-        let border_style = if is_focused {
+    fn render_game_status(&self, frame: &mut Frame, area: Rect, state: &UiState) {
+        let border_style = if state.status_focused {
             Style::default().fg(Color::Cyan)
         } else {
             Style::default()
         };
 
+        let title = match state.alerts.len() {
+            0 => "Game Status".to_string(),
+            count => format!("Game Status - {} alert(s)", count),
+        };
         let status_block = Block::default()
-            .title("Game Status")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(border_style);
 
@@ -105,7 +194,12 @@ impl UI {
             .constraints([
                 Constraint::Length(1), // Current Turn
                 Constraint::Length(1), // Player Name
+                Constraint::Length(1), // Credits
+                Constraint::Length(if state.artifacts.is_empty() { 0 } else { 1 }), // Artifacts
                 Constraint::Length(1), // Planet Name + Arrows
+                Constraint::Length(1), // Sub-location Selector
+                Constraint::Length(1), // Population + Tax Rate
+                Constraint::Length(1), // Morale + Governor
                 Constraint::Min(1),    // Building List (takes remaining space)
                 Constraint::Length(1), // Production Rates Title
                 Constraint::Length(1), // Energy Prod/Storage
@@ -114,89 +208,260 @@ impl UI {
             ])
             .split(status_block.inner(area)); // Apply layout *inside* the block
         
-        let turn_line = Line::from(format!("Turn: {}", current_turn)).alignment(Alignment::Center);
+        let turn_text = match &state.playtime {
+            Some(playtime) => format!(
+                "Turn: {} ({}) | Session {} | Total {}",
+                state.current_turn,
+                format_playtime(playtime.turn_secs),
+                format_playtime(playtime.session_secs),
+                format_playtime(playtime.total_secs),
+            ),
+            None => format!("Turn: {}", state.current_turn),
+        };
+        let turn_line = Line::from(turn_text).alignment(Alignment::Center);
         frame.render_widget(Paragraph::new(turn_line), status_layout[0]);
     
         // Player Name (Centered)
-        let player_line = Line::from(player_name).alignment(Alignment::Center);
+        let player_line = Line::styled(state.player_name, get_player_style(state.player_color))
+            .alignment(Alignment::Center);
         frame.render_widget(Paragraph::new(player_line), status_layout[1]);
 
+        // Credits belong to the player, not the planet
+        let credits_line = Line::from(format!("Credits: {} ({:+}/t)", state.credits, state.credits_income))
+            .alignment(Alignment::Center);
+        frame.render_widget(Paragraph::new(credits_line), status_layout[2]);
+
+        // Artifacts belong to the player as well
+        if !state.artifacts.is_empty() {
+            let artifacts_line = Line::from(vec![
+                Span::raw("Artifacts: "),
+                Span::styled(state.artifacts.join(", "), Style::default().fg(Color::Magenta)),
+            ]).alignment(Alignment::Center);
+            frame.render_widget(Paragraph::new(artifacts_line), status_layout[3]);
+        }
+
         // --- Use data from planet_status if Some, otherwise show defaults ---
-        if let Some(status) = planet_status {
+        if let Some(status) = state.planet_status {
+            let planet_details = match &status.specialization {
+                Some((specialization, _)) => format!("{}, {}", status.size, specialization),
+                None => status.size.clone(),
+            };
             let planet_display = if status.planet_count > 1 {
-                format!("< {} >", status.planet_name)
+                format!("< {} ({}) >", status.planet_name, planet_details)
             } else {
-                status.planet_name.clone()
+                format!("{} ({})", status.planet_name, planet_details)
+            };
+            let selected_section = state.status_focused.then_some(state.status_section);
+            let selected_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+            let planet_style = if selected_section == Some(StatusSection::Planet) { selected_style } else { Style::default() };
+            let planet_line = Line::styled(planet_display, planet_style).alignment(Alignment::Center);
+            frame.render_widget(Paragraph::new(planet_line), status_layout[4]);
+
+            // Surface and sub-locations, the selected one decides which buildings are listed
+            if !status.sub_locations.is_empty() {
+                let site_names = std::iter::once("Surface")
+                    .chain(status.sub_locations.iter().map(|sub_location| sub_location.name.as_str()));
+                let mut site_spans = Vec::new();
+                for (idx, name) in site_names.enumerate() {
+                    if idx > 0 {
+                        site_spans.push(Span::raw(" "));
+                    }
+                    let style = if idx == state.selected_site {
+                        Style::default().fg(Color::Black).bg(Color::Cyan)
+                    } else {
+                        Style::default()
+                    };
+                    site_spans.push(Span::styled(format!(" {} ", name), style));
+                }
+                let site_style = if selected_section == Some(StatusSection::Site) {
+                    Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                } else {
+                    Style::default()
+                };
+                let site_line = Line::from(site_spans).style(site_style).alignment(Alignment::Center);
+                frame.render_widget(Paragraph::new(site_line), status_layout[5]);
+            }
+
+            // Slots of the selected site, sub-locations without a limit show the surface's
+            let (used_slots, total_slots) = state.selected_site.checked_sub(1)
+                .and_then(|idx| status.sub_locations.get(idx))
+                .and_then(|sub_location| sub_location.slots)
+                .unwrap_or(status.building_slots);
+            let population_line = Line::from(format!(
+                "Population: {}/{} | Tax: {}% | Slots: {}/{}",
+                status.population, status.housing, status.tax_rate, used_slots, total_slots,
+            )).alignment(Alignment::Center);
+            frame.render_widget(Paragraph::new(population_line), status_layout[6]);
+
+            let (face, morale_color) = match status.morale {
+                60.. => (":)", Color::Green),
+                35..=59 => (":|", Color::Yellow),
+                _ => (":(", Color::Red),
             };
-            let planet_line = Line::from(planet_display).alignment(Alignment::Center);
-            frame.render_widget(Paragraph::new(planet_line), status_layout[2]);
+            let mut morale_spans = vec![
+                Span::raw("Morale: "),
+                Span::styled(format!("{} {}%", face, status.morale), Style::default().fg(morale_color)),
+            ];
+            if status.on_strike {
+                morale_spans.push(Span::styled(" ON STRIKE", Style::default().fg(Color::Red)));
+            }
+            if let Some((governor, level)) = &status.governor {
+                morale_spans.push(Span::raw(format!(" | Governor: {} (Lvl {})", governor, level)));
+            }
+            let morale_line = Line::from(morale_spans).alignment(Alignment::Center);
+            frame.render_widget(Paragraph::new(morale_line), status_layout[7]);
 
-            // Building List
-            let building_items: Vec<ListItem> = status
-                .buildings
+            // Building List of the selected site, followed by the planet's construction queue
+            let (buildings_title, buildings) = match state.selected_site.checked_sub(1)
+                .and_then(|idx| status.sub_locations.get(idx))
+            {
+                Some(sub_location) => (format!("Buildings on {}", sub_location.name), &sub_location.buildings),
+                None => ("Buildings".to_string(), &status.buildings),
+            };
+            let mut building_items: Vec<ListItem> = buildings
                 .iter()
                 .map(|(name, level)| ListItem::new(format!("{} Lvl {}", name, level)))
                 .collect();
+            building_items.extend(status.construction_queue.iter().enumerate().map(|(idx, construction)| {
+                // Levels paid on start wait at the front of the queue until they can be afforded
+                let progress = if idx == 0 && !construction.is_reserved {
+                    "waiting for resources".to_string()
+                } else {
+                    format!("{}t", construction.remaining_turns)
+                };
+                let site = construction.sub_location
+                    .as_ref()
+                    .and_then(|id| status.sub_locations.iter().find(|sub_location| &sub_location.id == id))
+                    .map(|sub_location| format!(" on {}", sub_location.name))
+                    .unwrap_or_default();
+                ListItem::new(format!(
+                    "Building {} Lvl {}{} ({})",
+                    construction.building.get_name(),
+                    construction.level,
+                    site,
+                    progress,
+                )).style(Style::default().fg(Color::Yellow))
+            }));
             let building_list = List::new(building_items)
-                .block(Block::default().title("Buildings"));
-            frame.render_widget(building_list, status_layout[3]);
+                .block(Block::default().title(buildings_title))
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol(">> ");
+            let mut building_list_state = ListState::default();
+            if let Some(StatusSection::Building(row)) = selected_section {
+                building_list_state.select(Some(row));
+            }
+            frame.render_stateful_widget(building_list, status_layout[8], &mut building_list_state);
 
             // Production & Storage Title
             frame.render_widget(
                 Paragraph::new("Production / Storage").alignment(Alignment::Center),
-                status_layout[4]
+                status_layout[9]
             );
 
             // Helper closure to get prod/storage safely
             let get_res_info = |resource: Resource| {
-                let prod = status.production.get(&resource).cloned().unwrap_or(0);
-                let (current, capacity) = status.storage.get(&resource).cloned().unwrap_or((0, 0));
-                (prod, current, capacity)
+                let prod = status.production.get(&resource).cloned().unwrap_or_default();
+                let (current, capacity) = status.storage.get(&resource).cloned().unwrap_or_default();
+                let full_in = match status.turns_until_full.get(&resource) {
+                    Some(0) => " (full)".to_string(),
+                    Some(turns) => format!(" (full in {}t)", turns),
+                    None => String::new(),
+                };
+                // What queued constructions set aside is no longer free to spend
+                let reserved = match status.reserved.get(&resource) {
+                    Some(amount) => format!(
+                        " | {} free, {} reserved",
+                        (current - *amount).get_abbreviated(),
+                        amount.get_abbreviated(),
+                    ),
+                    None => String::new(),
+                };
+                (prod, current.get_abbreviated(), capacity.get_abbreviated(), format!("{}{}", reserved, full_in))
             };
 
-            let (energy_prod, energy_curr, energy_cap) = get_res_info(Resource::Energy);
-            let (min_prod, min_curr, min_cap) = get_res_info(Resource::Minerals);
-            let (gas_prod, gas_curr, gas_cap) = get_res_info(Resource::Gas);
+            let (energy_prod, energy_curr, energy_cap, energy_full) = get_res_info(Resource::Energy);
+            let (min_prod, min_curr, min_cap, min_full) = get_res_info(Resource::Minerals);
+            let (gas_prod, gas_curr, gas_cap, gas_full) = get_res_info(Resource::Gas);
 
 
             // Display Production & Storage
+            let resource_style = |row: usize| {
+                if selected_section == Some(StatusSection::Resource(row)) {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                }
+            };
             frame.render_widget(
-                Paragraph::new(format!("Energy:   +{}/t | {}/{}", energy_prod, energy_curr, energy_cap)),
-                status_layout[5]
+                Paragraph::new(format!("Energy:   +{}/t | {}/{}{}", energy_prod, energy_curr, energy_cap, energy_full))
+                    .style(resource_style(0)),
+                status_layout[10]
             );
             frame.render_widget(
-                Paragraph::new(format!("Minerals: +{}/t | {}/{}", min_prod, min_curr, min_cap)),
-                status_layout[6]
+                Paragraph::new(format!("Minerals: +{}/t | {}/{}{}", min_prod, min_curr, min_cap, min_full))
+                    .style(resource_style(1)),
+                status_layout[11]
             );
             frame.render_widget(
-                Paragraph::new(format!("Gas:      +{}/t | {}/{}", gas_prod, gas_curr, gas_cap)),
-                status_layout[7]
+                Paragraph::new(format!("Gas:      +{}/t | {}/{}{}", gas_prod, gas_curr, gas_cap, gas_full))
+                    .style(resource_style(2)),
+                status_layout[12]
             );
 
         } else {
             let placeholder = Paragraph::new("No planet data available.")
                 .alignment(Alignment::Center);
-            frame.render_widget(placeholder, status_layout[2]);
+            frame.render_widget(placeholder, status_layout[4]);
         }
 
         frame.render_widget(status_block, area);
     }
 
-    fn render_log(&self, frame: &mut Frame, area: Rect, logs: &[LogMessage]) {
-        let log_block = Block::default().title("Log").borders(Borders::ALL);
+    // The player's name is shown in their color wherever a message mentions them
+    fn render_log(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        logs: &[LogMessage],
+        show_thoughts: bool,
+        player_name: &str,
+        player_color: PlayerColor,
+    ) {
+        let title = if show_thoughts { "Log - AI Thoughts" } else { "Log" };
+        let log_block = Block::default().title(title).borders(Borders::ALL);
 
         let log_items: Vec<ListItem> = logs
             .iter()
+            .filter(|log| !show_thoughts || matches!(log.level, LogLevel::Thought))
             .rev() // Display newest logs first
-            .map(|log| {
+            .flat_map(|log| {
                 let style = match log.level {
                     LogLevel::Info => Style::default().fg(Color::White),
                     LogLevel::Error => Style::default().fg(Color::Red),
                     LogLevel::Success => Style::default().fg(Color::Green),
+                    LogLevel::Thought => Style::default().fg(Color::Magenta),
+                    LogLevel::Help => {
+                        let width = area.width.saturating_sub(2) as usize;
+                        return log.text.lines().flat_map(|line| get_help_lines(line, width)).collect::<Vec<Line>>();
+                    }
                 };
-                let line = Line::from(Span::styled(log.text.clone(), style));
-                ListItem::new(line)
+                log.text
+                    .lines()
+                    .map(|line| {
+                        let mut spans = Vec::new();
+                        for (idx, part) in line.split(player_name).enumerate() {
+                            if idx > 0 {
+                                spans.push(Span::styled(player_name.to_string(), get_player_style(player_color)));
+                            }
+                            spans.push(Span::styled(part.to_string(), style));
+                        }
+                        Line::from(spans)
+                    })
+                    .collect()
             })
+            // A line per item, so a message taller than the pane, e.g. help, is cut off at the
+            // bottom rather than left out
+            .map(ListItem::new)
             .collect();
 
         let log_list = List::new(log_items).block(log_block)
@@ -206,29 +471,365 @@ impl UI {
         frame.render_widget(log_list, area);
     }
 
-    fn render_command_input(
-        &self,
-        frame: &mut Frame,
-        area: Rect,
-        input: &str,
-        show_cursor: bool,
-        is_focused: bool,
-    ) {
-        let cursor_char = if show_cursor { "|" } else { " " };
+    fn render_command_input(&self, frame: &mut Frame, area: Rect, state: &UiState) {
+        let cursor_char = if state.show_cursor { "|" } else { " " };
 
-        let border_style = if is_focused {
+        let border_style = if state.command_input_focused {
             Style::default().fg(Color::Cyan)
         } else {
             Style::default()
         };
 
+        let title = match (state.input_check, state.command_preview) {
+            (Some(check), _) => {
+                let color = match check {
+                    InputCheck::Incomplete(_) => Color::Yellow,
+                    InputCheck::Invalid(_) => Color::Red,
+                };
+                Line::from(vec![Span::raw("Command - "), Span::styled(format!("● {}", check), Style::default().fg(color))])
+            }
+            (None, Some(preview)) => Line::from(format!("Command - {}", preview)),
+            (None, None) => Line::from("Command"),
+        };
         let input_block = Block::default()
-            .title("Command")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(border_style);
 
-        let input_paragraph = Paragraph::new(Text::raw(format!("> {}{}", input, cursor_char)))
-            .block(input_block);
+        let input_line = Line::from(vec![
+            Span::raw(format!("> {}{}", state.command_input, cursor_char)),
+            Span::styled(state.arg_hints.unwrap_or_default().to_string(), Style::default().fg(Color::DarkGray)),
+        ]);
+        let input_paragraph = Paragraph::new(input_line).block(input_block);
         frame.render_widget(input_paragraph, area);
     }
-}
\ No newline at end of file
+
+    fn render_replay_controls(&self, frame: &mut Frame, area: Rect, state: &ReplayState) {
+        let replay_block = Block::default()
+            .title("Replay - Space Play/Pause | Left/Right Step | Home/End Seek | Up/Down Planet/Site | Esc Quit")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta));
+        let inner_area = replay_block.inner(area);
+        frame.render_widget(replay_block, area);
+
+        let (symbol, label) = if state.playing { (">", "Playing") } else { ("||", "Paused") };
+        let ratio = if state.last_turn > 1 {
+            (state.turn - 1) as f64 / (state.last_turn - 1) as f64
+        } else {
+            1.0
+        };
+        let gauge = LineGauge::default()
+            .filled_style(Style::default().fg(Color::Magenta))
+            .unfilled_style(Style::default().fg(Color::DarkGray))
+            .label(format!("{} {} - turn {}/{}", symbol, label, state.turn, state.last_turn))
+            .ratio(ratio);
+        frame.render_widget(gauge, Rect { height: 1, ..inner_area });
+    }
+
+    fn render_tutorial(&self, frame: &mut Frame, log_area: Rect, tutorial_status: &TutorialStatus) {
+        let height = log_area.height.saturating_sub(2).min(6);
+        let area = Rect {
+            x: log_area.x + 1,
+            y: (log_area.y + log_area.height).saturating_sub(height + 1),
+            width: log_area.width.saturating_sub(2),
+            height,
+        };
+
+        let tutorial_block = Block::default()
+            .title(format!("Tutorial {}/{}", tutorial_status.step, tutorial_status.step_count))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let tutorial_paragraph = Paragraph::new(tutorial_status.instruction.as_str())
+            .wrap(Wrap { trim: true })
+            .block(tutorial_block);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(tutorial_paragraph, area);
+    }
+
+    fn render_alerts(&self, frame: &mut Frame, area: Rect, alerts: &[Alert]) {
+        let alerts_block = Block::default()
+            .title("Alerts - 1-9 Jump to Planet | F7/Esc Close")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let lines: Vec<Line> = if alerts.is_empty() {
+            vec![Line::styled("Nothing needs your attention.", Style::default().fg(Color::Green))]
+        } else {
+            alerts
+                .iter()
+                .enumerate()
+                .map(|(idx, alert)| {
+                    let color = match alert.kind {
+                        AlertKind::Strike => Color::Red,
+                        AlertKind::StorageFull(_) => Color::Yellow,
+                        AlertKind::EmptyQueue => Color::White,
+                    };
+                    Line::from(vec![
+                        Span::raw(format!("[{}] ", idx + 1)),
+                        Span::styled(alert.to_string(), Style::default().fg(color)),
+                    ])
+                })
+                .collect()
+        };
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }).block(alerts_block), area);
+    }
+
+    fn render_missions(&self, frame: &mut Frame, area: Rect, missions: &[MissionStatus]) {
+        let missions_block = Block::default()
+            .title("Missions - F4/Esc Close")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner_area = missions_block.inner(area);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(missions_block, area);
+
+        // Every mission takes a title line, a progress bar and a blank line
+        let mission_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(missions.iter().map(|_| Constraint::Length(3)).chain([Constraint::Min(0)]))
+            .split(inner_area);
+
+        for (mission, mission_area) in missions.iter().zip(mission_layout.iter()) {
+            let (state_label, color) = match mission.state {
+                MissionState::Active => (
+                    mission.deadline_turn.map(|turn| format!("by turn {}", turn)).unwrap_or_default(),
+                    Color::Yellow,
+                ),
+                MissionState::Completed => ("completed".to_string(), Color::Green),
+                MissionState::Failed => ("failed".to_string(), Color::Red),
+            };
+            let title_line = Line::from(vec![
+                Span::styled(mission.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" - {} ", mission.goal)),
+                Span::styled(state_label, Style::default().fg(color)),
+            ]);
+            frame.render_widget(
+                Paragraph::new(title_line),
+                Rect { height: 1, ..*mission_area },
+            );
+
+            let ratio = if mission.target > 0 {
+                (mission.progress as f64 / mission.target as f64).min(1.0)
+            } else {
+                1.0
+            };
+            let gauge = LineGauge::default()
+                .filled_style(Style::default().fg(color))
+                .unfilled_style(Style::default().fg(Color::DarkGray))
+                .label(format!("{}/{}", mission.progress, mission.target))
+                .ratio(ratio);
+            frame.render_widget(
+                gauge,
+                Rect { y: mission_area.y + 1, height: 1, ..*mission_area },
+            );
+        }
+    }
+
+    fn render_choice(&self, frame: &mut Frame, area: Rect, choice: &Choice) {
+        let title = format!("{} - 1-{} Choose | Esc Later", choice.title, choice.options.len());
+        let labels: Vec<&str> = choice.options.iter().map(|option| option.label.as_str()).collect();
+        self.render_dialog(frame, area, &title, &choice.text, &labels, None);
+    }
+
+    fn render_pause_menu(&self, frame: &mut Frame, area: Rect, selected: usize) {
+        let title = format!("Paused - 1-{} or Enter Choose | Esc Resume", PauseAction::all().len());
+        let labels: Vec<&str> = PauseAction::all().iter().map(|action| action.get_label()).collect();
+        self.render_dialog(frame, area, &title, "The game waits until you resume.", &labels, Some(selected));
+    }
+
+    // A modal box with numbered options, optionally highlighting the one selected with the arrows
+    fn render_dialog(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        text: &str,
+        labels: &[&str],
+        selected: Option<usize>,
+    ) {
+        let dialog_block = Block::default()
+            .title(title.to_string())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .padding(Padding::horizontal(1));
+
+        let mut lines: Vec<Line> = text.lines().map(Line::from).collect();
+        lines.push(Line::from(""));
+        for (idx, label) in labels.iter().enumerate() {
+            let label_style = if selected == Some(idx) {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("[{}] ", idx + 1), Style::default().fg(Color::Yellow)),
+                Span::styled(label.to_string(), label_style),
+            ]));
+        }
+
+        // Sized to the text, wrapped lines get a little extra room
+        let width = area.width.min(64);
+        let height = (lines.len() as u16 + 4).min(area.height);
+        let dialog_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        frame.render_widget(Clear, dialog_area);
+        frame.render_widget(
+            Paragraph::new(Text::from(lines)).block(dialog_block).wrap(Wrap { trim: true }),
+            dialog_area,
+        );
+    }
+
+    fn render_colonypedia(&self, frame: &mut Frame, area: Rect, state: &PediaState) {
+        let pedia_block = Block::default()
+            .title("Colonypedia - Up/Down Browse | 1-9 Follow Link | Backspace Back | Esc Close")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let pedia_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(30),
+                Constraint::Percentage(70),
+            ])
+            .split(pedia_block.inner(area));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(pedia_block, area);
+
+        // Entry list with a header line before each category
+        let header_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let mut items = Vec::new();
+        let mut selected_item = 0;
+        let mut last_category = None;
+        for (idx, entry) in state.entries.iter().enumerate() {
+            if last_category != Some(entry.category) {
+                items.push(ListItem::new(Line::from(Span::styled(entry.category.to_string(), header_style))));
+                last_category = Some(entry.category);
+            }
+            if idx == state.selected {
+                selected_item = items.len();
+            }
+            items.push(ListItem::new(format!("  {}", entry.title)));
+        }
+
+        let entry_list = List::new(items)
+            .block(Block::default().borders(Borders::RIGHT))
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+        let mut list_state = ListState::default().with_selected(Some(selected_item));
+        frame.render_stateful_widget(entry_list, pedia_layout[0], &mut list_state);
+
+        // Selected entry
+        let Some(entry) = state.entries.get(state.selected) else {
+            return;
+        };
+
+        let mut lines = vec![
+            Line::from(Span::styled(entry.title.clone(), header_style)),
+            Line::from(""),
+        ];
+        if entry.category == PediaCategory::Command {
+            let width = pedia_layout[1].width.saturating_sub(2) as usize;
+            lines.extend(entry.lines.iter().flat_map(|line| get_help_lines(line, width)));
+        } else {
+            lines.extend(entry.lines.iter().map(|line| Line::from(line.as_str())));
+        }
+
+        let get_title = |id: &str| {
+            state.entries
+                .iter()
+                .find(|entry| entry.id == id)
+                .map_or(id.to_string(), |entry| entry.title.clone())
+        };
+        let link_style = Style::default().fg(Color::Cyan);
+        for (number, link) in entry.get_all_links().into_iter().enumerate() {
+            if number == 0 {
+                lines.push(Line::from(""));
+                lines.push(Line::from("Related:"));
+            }
+            let direction = if entry.links.iter().any(|own_link| own_link == link) { "" } else { " (refers here)" };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  [{}] {}", number + 1, get_title(link)), link_style),
+                Span::raw(direction),
+            ]));
+        }
+
+        let entry_paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(entry_paragraph, pedia_layout[1]);
+    }
+
+    fn render_key_hints(&self, frame: &mut Frame, area: Rect, key_hints: &[KeyHint]) {
+        let key_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+
+        let mut spans = Vec::new();
+        for hint in key_hints {
+            spans.push(Span::styled(format!(" {} ", hint.key), key_style));
+            spans.push(Span::raw(format!(" {}  ", hint.label)));
+        }
+        spans.push(Span::styled(" Tab ", key_style));
+        spans.push(Span::raw(" Switch Pane  "));
+        spans.push(Span::styled(" Esc ", key_style));
+        spans.push(Span::raw(" Menu"));
+
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
+
+fn get_player_style(color: PlayerColor) -> Style {
+    let color = match color {
+        PlayerColor::Red => Color::Red,
+        PlayerColor::Green => Color::Green,
+        PlayerColor::Yellow => Color::Yellow,
+        PlayerColor::Blue => Color::Blue,
+        PlayerColor::Magenta => Color::Magenta,
+        PlayerColor::Cyan => Color::Cyan,
+        PlayerColor::White => Color::White,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+// A line of a help table wrapped to the width: category headings stand out, in rows the command
+// stands out from its description, which wraps below itself
+fn get_help_lines(line: &str, width: usize) -> Vec<Line<'static>> {
+    let Some(row) = line.strip_prefix("  ") else {
+        return vec![Line::from(Span::styled(line.to_string(), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)))];
+    };
+    let (usage, rest) = row.split_once("  ").unwrap_or((row, ""));
+    let description = rest.trim_start();
+    let indent = line.len() - description.len();
+
+    let mut chunks: Vec<String> = Vec::new();
+    let max_len = width.saturating_sub(indent).max(20);
+    for word in description.split_whitespace() {
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() + 1 + word.len() <= max_len => {
+                chunk.push(' ');
+                chunk.push_str(word);
+            }
+            _ => chunks.push(word.to_string()),
+        }
+    }
+
+    let description_style = Style::default().fg(Color::White);
+    let mut lines = vec![Line::from(vec![
+        Span::raw("  "),
+        Span::styled(usage.to_string(), Style::default().fg(Color::Cyan)),
+        Span::raw(" ".repeat(indent - 2 - usage.len())),
+        Span::styled(chunks.first().cloned().unwrap_or_default(), description_style),
+    ])];
+    lines.extend(chunks.iter().skip(1).map(|chunk| {
+        Line::from(vec![Span::raw(" ".repeat(indent)), Span::styled(chunk.clone(), description_style)])
+    }));
+    lines
+}
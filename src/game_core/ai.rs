@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::{BuildingTypeId, Resource};
+
+#[derive(Debug)]
+pub enum AiConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicatePersonality(String),
+    NoPersonalities,
+    UnknownBuilding(String),
+    InvalidPercent { personality: String, field: &'static str },
+}
+
+impl fmt::Display for AiConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiConfigError::Io(e) => write!(f, "Failed to read AI file: {}", e),
+            AiConfigError::Toml(e) => write!(f, "Failed to parse AI file (TOML): {}", e),
+            AiConfigError::DuplicatePersonality(id) => write!(f, "AI personality '{}' is defined twice", id),
+            AiConfigError::NoPersonalities => write!(f, "The AI file needs at least one personality"),
+            AiConfigError::UnknownBuilding(building) => write!(f, "AI resource uses unknown building '{}'", building),
+            AiConfigError::InvalidPercent { personality, field } => {
+                write!(f, "AI personality '{}' has {} above 100", personality, field)
+            }
+        }
+    }
+}
+
+impl Error for AiConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AiConfigError::Io(e) => Some(e),
+            AiConfigError::Toml(e) => Some(e),
+            AiConfigError::DuplicatePersonality(_) => None,
+            AiConfigError::NoPersonalities => None,
+            AiConfigError::UnknownBuilding(_) => None,
+            AiConfigError::InvalidPercent { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for AiConfigError {
+    fn from(err: io::Error) -> Self {
+        AiConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for AiConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        AiConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// A resource the AI keeps in balance, with the buildings that produce and store it, by id as the
+// build command takes them
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AiResourceConfig {
+    pub resource: Resource,
+    pub producer: String,
+    pub storage: String,
+}
+
+// How a computer player plays. Percentages run from 0 to 100.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AiPersonality {
+    pub id: String,
+    pub name: String,
+    // TODO: Decide when to attack and how much to spend on fleets once there are fleets
+    pub aggression: u32,
+    // TODO: Decide when to settle new planets once colonization exists
+    pub expansion: u32,
+    // Storage fill at which more storage is built before more production
+    pub risk_tolerance: u32,
+    // Constructions a seeded AI picks at random instead of by its plan
+    pub wildcard_percent: u32,
+    // Share of production each resource is aimed at, resources left out are never produced
+    pub build_weights: HashMap<Resource, u32>,
+}
+
+impl AiPersonality {
+    pub fn get_weight(&self, resource: Resource) -> u32 {
+        self.build_weights.get(&resource).copied().unwrap_or(0)
+    }
+}
+
+impl fmt::Display for AiPersonality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (aggression {}, expansion {}, risk tolerance {}%)",
+            self.name, self.aggression, self.expansion, self.risk_tolerance,
+        )
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AiConfig {
+    pub resources: Vec<AiResourceConfig>,
+    pub personalities: Vec<AiPersonality>,
+}
+
+impl AiConfig {
+    pub fn load(path: &Path) -> Result<Self, AiConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: AiConfig = toml::from_str(&toml_content)?;
+
+        for resource in &config.resources {
+            for building in [&resource.producer, &resource.storage] {
+                if !BuildingTypeId::all().iter().any(|id| id.get_name() == building) {
+                    return Err(AiConfigError::UnknownBuilding(building.clone()));
+                }
+            }
+        }
+
+        if config.personalities.is_empty() {
+            return Err(AiConfigError::NoPersonalities);
+        }
+        let mut seen = Vec::new();
+        for personality in &config.personalities {
+            let id = personality.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(AiConfigError::DuplicatePersonality(personality.id.clone()));
+            }
+            seen.push(id);
+
+            let percents = [
+                ("aggression", personality.aggression),
+                ("expansion", personality.expansion),
+                ("risk_tolerance", personality.risk_tolerance),
+                ("wildcard_percent", personality.wildcard_percent),
+            ];
+            if let Some((field, _)) = percents.into_iter().find(|(_, value)| *value > 100) {
+                return Err(AiConfigError::InvalidPercent { personality: personality.id.clone(), field });
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn find_personality(&self, id: &str) -> Option<&AiPersonality> {
+        self.personalities.iter().find(|personality| personality.id.eq_ignore_ascii_case(id))
+    }
+
+    // Seats take turns through the personalities in the order they are listed
+    pub fn get_personality_for_seat(&self, seat: u64) -> &AiPersonality {
+        &self.personalities[(seat % self.personalities.len() as u64) as usize]
+    }
+}
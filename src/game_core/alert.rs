@@ -0,0 +1,55 @@
+use std::fmt;
+
+use super::planet::PlanetStatus;
+use super::Resource;
+
+// TODO: Alert on blockaded planets and fleets out of fuel once there are fleets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertKind {
+    // Producers of the resource stand idle
+    StorageFull(Resource),
+    Strike,
+    EmptyQueue,
+}
+
+// A problem on a planet the player can act on, listed until it is solved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub planet: String,
+    pub kind: AlertKind,
+}
+
+impl Alert {
+    pub fn collect(status: &PlanetStatus) -> Vec<Alert> {
+        let mut kinds = Vec::new();
+        if status.on_strike {
+            kinds.push(AlertKind::Strike);
+        }
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            let producing = status.production.get(&resource).is_some_and(|rate| !rate.is_zero());
+            if producing && status.turns_until_full.get(&resource) == Some(&0) {
+                kinds.push(AlertKind::StorageFull(resource));
+            }
+        }
+        if status.construction_queue.is_empty() {
+            kinds.push(AlertKind::EmptyQueue);
+        }
+
+        kinds
+            .into_iter()
+            .map(|kind| Alert { planet: status.planet_name.clone(), kind })
+            .collect()
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            AlertKind::StorageFull(resource) => {
+                write!(f, "{}: {} storage is full, its producers stand idle", self.planet, resource)
+            }
+            AlertKind::Strike => write!(f, "{}: workers are on strike", self.planet),
+            AlertKind::EmptyQueue => write!(f, "{}: nothing is under construction", self.planet),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+
+#[derive(Debug)]
+pub enum ArtifactsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateArtifact(String),
+}
+
+impl fmt::Display for ArtifactsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactsConfigError::Io(e) => write!(f, "Failed to read artifacts file: {}", e),
+            ArtifactsConfigError::Toml(e) => write!(f, "Failed to parse artifacts file (TOML): {}", e),
+            ArtifactsConfigError::DuplicateArtifact(id) => write!(f, "Artifact '{}' is defined twice", id),
+        }
+    }
+}
+
+impl Error for ArtifactsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ArtifactsConfigError::Io(e) => Some(e),
+            ArtifactsConfigError::Toml(e) => Some(e),
+            ArtifactsConfigError::DuplicateArtifact(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ArtifactsConfigError {
+    fn from(err: io::Error) -> Self {
+        ArtifactsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ArtifactsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        ArtifactsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// A unique relic, only one empire can hold it at a time
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ArtifactConfig {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    // Apply to the whole empire for as long as the artifact is held
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+impl ArtifactConfig {
+    pub fn get_modifier_source(&self) -> ModifierSource {
+        ModifierSource::Artifact(self.name.clone())
+    }
+
+    pub fn get_modifiers(&self) -> Vec<Modifier> {
+        self.modifiers
+            .iter()
+            .map(|modifier| modifier.to_modifier(self.get_modifier_source(), ModifierScope::Empire))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ArtifactsConfig {
+    pub artifacts: Vec<ArtifactConfig>,
+}
+
+impl ArtifactsConfig {
+    pub fn load(path: &Path) -> Result<Self, ArtifactsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: ArtifactsConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for artifact in &config.artifacts {
+            let id = artifact.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(ArtifactsConfigError::DuplicateArtifact(artifact.id.clone()));
+            }
+            seen.push(id);
+        }
+
+        Ok(config)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&ArtifactConfig> {
+        self.artifacts.iter().find(|artifact| artifact.id.eq_ignore_ascii_case(id))
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use super::ai::{AiConfig, AiPersonality, AiResourceConfig};
+use super::building::BuildingInstanceId;
+use super::rng::Rng;
+use super::{BuildingTypeId, Choice, GameCore, GameCoreError, PlanetStatus, Resource, ResourceAmount};
+
+// How much more a resource weighs on a planet designated for it
+const FOCUS_WEIGHT_MULTIPLIER: u32 = 3;
+
+// What a player can see of the game when it is their turn. Only their own empire is included, so a
+// bot can't make use of anything hidden from a human in the same seat.
+// TODO: Add the visible parts of other empires once there is a map and fog of war to cut them by
+#[derive(Debug, Clone)]
+pub struct PlayerView {
+    pub turn: u32,
+    pub player_name: String,
+    pub credits: ResourceAmount,
+    pub credits_income: i64,
+    pub planets: Vec<PlanetStatus>,
+    pub artifacts: Vec<String>,
+    pub pending_choice: Option<Choice>,
+    // Anyone can look these up in the Colonypedia
+    pub max_levels: HashMap<BuildingTypeId, u8>,
+}
+
+// A computer player. Commands are given in the same form a player types them, so a bot can do
+// anything a human can and nothing more.
+pub trait Bot {
+    fn get_name(&self) -> &str;
+    // Called at the start of the bot's turn, the turn is over once it decides `endturn`
+    fn decide(&mut self, view: &PlayerView) -> Vec<String>;
+    // Why the last decisions were made, for debugging and tuning bots. Empty unless the bot
+    // explains itself.
+    fn take_thoughts(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct BotMove {
+    pub player_name: String,
+    pub command: String,
+    pub result: Result<Option<String>, GameCoreError>,
+}
+
+// Hands the turn to the bot seated at the current player, if any
+#[derive(Default)]
+pub struct BotRunner {
+    seats: Vec<(String, Box<dyn Bot>)>,
+}
+
+impl BotRunner {
+    pub fn new() -> Self {
+        BotRunner::default()
+    }
+
+    // Replaces any bot already playing the player
+    pub fn seat(&mut self, player_name: &str, bot: Box<dyn Bot>) {
+        self.seats.retain(|(name, _)| name != player_name);
+        self.seats.push((player_name.to_string(), bot));
+    }
+
+    pub fn take_thoughts(&mut self, player_name: &str) -> Vec<String> {
+        self.seats
+            .iter_mut()
+            .find(|(name, _)| name == player_name)
+            .map_or(Vec::new(), |(_, bot)| bot.take_thoughts())
+    }
+
+    // Lets the current player's bot decide and runs its commands, failed ones included. Returns
+    // nothing when a human holds the seat.
+    pub fn play(&mut self, game_core: &mut GameCore) -> Vec<BotMove> {
+        let player_name = game_core.get_current_player_name();
+        let Some((_, bot)) = self.seats.iter_mut().find(|(name, _)| *name == player_name) else {
+            return Vec::new();
+        };
+        let Some(view) = game_core.get_player_view(&player_name) else {
+            return Vec::new();
+        };
+
+        let mut moves = Vec::new();
+        for command in bot.decide(&view) {
+            if !game_core.is_running() {
+                break;
+            }
+            let result = game_core.execute_command(&command);
+            moves.push(BotMove { player_name: player_name.clone(), command, result });
+        }
+        moves
+    }
+}
+
+// =================================================================================================
+
+// The built-in AI. It grows its economy one construction at a time per planet, adding storage
+// for any resource about to run out of room and otherwise production of the resource furthest
+// below its share, a larger one for the focus of a designated planet. Once a planet's building slots are full it only upgrades what is already built.
+// All of its tuning comes from its personality in ai.toml. A seeded one now and then builds
+// something else, so simulated games don't all play alike.
+// TODO: Expand, trade and answer merchants once it can judge the value of a deal
+#[derive(Debug)]
+pub struct EconomyBot {
+    personality: AiPersonality,
+    resources: Vec<AiResourceConfig>,
+    rng: Option<Rng>,
+    // Why each decision was made, only kept when asked for
+    thoughts: Option<Vec<String>>,
+}
+
+impl EconomyBot {
+    pub fn new(config: &AiConfig, personality: &AiPersonality) -> Self {
+        EconomyBot {
+            personality: personality.clone(),
+            resources: config.resources.clone(),
+            rng: None,
+            thoughts: None,
+        }
+    }
+
+    pub fn with_seed(config: &AiConfig, personality: &AiPersonality, seed: u64) -> Self {
+        EconomyBot {
+            rng: Some(Rng::new(seed)),
+            ..EconomyBot::new(config, personality)
+        }
+    }
+
+    // Explains every decision from now on, see Bot::take_thoughts
+    pub fn with_thoughts(mut self) -> Self {
+        self.thoughts = Some(Vec::new());
+        self
+    }
+
+    // Why the bot would play the current player's turn as it would, without playing it, so its
+    // thoughts can be shown next to a human in the same seat
+    pub fn think_about(&mut self, game_core: &GameCore) -> Vec<String> {
+        let Some(view) = game_core.get_player_view(&game_core.get_current_player_name()) else {
+            return Vec::new();
+        };
+        let commands = self.decide(&view);
+        let mut thoughts = self.take_thoughts();
+        thoughts.push(format!("Turn {}: would run {}", view.turn, commands.join(", ")));
+        thoughts
+    }
+
+    fn think(&mut self, thought: impl FnOnce() -> String) {
+        if let Some(thoughts) = &mut self.thoughts {
+            thoughts.push(thought());
+        }
+    }
+
+    fn choose_building(&mut self, planet: &PlanetStatus, max_levels: &HashMap<BuildingTypeId, u8>) -> Option<String> {
+        let wildcard_percent = self.personality.wildcard_percent;
+        if let Some(rng) = &mut self.rng
+            && rng.below(100) < wildcard_percent as usize
+            && let Some(resource) = rng.pick(&self.resources)
+        {
+            let building = if rng.below(2) == 0 { resource.producer.clone() } else { resource.storage.clone() };
+            if Self::fits(planet, max_levels, &building) {
+                self.think(|| format!(
+                    "{}: wildcard ({}% chance) picked {}", planet.planet_name, wildcard_percent, building
+                ));
+                return Some(building);
+            }
+        }
+
+        let risk_tolerance = self.personality.risk_tolerance;
+        for resource in &self.resources {
+            let (amount, capacity) = planet.storage.get(&resource.resource).copied().unwrap_or_default();
+            let fill = amount.get_percent_of(capacity);
+            if !capacity.is_zero() && fill >= risk_tolerance && Self::fits(planet, max_levels, &resource.storage) {
+                let (name, storage) = (resource.resource, resource.storage.clone());
+                self.think(|| format!(
+                    "{}: {} storage {}% full, at or above risk tolerance {}%, builds {}",
+                    planet.planet_name, name, fill, risk_tolerance, storage,
+                ));
+                return Some(storage);
+            }
+        }
+
+        // Production per point of weight, the lowest is furthest below its share
+        let scored: Vec<(&AiResourceConfig, ResourceAmount, u32)> = self.resources
+            .iter()
+            .map(|resource| {
+                let production = planet.production.get(&resource.resource).copied().unwrap_or_default();
+                (resource, production, Self::get_weight(&self.personality, planet, resource.resource))
+            })
+            .filter(|(resource, _, weight)| *weight > 0 && Self::fits(planet, max_levels, &resource.producer))
+            .collect();
+        let choice = scored
+            .iter()
+            .min_by_key(|(_, production, weight)| production.get() as u64 * 1000 / *weight as u64)
+            .map(|(resource, _, _)| resource.producer.clone());
+
+        let alternatives: Vec<String> = scored
+            .iter()
+            .map(|(resource, production, weight)| format!("{} {}/{}", resource.resource, production, weight))
+            .collect();
+        self.think(|| format!(
+            "{}: production/weight {}, builds {}",
+            planet.planet_name,
+            if alternatives.is_empty() { "none fit".to_string() } else { alternatives.join(", ") },
+            choice.as_deref().unwrap_or("nothing"),
+        ));
+        choice
+    }
+
+    // The personality's weight of the resource, more on a planet designated for it. Resources the
+    // personality leaves out stay out.
+    fn get_weight(personality: &AiPersonality, planet: &PlanetStatus, resource: Resource) -> u32 {
+        let weight = personality.get_weight(resource);
+        match &planet.specialization {
+            Some((_, Some(focus))) if *focus == resource => weight * FOCUS_WEIGHT_MULTIPLIER,
+            _ => weight,
+        }
+    }
+
+    // A building that isn't built yet needs a free building slot, and none goes past its max level
+    // once the levels already queued are done
+    fn fits(planet: &PlanetStatus, max_levels: &HashMap<BuildingTypeId, u8>, building: &str) -> bool {
+        let Some(&id) = BuildingTypeId::all().iter().find(|id| id.get_name() == building) else {
+            return false;
+        };
+        let level = planet.buildings
+            .iter()
+            .find(|(name, _)| *name == id.to_string())
+            .map_or(0, |(_, level)| *level);
+        let queued_level = planet.construction_queue
+            .iter()
+            .filter(|construction| {
+                construction.building == BuildingInstanceId::first(id) && construction.sub_location.is_none()
+            })
+            .map(|construction| construction.level)
+            .max()
+            .unwrap_or(0);
+        if max_levels.get(&id).is_some_and(|&max_level| level.max(queued_level) >= max_level) {
+            return false;
+        }
+
+        let (used, total) = planet.building_slots;
+        used < total || level > 0
+    }
+}
+
+impl Bot for EconomyBot {
+    fn get_name(&self) -> &str {
+        &self.personality.name
+    }
+
+    fn decide(&mut self, view: &PlayerView) -> Vec<String> {
+        let mut commands: Vec<String> = view.planets
+            .iter()
+            .filter(|planet| planet.construction_queue.is_empty())
+            .filter_map(|planet| {
+                let building = self.choose_building(planet, &view.max_levels)?;
+                Some(format!("build {} {}", building, planet.planet_name))
+            })
+            .collect();
+        commands.push("endturn".to_string());
+        commands
+    }
+
+    fn take_thoughts(&mut self) -> Vec<String> {
+        self.thoughts.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 use std::error::Error;
 
-use crate::game_core::Resource;
+use crate::game_core::{Resource, ResourceAmount};
 
 use super::BuildingConfig;
 
@@ -9,7 +9,6 @@ use super::BuildingConfig;
 pub enum BuildingError {
     WrongBuildingConfiguration,
     MaxLevelReached { current: u8, max: u8 },
-    InsufficientResources { required: u32, available: u32 },
 }
 
 impl fmt::Display for BuildingError {
@@ -19,8 +18,6 @@ impl fmt::Display for BuildingError {
                 write!(f, "Wrong building configuration"),
             BuildingError::MaxLevelReached { current, max } => 
                 write!(f, "Cannot upgrade: level {current} is at max {max}"),
-            BuildingError::InsufficientResources { required, available } => 
-                write!(f, "Insufficient resources: need {required}, have {available}"),
         }
     }
 }
@@ -35,6 +32,10 @@ pub trait Building {
     fn upgrade(&mut self) -> Result<(), BuildingError>;
 }
 
+// TODO: Add a Barracks training troops once ships can carry them, planets should only be captured
+// after the troops win a ground battle following an orbital victory
+// TODO: Add a Sensor Array revealing fleets and planets in a radius growing with its level, with
+// "enemy fleet detected" events, once there is a map with fog of war
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum BuildingTypeId {
     CommandCenter,
@@ -46,6 +47,12 @@ pub enum BuildingTypeId {
     BatteryArray,
     GasTank,
     MineralSilo,
+    TradeHub,
+    RecreationDome,
+    HoloTheater,
+    StellarBeacon,
+    DefensePlatform,
+    SolarCollector,
 }
 
 impl BuildingTypeId {
@@ -60,6 +67,12 @@ impl BuildingTypeId {
             Self::BatteryArray => "BatteryArray",
             Self::GasTank => "GasTank",
             Self::MineralSilo => "MineralSilo",
+            Self::TradeHub => "TradeHub",
+            Self::RecreationDome => "RecreationDome",
+            Self::HoloTheater => "HoloTheater",
+            Self::StellarBeacon => "StellarBeacon",
+            Self::DefensePlatform => "DefensePlatform",
+            Self::SolarCollector => "SolarCollector",
         }
     }
 
@@ -75,10 +88,46 @@ impl BuildingTypeId {
             BatteryArray,
             GasTank,
             MineralSilo,
+            TradeHub,
+            RecreationDome,
+            HoloTheater,
+            StellarBeacon,
+            DefensePlatform,
+            SolarCollector,
         ]
     }
 }
 
+// One of possibly several buildings of a type on a planet, numbered from 1. Commands address the
+// first one by the type alone and the others with their number, e.g. "MineralMine#2".
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct BuildingInstanceId {
+    pub type_id: BuildingTypeId,
+    pub number: u8,
+}
+
+impl BuildingInstanceId {
+    pub fn first(type_id: BuildingTypeId) -> Self {
+        BuildingInstanceId { type_id, number: 1 }
+    }
+
+    pub fn get_name(&self) -> String {
+        match self.number {
+            1 => self.type_id.get_name().to_string(),
+            number => format!("{}#{}", self.type_id.get_name(), number),
+        }
+    }
+}
+
+impl fmt::Display for BuildingInstanceId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.number {
+            1 => write!(f, "{}", self.type_id),
+            number => write!(f, "{} #{}", self.type_id, number),
+        }
+    }
+}
+
 impl fmt::Display for BuildingTypeId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -91,6 +140,12 @@ impl fmt::Display for BuildingTypeId {
             Self::BatteryArray => write!(f, "Battery Array"),
             Self::GasTank => write!(f, "Gas Tank"),
             Self::MineralSilo => write!(f, "Mineral Silo"),
+            Self::TradeHub => write!(f, "Trade Hub"),
+            Self::RecreationDome => write!(f, "Recreation Dome"),
+            Self::HoloTheater => write!(f, "Holo Theater"),
+            Self::StellarBeacon => write!(f, "Stellar Beacon"),
+            Self::DefensePlatform => write!(f, "Defense Platform"),
+            Self::SolarCollector => write!(f, "Solar Collector"),
         }
     }
 }
@@ -100,9 +155,15 @@ pub enum BuildingType {
     CommandCenter(BuildingBase),
     OrbitalShipyard(BuildingBase),
     ResearchLab(BuildingBase),
+    RecreationDome(BuildingBase),
+    HoloTheater(BuildingBase),
+    StellarBeacon(BuildingBase),
+    DefensePlatform(BuildingBase),
     FusionReactor(Productor),
     GasExtractor(Productor),
     MineralMine(Productor),
+    TradeHub(Productor),
+    SolarCollector(Productor),
     BatteryArray(Storage),
     GasTank(Storage),
     MineralSilo(Storage),
@@ -114,9 +175,15 @@ impl BuildingType {
             Self::CommandCenter(_) => BuildingTypeId::CommandCenter,
             Self::OrbitalShipyard(_) => BuildingTypeId::OrbitalShipyard,
             Self::ResearchLab(_) => BuildingTypeId::ResearchLab,
+            Self::RecreationDome(_) => BuildingTypeId::RecreationDome,
+            Self::HoloTheater(_) => BuildingTypeId::HoloTheater,
+            Self::StellarBeacon(_) => BuildingTypeId::StellarBeacon,
+            Self::DefensePlatform(_) => BuildingTypeId::DefensePlatform,
             Self::FusionReactor(_) => BuildingTypeId::FusionReactor,
             Self::GasExtractor(_) => BuildingTypeId::GasExtractor,
             Self::MineralMine(_) => BuildingTypeId::MineralMine,
+            Self::TradeHub(_) => BuildingTypeId::TradeHub,
+            Self::SolarCollector(_) => BuildingTypeId::SolarCollector,
             Self::BatteryArray(_) => BuildingTypeId::BatteryArray,
             Self::GasTank(_) => BuildingTypeId::GasTank,
             Self::MineralSilo(_) => BuildingTypeId::MineralSilo,
@@ -131,20 +198,69 @@ impl BuildingType {
                 Self::GasExtractor(Productor::new("Gas Extractor", 0, Resource::Gas, building_config)),
             BuildingTypeId::MineralMine =>
                 Self::MineralMine(Productor::new("Mineral Mine", 0, Resource::Minerals, building_config)),
+            BuildingTypeId::TradeHub =>
+                Self::TradeHub(Productor::new("Trade Hub", 0, Resource::Credits, building_config)),
+            BuildingTypeId::SolarCollector =>
+                Self::SolarCollector(Productor::new("Solar Collector", 0, Resource::Energy, building_config)),
             BuildingTypeId::BatteryArray =>
                 Self::BatteryArray(Storage::new("Battery Array", 0, Resource::Energy, building_config)),
             BuildingTypeId::GasTank =>
                 Self::GasTank(Storage::new("Gas Tank", 0, Resource::Gas, building_config)),
             BuildingTypeId::MineralSilo =>
-                Self::MineralSilo(Storage::new("Mineral Storage", 0, Resource::Minerals, building_config)),
+                Self::MineralSilo(Storage::new("Mineral Silo", 0, Resource::Minerals, building_config)),
             BuildingTypeId::CommandCenter =>
                 Self::CommandCenter(BuildingBase::new("Command Center", 0, building_config)),
             BuildingTypeId::OrbitalShipyard =>
                 Self::OrbitalShipyard(BuildingBase::new("Orbital Shipyard", 0, building_config)),
             BuildingTypeId::ResearchLab =>
                 Self::ResearchLab(BuildingBase::new("Research Lab", 0, building_config)),
+            BuildingTypeId::RecreationDome =>
+                Self::RecreationDome(BuildingBase::new("Recreation Dome", 0, building_config)),
+            BuildingTypeId::HoloTheater =>
+                Self::HoloTheater(BuildingBase::new("Holo Theater", 0, building_config)),
+            BuildingTypeId::StellarBeacon =>
+                Self::StellarBeacon(BuildingBase::new("Stellar Beacon", 0, building_config)),
+            BuildingTypeId::DefensePlatform =>
+                Self::DefensePlatform(BuildingBase::new("Defense Platform", 0, building_config)),
+        }
+    }
+
+    // Resource and amount a producing building makes per turn at the current level
+    pub fn get_production(&self) -> Option<(Resource, ResourceAmount)> {
+        match self {
+            Self::FusionReactor(productor)
+            | Self::GasExtractor(productor)
+            | Self::MineralMine(productor)
+            | Self::TradeHub(productor)
+            | Self::SolarCollector(productor) => Some((*productor.get_resource(), productor.get_production_rate())),
+            _ => None,
         }
     }
+
+    // Morale points the building adds to its planet at the current level
+    pub fn get_morale_bonus(&self) -> u32 {
+        let building = match self {
+            Self::CommandCenter(building)
+            | Self::OrbitalShipyard(building)
+            | Self::ResearchLab(building)
+            | Self::RecreationDome(building)
+            | Self::HoloTheater(building)
+            | Self::StellarBeacon(building)
+            | Self::DefensePlatform(building) => building,
+            Self::FusionReactor(productor)
+            | Self::GasExtractor(productor)
+            | Self::MineralMine(productor)
+            | Self::TradeHub(productor)
+            | Self::SolarCollector(productor) => &productor.building,
+            Self::BatteryArray(storage)
+            | Self::GasTank(storage)
+            | Self::MineralSilo(storage) => &storage.building,
+        };
+
+        building.building_config.get_morale()
+            .and_then(|morale| morale.get_bonus_for_level(building.level as usize))
+            .unwrap_or(0)
+    }
 }
 
 impl Building for BuildingType {
@@ -152,13 +268,19 @@ impl Building for BuildingType {
         match self {
             Self::CommandCenter(building)
             | Self::OrbitalShipyard(building)
-            | Self::ResearchLab(building) => &building.get_name(),
+            | Self::ResearchLab(building)
+            | Self::RecreationDome(building)
+            | Self::HoloTheater(building)
+            | Self::StellarBeacon(building)
+            | Self::DefensePlatform(building) => building.get_name(),
             Self::FusionReactor(productor)
             | Self::GasExtractor(productor)
-            | Self::MineralMine(productor) => &productor.get_name(),
+            | Self::MineralMine(productor)
+            | Self::TradeHub(productor)
+            | Self::SolarCollector(productor) => productor.get_name(),
             Self::BatteryArray(storage)
             | Self::GasTank(storage)
-            | Self::MineralSilo(storage) => &storage.get_name(),
+            | Self::MineralSilo(storage) => storage.get_name(),
         }
     }
 
@@ -166,10 +288,16 @@ impl Building for BuildingType {
         match self {
             Self::CommandCenter(building)
             | Self::OrbitalShipyard(building)
-            | Self::ResearchLab(building) => building.get_level(),
+            | Self::ResearchLab(building)
+            | Self::RecreationDome(building)
+            | Self::HoloTheater(building)
+            | Self::StellarBeacon(building)
+            | Self::DefensePlatform(building) => building.get_level(),
             Self::FusionReactor(productor)
             | Self::GasExtractor(productor)
-            | Self::MineralMine(productor) => productor.get_level(),
+            | Self::MineralMine(productor)
+            | Self::TradeHub(productor)
+            | Self::SolarCollector(productor) => productor.get_level(),
             Self::BatteryArray(storage)
             | Self::GasTank(storage)
             | Self::MineralSilo(storage) => storage.get_level(),
@@ -180,10 +308,16 @@ impl Building for BuildingType {
         match self {
             Self::CommandCenter(building)
             | Self::OrbitalShipyard(building)
-            | Self::ResearchLab(building) => building.upgrade(),
+            | Self::ResearchLab(building)
+            | Self::RecreationDome(building)
+            | Self::HoloTheater(building)
+            | Self::StellarBeacon(building)
+            | Self::DefensePlatform(building) => building.upgrade(),
             Self::FusionReactor(productor)
             | Self::GasExtractor(productor)
-            | Self::MineralMine(productor) => productor.upgrade(),
+            | Self::MineralMine(productor)
+            | Self::TradeHub(productor)
+            | Self::SolarCollector(productor) => productor.upgrade(),
             Self::BatteryArray(storage)
             | Self::GasTank(storage)
             | Self::MineralSilo(storage) => storage.upgrade(),
@@ -232,25 +366,20 @@ impl Building for BuildingBase {
 pub struct Productor {
     building: BuildingBase,
     resource: Resource,
-    production_rate: u32,
+    production_rate: ResourceAmount,
 }
 
 impl Productor {
     pub fn new(name: &str, level: u8, resource: Resource, building_config: BuildingConfig) -> Self {
         let production_rate = match &building_config.get_production() {
-            Some(production) => {
-                match production.get_rate_for_level(level as usize) {
-                    Some(rate) => rate as u32,
-                    None => 0,
-                }
-            }
-            None => 0,
+            Some(production) => production.get_rate_for_level(level as usize).unwrap_or(0).into(),
+            None => ResourceAmount::ZERO,
         };
 
         Productor {
             building: BuildingBase::new(name, level, building_config),
-            resource: resource,
-            production_rate: production_rate
+            resource,
+            production_rate
         }
     }
 
@@ -258,7 +387,7 @@ impl Productor {
         &self.resource
     }
 
-    pub fn get_production_rate(&self) -> u32 {
+    pub fn get_production_rate(&self) -> ResourceAmount {
         self.production_rate
     }
 }
@@ -279,7 +408,7 @@ impl Building for Productor {
             Some(production) => {
                 match production.get_rate_for_level(self.building.level as usize) {
                     Some(rate) => {
-                        self.production_rate = rate as u32;
+                        self.production_rate = rate.into();
                     }
                     None => {
                         return Err(BuildingError::WrongBuildingConfiguration);
@@ -288,7 +417,7 @@ impl Building for Productor {
                 Ok(())
             }
             None => {
-                return Err(BuildingError::WrongBuildingConfiguration);
+                Err(BuildingError::WrongBuildingConfiguration)
             }
         }
     }
@@ -298,44 +427,50 @@ impl Building for Productor {
 pub struct Storage {
     building: BuildingBase,
     resource: Resource,
-    capacity: u32,
-    current_amount: u32,
+    capacity: ResourceAmount,
+    current_amount: ResourceAmount,
 }
 
 impl Storage {
     pub fn new(name: &str, level: u8, resource: Resource, building_config: BuildingConfig) -> Self {
         let capacity = match &building_config.get_storage() {
-            Some(storage) => {
-                match storage.get_capacity_for_level(level as usize) {
-                    Some(capacity) => capacity as u32,
-                    None => 0,
-                }
-            }
-            None => 0,
+            Some(storage) => storage.get_capacity_for_level(level as usize).unwrap_or(0).into(),
+            None => ResourceAmount::ZERO,
         };
 
         Storage {
             building: BuildingBase::new(name, level, building_config),
-            resource: resource,
-            capacity: capacity,
-            current_amount: 0,
+            resource,
+            capacity,
+            current_amount: ResourceAmount::ZERO,
         }
     }
 
-    pub fn get_capacity(&self) -> u32 {
+    pub fn get_resource(&self) -> Resource {
+        self.resource
+    }
+
+    pub fn get_capacity(&self) -> ResourceAmount {
         self.capacity
     }
 
-    pub fn get_current_amount(&self) -> u32 {
+    pub fn get_current_amount(&self) -> ResourceAmount {
         self.current_amount
     }
 
-    pub fn add_resource(&mut self, amount_to_add: u32) -> u32 {
-        let available_space = self.capacity.saturating_sub(self.current_amount);
+    pub fn add_resource(&mut self, amount_to_add: ResourceAmount) -> ResourceAmount {
+        let available_space = self.capacity - self.current_amount;
         let actual_added = std::cmp::min(amount_to_add, available_space);
         self.current_amount += actual_added;
         actual_added
     }
+
+    // Returns how much was actually removed, never more than is stored
+    pub fn remove_resource(&mut self, amount_to_remove: ResourceAmount) -> ResourceAmount {
+        let actual_removed = std::cmp::min(amount_to_remove, self.current_amount);
+        self.current_amount -= actual_removed;
+        actual_removed
+    }
 }
 
 impl Building for Storage {
@@ -354,7 +489,7 @@ impl Building for Storage {
             Some(storage) => {
                 match storage.get_capacity_for_level(self.building.level as usize) {
                     Some(capacity) => {
-                        self.capacity = capacity as u32;
+                        self.capacity = capacity.into();
                     }
                     None => {
                         return Err(BuildingError::WrongBuildingConfiguration);
@@ -363,7 +498,7 @@ impl Building for Storage {
                 Ok(())
             }
             None => {
-                return Err(BuildingError::WrongBuildingConfiguration);
+                Err(BuildingError::WrongBuildingConfiguration)
             }
         }
     }
@@ -4,6 +4,8 @@ use serde::Deserialize;
 
 use crate::game_core::Resource;
 
+use super::level_curve::evaluate_level_curve;
+
 #[derive(Debug)]
 pub enum BuildingsConfigError {
     Io(std::io::Error),
@@ -15,6 +17,11 @@ pub enum BuildingsConfigError {
     ProductionRateMismatch(String),
     StorageCapacityMismatch(String),
     BuildingTimeMismatch(String),
+    MoraleBonusMismatch(String),
+    UnknownBase(String),
+    InheritanceCycle(String),
+    InvalidFormula(String),
+    StagesMismatch(String),
 }
 
 impl std::fmt::Display for BuildingsConfigError {
@@ -39,14 +46,29 @@ impl std::fmt::Display for BuildingsConfigError {
                 f, "Gas cost mismatch: {} doesn't match max_level", err
             ),
             BuildingsConfigError::ProductionRateMismatch(err) => write!(
-                f, "Production rate mismatch: {} doesn't match max_level", err
+                f, "Production rate mismatch: {} needs a value for each level from 0 to max_level", err
             ),
             BuildingsConfigError::StorageCapacityMismatch(err) => write!(
-                f, "Storage capacity mismatch: {} doesn't match max_level", err
+                f, "Storage capacity mismatch: {} needs a value for each level from 0 to max_level", err
             ),
             BuildingsConfigError::BuildingTimeMismatch(err) => write!(
                 f, "Building time mismatch: {} doesn't match max_level", err
             ),
+            BuildingsConfigError::MoraleBonusMismatch(err) => write!(
+                f, "Morale bonus mismatch: {} needs a value for each level from 0 to max_level", err
+            ),
+            BuildingsConfigError::UnknownBase(err) => write!(
+                f, "Unknown base building: {}", err
+            ),
+            BuildingsConfigError::InheritanceCycle(err) => write!(
+                f, "Buildings inherit from each other in a cycle: {}", err
+            ),
+            BuildingsConfigError::InvalidFormula(err) => write!(
+                f, "Invalid level formula: {}", err
+            ),
+            BuildingsConfigError::StagesMismatch(err) => write!(
+                f, "Wonder stages mismatch: {} doesn't match max_level", err
+            ),
         }
     }
 }
@@ -63,6 +85,11 @@ impl std::error::Error for BuildingsConfigError {
             BuildingsConfigError::ProductionRateMismatch(_) => None,
             BuildingsConfigError::StorageCapacityMismatch(_) => None,
             BuildingsConfigError::BuildingTimeMismatch(_) => None,
+            BuildingsConfigError::MoraleBonusMismatch(_) => None,
+            BuildingsConfigError::UnknownBase(_) => None,
+            BuildingsConfigError::InheritanceCycle(_) => None,
+            BuildingsConfigError::InvalidFormula(_) => None,
+            BuildingsConfigError::StagesMismatch(_) => None,
         }
     }
 }
@@ -92,12 +119,20 @@ pub struct BuildingsConfig {
 pub struct BuildingConfig {
     name: String,
     max_level: u8,
+    // How many of it a planet may have, each leveled on its own
+    #[serde(default = "BuildingConfig::default_max_instances")]
+    max_instances: u8,
     upgrade_cost: UpgradeCost,
     building_time: BuildingTime,
     #[serde(default)]
     production: Option<ProductionInfo>,
     #[serde(default)]
     storage: Option<StorageInfo>,
+    #[serde(default)]
+    morale: Option<MoraleInfo>,
+    // Names of the levels of a wonder, which an empire can build only once, each level a stage
+    #[serde(default)]
+    stages: Vec<String>,
 }
 
 impl BuildingConfig {
@@ -109,10 +144,22 @@ impl BuildingConfig {
         self.max_level
     }
 
+    pub fn get_max_instances(&self) -> u8 {
+        self.max_instances
+    }
+
+    fn default_max_instances() -> u8 {
+        1
+    }
+
     pub fn get_upgrade_cost(&self) -> &UpgradeCost {
         &self.upgrade_cost
     }
 
+    pub fn get_building_time(&self) -> &BuildingTime {
+        &self.building_time
+    }
+
     pub fn get_production(&self) -> Option<&ProductionInfo> {
         self.production.as_ref()
     }
@@ -120,6 +167,19 @@ impl BuildingConfig {
     pub fn get_storage(&self) -> Option<&StorageInfo> {
         self.storage.as_ref()
     }
+
+    pub fn get_morale(&self) -> Option<&MoraleInfo> {
+        self.morale.as_ref()
+    }
+
+    pub fn is_wonder(&self) -> bool {
+        !self.stages.is_empty()
+    }
+
+    // Levels count from 1
+    pub fn get_stage_name(&self, level: u8) -> Option<&str> {
+        self.stages.get((level as usize).checked_sub(1)?).map(String::as_str)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -173,64 +233,111 @@ impl StorageInfo {
     }
 }
 
+// Morale points the building adds to its planet
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MoraleInfo {
+    pub bonus_per_level: Vec<u32>,
+}
+
+impl MoraleInfo {
+    pub fn get_bonus_for_level(&self, level: usize) -> Option<u32> {
+        self.bonus_per_level.get(level).cloned()
+    }
+}
+
 impl BuildingsConfig {
+    // Entries may name another building as their `base` to inherit its settings, overriding only
+    // what they set themselves. Per-level tables may be given as formulas instead of arrays, see
+    // level_curve.rs.
     pub fn load(path: &Path) -> Result<BuildingsConfig, BuildingsConfigError> {
         let config_content = fs::read_to_string(path)?;
-        let buildings_config: BuildingsConfig = toml::from_str(&config_content)?;
+        let raw_config: toml::Table = toml::from_str(&config_content)?;
+
+        let mut resolved = toml::Table::new();
+        for id in raw_config.keys() {
+            let mut entry = Self::resolve_base(&raw_config, id, &mut Vec::new())?;
+            Self::evaluate_formulas(id, &mut entry)?;
+            resolved.insert(id.clone(), toml::Value::Table(entry));
+        }
+        let buildings_config: BuildingsConfig = toml::Value::Table(resolved).try_into()?;
     
-        for (_, config) in &buildings_config.buildings {
+        for (id, config) in &buildings_config.buildings {
             let max_lvl = config.max_level as usize;
+            // Production, storage and morale also have a value for level 0
+            let value_levels = max_lvl + 1;
+            let describe = |table: &[u32]| format!("{} {:?} ({} levels)", id, table, table.len());
     
             // Validate upgrade costs
             if config.upgrade_cost.energy.len() != max_lvl {
                 return Err(
                     BuildingsConfigError::EnergyCostMismatch(
-                        config.upgrade_cost.energy.len().to_string()
+                        describe(&config.upgrade_cost.energy)
                     )
                 );
             }
             if config.upgrade_cost.minerals.len() != max_lvl {
                 return Err(
                     BuildingsConfigError::MineralsCostMismatch(
-                        config.upgrade_cost.minerals.len().to_string()
+                        describe(&config.upgrade_cost.minerals)
                     )
                 );
             }
-            if config.upgrade_cost.gas.len() != 0 && config.upgrade_cost.gas.len() != max_lvl {
+            if !config.upgrade_cost.gas.is_empty() && config.upgrade_cost.gas.len() != max_lvl {
                 return Err(
                     BuildingsConfigError::GasCostMismatch(
-                        config.upgrade_cost.gas.len().to_string()
+                        describe(&config.upgrade_cost.gas)
                     )
                 );
             }
     
             if let Some(prod) = &config.production {
                 // Validate production info
-                if prod.rate_per_level.len() != max_lvl {
+                if prod.rate_per_level.len() != value_levels {
                     return Err(
                         BuildingsConfigError::ProductionRateMismatch(
-                            prod.rate_per_level.len().to_string()
+                            describe(&prod.rate_per_level)
                         )
                     );
                 }
             }
     
             // Validate storage info
-            if let Some(stor) = &config.storage {
-                if stor.capacity_per_level.len() != max_lvl {
-                    return Err(
-                        BuildingsConfigError::StorageCapacityMismatch(
-                            stor.capacity_per_level.len().to_string()
-                        )
-                    );
-                }
+            if let Some(stor) = &config.storage
+                && stor.capacity_per_level.len() != value_levels
+            {
+                return Err(
+                    BuildingsConfigError::StorageCapacityMismatch(
+                        describe(&stor.capacity_per_level)
+                    )
+                );
+            }
+
+            // Validate morale bonus
+            if let Some(morale) = &config.morale
+                && morale.bonus_per_level.len() != value_levels
+            {
+                return Err(
+                    BuildingsConfigError::MoraleBonusMismatch(
+                        describe(&morale.bonus_per_level)
+                    )
+                );
             }
 
             // Validate building time
             if config.building_time.time_per_level.len() != max_lvl {
                 return Err(
                     BuildingsConfigError::BuildingTimeMismatch(
-                        config.building_time.time_per_level.len().to_string()
+                        describe(&config.building_time.time_per_level)
+                    )
+                );
+            }
+
+            // Validate wonder stages, there is only one wonder of a kind per empire
+            if config.is_wonder() && (config.stages.len() != max_lvl || config.max_instances != 1) {
+                return Err(
+                    BuildingsConfigError::StagesMismatch(
+                        format!("{} {:?} ({} stages, {} instances)", id, config.stages, config.stages.len(), config.max_instances)
                     )
                 );
             }
@@ -238,4 +345,109 @@ impl BuildingsConfig {
     
         Ok(buildings_config)
     }
+
+    // The entry with everything inherited from its bases merged in
+    fn resolve_base(
+        raw_config: &toml::Table,
+        id: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<toml::Table, BuildingsConfigError> {
+        if chain.iter().any(|visited| visited == id) {
+            chain.push(id.to_string());
+            return Err(BuildingsConfigError::InheritanceCycle(chain.join(" -> ")));
+        }
+        chain.push(id.to_string());
+
+        let mut entry = match raw_config.get(id) {
+            Some(toml::Value::Table(entry)) => entry.clone(),
+            _ => return Err(BuildingsConfigError::UnknownBase(format!("{} (via {})", id, chain.join(" -> ")))),
+        };
+        let Some(base) = entry.remove("base") else {
+            return Ok(entry);
+        };
+        let base = base
+            .as_str()
+            .ok_or_else(|| BuildingsConfigError::UnknownBase(format!("{} has a base that isn't a name", id)))?;
+
+        let mut merged = Self::resolve_base(raw_config, base, chain)?;
+        Self::merge_tables(&mut merged, entry);
+        Ok(merged)
+    }
+
+    // Nested tables are merged key by key, anything else in the override replaces the base value
+    fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+        for (key, value) in overrides {
+            match (base.get_mut(&key), value) {
+                (Some(toml::Value::Table(base_table)), toml::Value::Table(override_table)) => {
+                    Self::merge_tables(base_table, override_table);
+                }
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
+        }
+    }
+
+    // Replaces the formulas given for per-level tables with the values they evaluate to. Costs
+    // and times are those of building levels 1 to max_level, while what a building gives starts
+    // at level 0, as the first building of a type works a little before it is built.
+    fn evaluate_formulas(id: &str, entry: &mut toml::Table) -> Result<(), BuildingsConfigError> {
+        const LEVEL_TABLES: [(&str, &str, u8); 7] = [
+            ("upgrade_cost", "energy", 1),
+            ("upgrade_cost", "minerals", 1),
+            ("upgrade_cost", "gas", 1),
+            ("building_time", "time_per_level", 1),
+            ("production", "rate_per_level", 0),
+            ("storage", "capacity_per_level", 0),
+            ("morale", "bonus_per_level", 0),
+        ];
+
+        let max_level = entry
+            .get("max_level")
+            .and_then(|max_level| max_level.as_integer())
+            .and_then(|max_level| u8::try_from(max_level).ok())
+            .unwrap_or(0);
+        for (section, key, first_level) in LEVEL_TABLES {
+            let Some(toml::Value::Table(section_table)) = entry.get_mut(section) else {
+                continue;
+            };
+            let Some(toml::Value::String(formula)) = section_table.get(key) else {
+                continue;
+            };
+
+            let table = evaluate_level_curve(formula, first_level, max_level).map_err(|err| {
+                BuildingsConfigError::InvalidFormula(format!("{}.{}.{} = \"{}\": {}", id, section, key, formula, err))
+            })?;
+            let table = table.into_iter().map(|value| toml::Value::Integer(value as i64)).collect();
+            section_table.insert(key.to_string(), toml::Value::Array(table));
+        }
+        Ok(())
+    }
+
+    // The per-level tables of every building as the game uses them, for checking what formulas
+    // and inheritance evaluated to
+    pub fn get_level_tables_report(&self) -> String {
+        let mut ids: Vec<&String> = self.buildings.keys().collect();
+        ids.sort();
+
+        let mut lines = Vec::new();
+        for id in ids {
+            let config = &self.buildings[id];
+            lines.push(format!("{} ({}), max level {}", id, config.name, config.max_level));
+            lines.push(format!("  energy cost:   {:?}", config.upgrade_cost.energy));
+            lines.push(format!("  minerals cost: {:?}", config.upgrade_cost.minerals));
+            lines.push(format!("  gas cost:      {:?}", config.upgrade_cost.gas));
+            lines.push(format!("  build time:    {:?}", config.building_time.time_per_level));
+            if let Some(production) = &config.production {
+                lines.push(format!("  {} production: {:?}", production.resource, production.rate_per_level));
+            }
+            if let Some(storage) = &config.storage {
+                lines.push(format!("  {} storage: {:?}", storage.resource, storage.capacity_per_level));
+            }
+            if let Some(morale) = &config.morale {
+                lines.push(format!("  morale bonus:  {:?}", morale.bonus_per_level));
+            }
+        }
+        lines.join("\n")
+    }
 }
@@ -0,0 +1,153 @@
+// Formulas describing a per-level table of a building, e.g. `100 * 1.6^level`. They support
+// numbers, `+ - * / ^`, parentheses and the variables `level` and `max_level`. Tables are evaluated when the buildings are loaded, results are rounded to whole
+// numbers and never go below zero.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Variable(String),
+    Operator(char),
+    OpenParen,
+    CloseParen,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    level: f64,
+    max_level: f64,
+}
+
+impl Parser {
+    fn tokenize(formula: &str) -> Result<Vec<Token>, String> {
+        let mut tokens = Vec::new();
+        let mut chars = formula.chars().peekable();
+        while let Some(&char) = chars.peek() {
+            match char {
+                ' ' | '\t' => {
+                    chars.next();
+                }
+                '0'..='9' | '.' => {
+                    let mut number = String::new();
+                    while let Some(&digit) = chars.peek().filter(|char| char.is_ascii_digit() || **char == '.') {
+                        number.push(digit);
+                        chars.next();
+                    }
+                    let value = number.parse().map_err(|_| format!("Invalid number '{}'", number))?;
+                    tokens.push(Token::Number(value));
+                }
+                'a'..='z' | 'A'..='Z' | '_' => {
+                    let mut name = String::new();
+                    while let Some(&letter) = chars.peek().filter(|char| char.is_ascii_alphanumeric() || **char == '_') {
+                        name.push(letter);
+                        chars.next();
+                    }
+                    tokens.push(Token::Variable(name));
+                }
+                '+' | '-' | '*' | '/' | '^' => {
+                    tokens.push(Token::Operator(char));
+                    chars.next();
+                }
+                '(' => {
+                    tokens.push(Token::OpenParen);
+                    chars.next();
+                }
+                ')' => {
+                    tokens.push(Token::CloseParen);
+                    chars.next();
+                }
+                _ => return Err(format!("Unexpected '{}'", char)),
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn parse_sum(&mut self) -> Result<f64, String> {
+        let mut value = self.parse_product()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek().cloned() {
+            self.next();
+            let rhs = self.parse_product()?;
+            value = if operator == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    // product := power (('*' | '/') power)*
+    fn parse_product(&mut self) -> Result<f64, String> {
+        let mut value = self.parse_power()?;
+        while let Some(Token::Operator(operator @ ('*' | '/'))) = self.peek().cloned() {
+            self.next();
+            let rhs = self.parse_power()?;
+            value = if operator == '*' { value * rhs } else { value / rhs };
+        }
+        Ok(value)
+    }
+
+    // power := unary ('^' power)?, right associative
+    fn parse_power(&mut self) -> Result<f64, String> {
+        let base = self.parse_unary()?;
+        if self.peek() == Some(&Token::Operator('^')) {
+            self.next();
+            return Ok(base.powf(self.parse_power()?));
+        }
+        Ok(base)
+    }
+
+    // unary := '-' unary | atom
+    fn parse_unary(&mut self) -> Result<f64, String> {
+        if self.peek() == Some(&Token::Operator('-')) {
+            self.next();
+            return Ok(-self.parse_unary()?);
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Variable(name)) => match name.as_str() {
+                "level" => Ok(self.level),
+                "max_level" => Ok(self.max_level),
+                _ => Err(format!("Unknown variable '{}'", name)),
+            },
+            Some(Token::OpenParen) => {
+                let value = self.parse_sum()?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(value),
+                    _ => Err("Missing ')'".to_string()),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {:?}", token)),
+            None => Err("Unexpected end of formula".to_string()),
+        }
+    }
+}
+
+// Values of the formula for levels first_level to max_level
+pub fn evaluate_level_curve(formula: &str, first_level: u8, max_level: u8) -> Result<Vec<u32>, String> {
+    let tokens = Parser::tokenize(formula)?;
+    (first_level..=max_level)
+        .map(|level| {
+            let mut parser = Parser { tokens: tokens.clone(), position: 0, level: level as f64, max_level: max_level as f64 };
+            let value = parser.parse_sum()?;
+            if let Some(token) = parser.peek() {
+                return Err(format!("Unexpected {:?}", token));
+            }
+            if !value.is_finite() {
+                return Err(format!("No finite value at level {}", level));
+            }
+            Ok(value.round().clamp(0.0, u32::MAX as f64) as u32)
+        })
+        .collect()
+}
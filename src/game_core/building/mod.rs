@@ -1,11 +1,12 @@
+#[allow(clippy::module_inception)]
 pub mod building;
 pub mod building_config;
+mod level_curve;
 
 pub use building::{
     BuildingTypeId, 
+    BuildingInstanceId,
     BuildingType, 
-    BuildingBase, 
-    Productor, 
     Storage, 
     BuildingError
 };
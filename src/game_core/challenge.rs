@@ -0,0 +1,270 @@
+use std::error::Error;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+use super::rng::{get_checksum, Rng};
+use super::{Resource, ResourceAmount};
+
+#[derive(Debug)]
+pub enum ChallengeConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    InvalidRange(String),
+    NoTwists,
+}
+
+impl fmt::Display for ChallengeConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChallengeConfigError::Io(e) => write!(f, "Failed to read challenge file: {}", e),
+            ChallengeConfigError::Toml(e) => write!(f, "Failed to parse challenge file (TOML): {}", e),
+            ChallengeConfigError::InvalidRange(name) => write!(f, "Challenge range '{}' has min above max", name),
+            ChallengeConfigError::NoTwists => write!(f, "The challenge file needs at least one twist"),
+        }
+    }
+}
+
+impl Error for ChallengeConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ChallengeConfigError::Io(e) => Some(e),
+            ChallengeConfigError::Toml(e) => Some(e),
+            ChallengeConfigError::InvalidRange(_) => None,
+            ChallengeConfigError::NoTwists => None,
+        }
+    }
+}
+
+impl From<io::Error> for ChallengeConfigError {
+    fn from(err: io::Error) -> Self {
+        ChallengeConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ChallengeConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        ChallengeConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct ChallengeRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl ChallengeRange {
+    fn pick(&self, rng: &mut Rng) -> u32 {
+        self.min + rng.below((self.max - self.min + 1) as usize) as u32
+    }
+}
+
+// A rule of the day that applies to the whole empire for the entire challenge
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ChallengeTwist {
+    pub name: String,
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+// Points the final colony is worth
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScoreWeights {
+    pub credits: u32,
+    pub building_level: u32,
+    pub population: u32,
+    // Stored resources of every kind are worth a point for each this many
+    pub resources_per_point: u32,
+}
+
+impl ScoreWeights {
+    pub fn get_score(&self, tally: &ColonyTally) -> u32 {
+        tally.credits.get().saturating_mul(self.credits)
+            + tally.building_levels.saturating_mul(self.building_level)
+            + tally.population.saturating_mul(self.population)
+            + tally.resources.get() / self.resources_per_point.max(1)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ChallengeConfig {
+    pub turns: u32,
+    pub starting_credits: ChallengeRange,
+    // Picked for each stored resource on its own
+    pub starting_resources: ChallengeRange,
+    pub score: ScoreWeights,
+    pub twists: Vec<ChallengeTwist>,
+}
+
+impl ChallengeConfig {
+    pub fn load(path: &Path) -> Result<Self, ChallengeConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: ChallengeConfig = toml::from_str(&toml_content)?;
+
+        for (name, range) in [("starting_credits", config.starting_credits), ("starting_resources", config.starting_resources)] {
+            if range.min > range.max {
+                return Err(ChallengeConfigError::InvalidRange(name.to_string()));
+            }
+        }
+        if config.twists.is_empty() {
+            return Err(ChallengeConfigError::NoTwists);
+        }
+
+        Ok(config)
+    }
+}
+
+// What a building, colonist or stored unit contributed to a score
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColonyTally {
+    pub credits: ResourceAmount,
+    pub building_levels: u32,
+    pub population: u32,
+    pub resources: ResourceAmount,
+}
+
+// A scenario everyone playing the same date gets, so results can be compared. The date is the
+// only input, the game itself has no randomness.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    date: String,
+    turns: u32,
+    starting_credits: ResourceAmount,
+    starting_resources: Vec<(Resource, ResourceAmount)>,
+    twist: ChallengeTwist,
+    score_weights: ScoreWeights,
+    // Set once the last turn ended
+    final_score: Option<u32>,
+}
+
+impl Challenge {
+    // Returns None when the date is not a valid YYYY-MM-DD date
+    pub fn new(config: &ChallengeConfig, date: &str) -> Option<Self> {
+        let (year, month, day) = parse_date(date)?;
+        let date = format!("{:04}-{:02}-{:02}", year, month, day);
+        let mut rng = Rng::new(get_checksum(&date));
+
+        let starting_credits = config.starting_credits.pick(&mut rng).into();
+        let starting_resources = Resource::all()
+            .iter()
+            .filter(|resource| **resource != Resource::Credits)
+            .map(|resource| (*resource, config.starting_resources.pick(&mut rng).into()))
+            .collect();
+        let twist = rng.pick(&config.twists)?.clone();
+
+        Some(Challenge {
+            date,
+            turns: config.turns,
+            starting_credits,
+            starting_resources,
+            twist,
+            score_weights: config.score.clone(),
+            final_score: None,
+        })
+    }
+
+    pub fn get_date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn get_turns(&self) -> u32 {
+        self.turns
+    }
+
+    pub fn get_starting_credits(&self) -> ResourceAmount {
+        self.starting_credits
+    }
+
+    pub fn get_starting_resources(&self) -> &[(Resource, ResourceAmount)] {
+        &self.starting_resources
+    }
+
+    pub fn get_modifier_source(&self) -> ModifierSource {
+        ModifierSource::Event(format!("Challenge: {}", self.twist.name))
+    }
+
+    pub fn get_modifiers(&self) -> Vec<Modifier> {
+        self.twist
+            .modifiers
+            .iter()
+            .map(|modifier| modifier.to_modifier(self.get_modifier_source(), ModifierScope::Empire))
+            .collect()
+    }
+
+    pub fn get_score(&self, tally: &ColonyTally) -> u32 {
+        self.score_weights.get_score(tally)
+    }
+
+    pub fn finish(&mut self, score: u32) {
+        self.final_score = Some(score);
+    }
+
+    pub fn get_final_score(&self) -> Option<u32> {
+        self.final_score
+    }
+
+    // Starting conditions and the twist, shown when the challenge starts
+    pub fn get_summary(&self) -> String {
+        let resources: Vec<String> = self
+            .starting_resources
+            .iter()
+            .map(|(resource, amount)| format!("{} {}", amount, resource))
+            .collect();
+        let modifiers: Vec<String> = self.twist.modifiers.iter().map(ModifierConfig::to_string).collect();
+        format!(
+            "Daily challenge {}: score as much as you can in {} turns. Start with {} credits and {}. Twist: {} ({}).",
+            self.date,
+            self.turns,
+            self.starting_credits,
+            resources.join(", "),
+            self.twist.name,
+            modifiers.join(", "),
+        )
+    }
+
+    // A line to paste elsewhere, the code catches typos and casual edits of the score
+    pub fn get_result_line(&self) -> Option<String> {
+        let score = self.final_score?;
+        let code = get_checksum(&format!("{}:{}:{}", self.date, self.turns, score)) % 0x10000;
+        Some(format!("TerminalColony daily {} | {} turns | score {} | #{:04X}", self.date, self.turns, score, code))
+    }
+}
+
+// Today's date in UTC as YYYY-MM-DD, the seed of today's challenge
+pub fn get_todays_date() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    let (year, month, day) = get_civil_date(secs / 86_400);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn parse_date(date: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = date.trim().splitn(3, '-').map(|part| part.parse::<u32>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+// Year, month and day of a day counted from 1970-01-01, after Howard Hinnant's civil_from_days
+fn get_civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
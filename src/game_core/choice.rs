@@ -0,0 +1,25 @@
+// A decision the game puts in front of the player. The interface shows it as a modal dialog,
+// picking an option runs its command exactly as if it was typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    // Changes whenever a different decision is asked for
+    pub id: String,
+    pub title: String,
+    pub text: String,
+    pub options: Vec<ChoiceOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChoiceOption {
+    pub label: String,
+    pub command: String,
+}
+
+impl ChoiceOption {
+    pub fn new(label: &str, command: &str) -> Self {
+        ChoiceOption {
+            label: label.to_string(),
+            command: command.to_string(),
+        }
+    }
+}
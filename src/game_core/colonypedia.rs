@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::command::CommandCategory;
+use super::{BuildingsConfig, CommandRegistry};
+
+#[derive(Debug)]
+pub enum ColonypediaError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateEntry(String),
+    UnknownLink { entry: String, link: String },
+}
+
+impl fmt::Display for ColonypediaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColonypediaError::Io(e) => write!(f, "Failed to read lore file: {}", e),
+            ColonypediaError::Toml(e) => write!(f, "Failed to parse lore file (TOML): {}", e),
+            ColonypediaError::DuplicateEntry(id) => write!(f, "Lore entry '{}' is defined more than once", id),
+            ColonypediaError::UnknownLink { entry, link } => write!(
+                f, "Lore entry '{}' links to unknown entry '{}'", entry, link
+            ),
+        }
+    }
+}
+
+impl Error for ColonypediaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ColonypediaError::Io(e) => Some(e),
+            ColonypediaError::Toml(e) => Some(e),
+            ColonypediaError::DuplicateEntry(_) => None,
+            ColonypediaError::UnknownLink { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ColonypediaError {
+    fn from(err: io::Error) -> Self {
+        ColonypediaError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ColonypediaError {
+    fn from(err: toml::de::Error) -> Self {
+        ColonypediaError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct LoreEntryConfig {
+    // An id matching a building adds the text to that building's page
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    text: String,
+    #[serde(default)]
+    links: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LoreConfig {
+    #[serde(default)]
+    entries: Vec<LoreEntryConfig>,
+}
+
+// TODO: Add ship and technology categories once those configs exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PediaCategory {
+    // First, so help opens on the commands
+    Command,
+    Building,
+    Lore,
+}
+
+impl fmt::Display for PediaCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PediaCategory::Command => write!(f, "Commands"),
+            PediaCategory::Building => write!(f, "Buildings"),
+            PediaCategory::Lore => write!(f, "Lore"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PediaEntry {
+    pub id: String,
+    pub title: String,
+    pub category: PediaCategory,
+    pub lines: Vec<String>,
+    // Entries this one refers to and entries referring to this one
+    pub links: Vec<String>,
+    pub linked_from: Vec<String>,
+}
+
+impl PediaEntry {
+    // Every entry reachable from this one, in the order they are numbered on the page
+    pub fn get_all_links(&self) -> Vec<&str> {
+        let mut all_links: Vec<&str> = Vec::new();
+        for link in self.links.iter().chain(&self.linked_from) {
+            if !all_links.contains(&link.as_str()) {
+                all_links.push(link);
+            }
+        }
+        all_links
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Colonypedia {
+    entries: Vec<PediaEntry>,
+}
+
+impl Colonypedia {
+    // Generates an entry for every configured building and a help table for every command
+    // category, merged with the hand-written lore. A missing lore file only means there is no lore.
+    pub fn load(
+        lore_path: &Path,
+        buildings_config: &BuildingsConfig,
+        command_registry: &CommandRegistry,
+    ) -> Result<Self, ColonypediaError> {
+        let lore = match fs::read_to_string(lore_path) {
+            Ok(content) => toml::from_str(&content)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => LoreConfig { entries: Vec::new() },
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut entries: HashMap<String, PediaEntry> = HashMap::new();
+        for (id, config) in &buildings_config.buildings {
+            let mut lines = Vec::new();
+            lines.push(format!("Max level: {}", config.get_max_level()));
+
+            let cost = config.get_upgrade_cost();
+            let first = |values: &[u32]| values.first().cloned().unwrap_or(0);
+            lines.push(format!(
+                "Cost (level 1): {} energy, {} minerals, {} gas",
+                first(&cost.energy), first(&cost.minerals), first(&cost.gas)
+            ));
+            lines.push(format!(
+                "Build time (level 1): {} turn(s)",
+                first(&config.get_building_time().time_per_level)
+            ));
+
+            if let Some(production) = config.get_production() {
+                lines.push(format!(
+                    "Produces {}: +{} per turn at level 1, +{} at max level",
+                    production.get_resource(),
+                    production.get_rate_for_level(1).unwrap_or(0),
+                    production.rate_per_level.last().cloned().unwrap_or(0),
+                ));
+            }
+            if let Some(storage) = config.get_storage() {
+                lines.push(format!(
+                    "Stores {}: {} at level 1, {} at max level",
+                    storage.get_resource(),
+                    storage.get_capacity_for_level(1).unwrap_or(0),
+                    storage.capacity_per_level.last().cloned().unwrap_or(0),
+                ));
+            }
+            if let Some(morale) = config.get_morale() {
+                lines.push(format!(
+                    "Raises morale on its planet by up to {} points at max level",
+                    morale.bonus_per_level.last().cloned().unwrap_or(0),
+                ));
+            }
+
+            entries.insert(id.clone(), PediaEntry {
+                id: id.clone(),
+                title: config.get_name().to_string(),
+                category: PediaCategory::Building,
+                lines,
+                links: Vec::new(),
+                linked_from: Vec::new(),
+            });
+        }
+
+        for category in CommandCategory::all() {
+            let lines = command_registry.get_help_lines(Some(category));
+            if lines.is_empty() {
+                continue;
+            }
+            let id = format!("{}Commands", category);
+            entries.insert(id.clone(), PediaEntry {
+                id,
+                title: format!("{} Commands", category),
+                category: PediaCategory::Command,
+                lines,
+                links: Vec::new(),
+                linked_from: Vec::new(),
+            });
+        }
+
+        let mut seen_lore = Vec::new();
+        for lore_entry in lore.entries {
+            if seen_lore.contains(&lore_entry.id) {
+                return Err(ColonypediaError::DuplicateEntry(lore_entry.id));
+            }
+            seen_lore.push(lore_entry.id.clone());
+
+            let entry = entries.entry(lore_entry.id.clone()).or_insert_with(|| PediaEntry {
+                id: lore_entry.id.clone(),
+                title: lore_entry.title.clone().unwrap_or_else(|| lore_entry.id.clone()),
+                category: PediaCategory::Lore,
+                lines: Vec::new(),
+                links: Vec::new(),
+                linked_from: Vec::new(),
+            });
+            // Lore comes before the generated facts
+            entry.lines.splice(0..0, [lore_entry.text.trim().to_string(), String::new()]);
+            entry.links.extend(lore_entry.links);
+        }
+
+        let mut backlinks: Vec<(String, String)> = Vec::new();
+        for entry in entries.values() {
+            for link in &entry.links {
+                if !entries.contains_key(link) {
+                    return Err(ColonypediaError::UnknownLink { entry: entry.id.clone(), link: link.clone() });
+                }
+                backlinks.push((link.clone(), entry.id.clone()));
+            }
+        }
+        for (target, source) in backlinks {
+            if let Some(entry) = entries.get_mut(&target) {
+                entry.linked_from.push(source);
+            }
+        }
+
+        let mut entries: Vec<PediaEntry> = entries.into_values().collect();
+        for entry in &mut entries {
+            entry.linked_from.sort();
+        }
+        entries.sort_by(|a, b| a.category.cmp(&b.category).then_with(|| a.title.cmp(&b.title)));
+
+        Ok(Colonypedia { entries })
+    }
+
+    pub fn get_entries(&self) -> &[PediaEntry] {
+        &self.entries
+    }
+
+    pub fn find_index(&self, id: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.id == id)
+    }
+}
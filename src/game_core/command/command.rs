@@ -1,6 +1,8 @@
 use std::fmt;
 use std::error::Error;
 
+use crate::game_core::{Resource, ResourceAmount};
+
 use super::{CommandDefinition, CommandRegistry};
 
 #[derive(Debug)]
@@ -24,6 +26,23 @@ impl fmt::Display for CommandError {
 
 impl Error for CommandError {}
 
+// What a dry run of the command being typed found, before it is entered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputCheck {
+    // Fine so far, but arguments are still missing
+    Incomplete(String),
+    // Fails as it is when entered
+    Invalid(String),
+}
+
+impl fmt::Display for InputCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputCheck::Incomplete(reason) | InputCheck::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
 // =================================================================================================
 
 #[derive(Debug, Clone)]
@@ -33,18 +52,58 @@ pub struct ParsedCommand {
     args: Vec<String>,
 }
 
+impl ParsedCommand {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn get_definition(&self) -> &CommandDefinition {
+        &self.definition
+    }
+}
+
 #[derive(Debug)]
 pub enum CommandExecution {
     Help(ParsedCommand),
     Build(BuildCommand),
+    Rush(RushCommand),
+    Confirm(ConfirmCommand),
     EndTurn(EndTurnCommand),
+    Wait(WaitCommand),
+    Speed(SpeedCommand),
     Quit(QuitCommand),
+    Surrender(SurrenderCommand),
+    Player(PlayerCommand),
+    Rules(RulesCommand),
+    Macro(MacroCommand),
+    Profile(ProfileCommand),
+    Tax(TaxCommand),
+    Governor(GovernorCommand),
+    Queue(QueueCommand),
+    Edict(EdictCommand),
+    Artifacts(ArtifactsCommand),
+    Synergies(SynergiesCommand),
+    Compare(CompareCommand),
+    Breakdown(BreakdownCommand),
+    Timeline(TimelineCommand),
+    Production(ProductionCommand),
+    Merchant(MerchantCommand),
+    Disaster(DisasterCommand),
+    Designate(DesignateCommand),
+    Stockpile(StockpileCommand),
+    Tutorial(TutorialCommand),
+    Challenge(ChallengeCommand),
+    Script(ParsedCommand),
     UnknownInternal(ParsedCommand),
 }
 
 impl CommandExecution {
     pub fn parse(command_registry: &CommandRegistry, input: &str) -> Result<CommandExecution, CommandError> {
-        let parts: Vec<&str> = input.trim().split_whitespace().collect();
+        let parts: Vec<&str> = input.split_whitespace().collect();
         if parts.is_empty() {
             return Err(CommandError::new("No command provided. Type 'help' for options."));
         }
@@ -63,20 +122,120 @@ impl CommandExecution {
                             args: provided_args,
                         };
 
-                        match command_name.as_str() {
+                        // Aliases resolve to the definition they belong to
+                        match matching_def.name.as_str() {
                             "help" => Ok(CommandExecution::Help(parsed_cmd)),
                             "build" => {
                                 let build_cmd = BuildCommand::try_from(parsed_cmd)?;
                                 Ok(CommandExecution::Build(build_cmd))
                             }
+                            "rush" => {
+                                let rush_cmd = RushCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Rush(rush_cmd))
+                            }
+                            "confirm" => Ok(CommandExecution::Confirm(ConfirmCommand::new(&parsed_cmd.name, true))),
+                            "cancel" => Ok(CommandExecution::Confirm(ConfirmCommand::new(&parsed_cmd.name, false))),
                             "endturn" => {
                                 let end_turn_cmd = EndTurnCommand::try_from(parsed_cmd)?;
                                 Ok(CommandExecution::EndTurn(end_turn_cmd))
                             }
+                            "wait" => {
+                                let wait_cmd = WaitCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Wait(wait_cmd))
+                            }
+                            "speed" => {
+                                let speed_cmd = SpeedCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Speed(speed_cmd))
+                            }
                             "quit" => {
                                 let quit_cmd = QuitCommand::try_from(parsed_cmd)?;
                                 Ok(CommandExecution::Quit(quit_cmd))
                             }
+                            "player" => {
+                                let player_cmd = PlayerCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Player(player_cmd))
+                            }
+                            "rules" => {
+                                let rules_cmd = RulesCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Rules(rules_cmd))
+                            }
+                            "surrender" => {
+                                let surrender_cmd = SurrenderCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Surrender(surrender_cmd))
+                            }
+                            "macro" => {
+                                let macro_cmd = MacroCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Macro(macro_cmd))
+                            }
+                            "profile" => {
+                                let profile_cmd = ProfileCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Profile(profile_cmd))
+                            }
+                            "tax" => {
+                                let tax_cmd = TaxCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Tax(tax_cmd))
+                            }
+                            "queue" => {
+                                let queue_cmd = QueueCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Queue(queue_cmd))
+                            }
+                            "governors" | "hire-governor" | "assign-governor" => {
+                                let governor_cmd = GovernorCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Governor(governor_cmd))
+                            }
+                            "edict" => {
+                                let edict_cmd = EdictCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Edict(edict_cmd))
+                            }
+                            "artifacts" => {
+                                let artifacts_cmd = ArtifactsCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Artifacts(artifacts_cmd))
+                            }
+                            "breakdown" => {
+                                let breakdown_cmd = BreakdownCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Breakdown(breakdown_cmd))
+                            }
+                            "synergies" => {
+                                let synergies_cmd = SynergiesCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Synergies(synergies_cmd))
+                            }
+                            "compare" => {
+                                let compare_cmd = CompareCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Compare(compare_cmd))
+                            }
+                            "timeline" => {
+                                let timeline_cmd = TimelineCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Timeline(timeline_cmd))
+                            }
+                            "production" => {
+                                let production_cmd = ProductionCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Production(production_cmd))
+                            }
+                            "merchant" => {
+                                let merchant_cmd = MerchantCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Merchant(merchant_cmd))
+                            }
+                            "disaster" => {
+                                let disaster_cmd = DisasterCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Disaster(disaster_cmd))
+                            }
+                            "designate" => {
+                                let designate_cmd = DesignateCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Designate(designate_cmd))
+                            }
+                            "stockpile" => {
+                                let stockpile_cmd = StockpileCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Stockpile(stockpile_cmd))
+                            }
+                            "tutorial" => {
+                                let tutorial_cmd = TutorialCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Tutorial(tutorial_cmd))
+                            }
+                            "challenge" => {
+                                let challenge_cmd = ChallengeCommand::try_from(parsed_cmd)?;
+                                Ok(CommandExecution::Challenge(challenge_cmd))
+                            }
+                            _ if matching_def.script.is_some() => Ok(CommandExecution::Script(parsed_cmd)),
                             _ => Ok(CommandExecution::UnknownInternal(parsed_cmd)),
                         }
                     }
@@ -97,6 +256,43 @@ impl CommandExecution {
             ))),
         }
     }
+
+    // The name the command was typed with, which may be an alias
+    pub fn get_name(&self) -> &str {
+        match self {
+            CommandExecution::Help(command)
+            | CommandExecution::Script(command)
+            | CommandExecution::UnknownInternal(command) => command.get_name(),
+            CommandExecution::Build(command) => &command.name,
+            CommandExecution::Rush(command) => &command.name,
+            CommandExecution::Confirm(command) => &command.name,
+            CommandExecution::EndTurn(command) => &command.name,
+            CommandExecution::Wait(command) => &command.name,
+            CommandExecution::Speed(command) => &command.name,
+            CommandExecution::Quit(command) => &command.name,
+            CommandExecution::Surrender(command) => &command.name,
+            CommandExecution::Player(command) => &command.name,
+            CommandExecution::Rules(command) => &command.name,
+            CommandExecution::Macro(command) => &command.name,
+            CommandExecution::Profile(command) => &command.name,
+            CommandExecution::Tax(command) => &command.name,
+            CommandExecution::Governor(command) => &command.name,
+            CommandExecution::Queue(command) => &command.name,
+            CommandExecution::Edict(command) => &command.name,
+            CommandExecution::Artifacts(command) => &command.name,
+            CommandExecution::Synergies(command) => &command.name,
+            CommandExecution::Compare(command) => &command.name,
+            CommandExecution::Breakdown(command) => &command.name,
+            CommandExecution::Timeline(command) => &command.name,
+            CommandExecution::Production(command) => &command.name,
+            CommandExecution::Merchant(command) => &command.name,
+            CommandExecution::Disaster(command) => &command.name,
+            CommandExecution::Designate(command) => &command.name,
+            CommandExecution::Stockpile(command) => &command.name,
+            CommandExecution::Tutorial(command) => &command.name,
+            CommandExecution::Challenge(command) => &command.name,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -149,19 +345,47 @@ impl TryFrom<ParsedCommand> for QuitCommand {
     }
 }
 
+#[derive(Debug)]
+pub struct SurrenderCommand {
+    name: String,
+}
+
+impl SurrenderCommand {
+    pub fn new(name: &str) -> Self {
+        SurrenderCommand {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl TryFrom<ParsedCommand> for SurrenderCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        if parsed_command.args.is_empty() {
+            Ok(SurrenderCommand::new(&parsed_command.name))
+        } else {
+            Err(CommandError::new("Surrender command does not accept any arguments."))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BuildCommand {
     name: String,
     building: String,
     planet: String,
+    // Levels queued back to back
+    count: u8,
 }
 
 impl BuildCommand {
-    pub fn new(name: &str, building: &str, planet: &str) -> Self {
+    pub fn new(name: &str, building: &str, planet: &str, count: u8) -> Self {
         BuildCommand {
             name: name.to_string(),
             building: building.to_string(),
             planet: planet.to_string(),
+            count,
         }
     }
 
@@ -172,6 +396,10 @@ impl BuildCommand {
     pub fn get_planet(&self) -> &str {
         &self.planet
     }
+
+    pub fn get_count(&self) -> u8 {
+        self.count
+    }
 }
 
 impl TryFrom<ParsedCommand> for BuildCommand {
@@ -183,9 +411,19 @@ impl TryFrom<ParsedCommand> for BuildCommand {
             return Err(CommandError::new("Not enough arguments for build command."));
         }
 
-        if let Some(building) = args.get(0) {
+        // Written as x3 or just 3
+        let count = match args.get(2) {
+            Some(count) => count.trim_start_matches(['x', 'X'])
+                .parse::<u8>()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or_else(|| CommandError::new(&format!("Invalid count '{}', use e.g. x3.", count)))?,
+            None => 1,
+        };
+
+        if let Some(building) = args.first() {
             if let Some(planet) = args.get(1) {
-                Ok(BuildCommand::new(&parsed_command.name, building, planet))
+                Ok(BuildCommand::new(&parsed_command.name, building, planet, count))
             } else {
                 Err(CommandError::new("Planet argument is missing."))
             }
@@ -193,4 +431,885 @@ impl TryFrom<ParsedCommand> for BuildCommand {
             Err(CommandError::new("Building argument is missing."))
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug)]
+pub struct RushCommand {
+    name: String,
+    building: String,
+    planet: String,
+}
+
+impl RushCommand {
+    pub fn new(name: &str, building: &str, planet: &str) -> Self {
+        RushCommand {
+            name: name.to_string(),
+            building: building.to_string(),
+            planet: planet.to_string(),
+        }
+    }
+
+    pub fn get_building(&self) -> &str {
+        &self.building
+    }
+
+    pub fn get_planet(&self) -> &str {
+        &self.planet
+    }
+}
+
+impl TryFrom<ParsedCommand> for RushCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = parsed_command.args;
+        match (args.first(), args.get(1)) {
+            (Some(building), Some(planet)) => Ok(RushCommand::new(&parsed_command.name, building, planet)),
+            _ => Err(CommandError::new("Usage: rush <building> <planet>")),
+        }
+    }
+}
+
+// Answers the action waiting for confirmation, either accepting or cancelling it
+#[derive(Debug)]
+pub struct ConfirmCommand {
+    name: String,
+    accepted: bool,
+}
+
+impl ConfirmCommand {
+    pub fn new(name: &str, accepted: bool) -> Self {
+        ConfirmCommand {
+            name: name.to_string(),
+            accepted,
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.accepted
+    }
+}
+
+// Keeps a mistyped count from running the game for a long time
+const MAX_WAIT_TURNS: u32 = 100;
+
+#[derive(Debug)]
+pub struct WaitCommand {
+    name: String,
+    turns: u32,
+}
+
+impl WaitCommand {
+    pub fn new(name: &str, turns: u32) -> Self {
+        WaitCommand {
+            name: name.to_string(),
+            turns,
+        }
+    }
+
+    pub fn get_turns(&self) -> u32 {
+        self.turns
+    }
+}
+
+impl TryFrom<ParsedCommand> for WaitCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let turns = parsed_command.args.first().ok_or_else(|| CommandError::new("Turns argument is missing."))?;
+        match turns.parse::<u32>() {
+            Ok(count) if (1..=MAX_WAIT_TURNS).contains(&count) => Ok(WaitCommand::new(&parsed_command.name, count)),
+            _ => Err(CommandError::new(&format!(
+                "Invalid number of turns '{}'. Expected 1 to {}.", turns, MAX_WAIT_TURNS
+            ))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SpeedCommand {
+    name: String,
+    // Shows the current speed when not set
+    speed: Option<String>,
+}
+
+impl SpeedCommand {
+    pub fn new(name: &str, speed: Option<String>) -> Self {
+        SpeedCommand {
+            name: name.to_string(),
+            speed,
+        }
+    }
+
+    pub fn get_speed(&self) -> Option<&str> {
+        self.speed.as_deref()
+    }
+}
+
+impl TryFrom<ParsedCommand> for SpeedCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        Ok(SpeedCommand::new(&parsed_command.name, parsed_command.args.first().map(|speed| speed.to_lowercase())))
+    }
+}
+
+#[derive(Debug)]
+pub struct TaxCommand {
+    name: String,
+    planet: String,
+    // Only shows the current rate when not set
+    rate: Option<u8>,
+}
+
+impl TaxCommand {
+    pub fn new(name: &str, planet: &str, rate: Option<u8>) -> Self {
+        TaxCommand {
+            name: name.to_string(),
+            planet: planet.to_string(),
+            rate,
+        }
+    }
+
+    pub fn get_planet(&self) -> &str {
+        &self.planet
+    }
+
+    pub fn get_rate(&self) -> Option<u8> {
+        self.rate
+    }
+}
+
+impl TryFrom<ParsedCommand> for TaxCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = parsed_command.args;
+        let planet = args.first().ok_or_else(|| CommandError::new("Planet argument is missing."))?;
+
+        let rate = match args.get(1) {
+            Some(rate) => Some(rate.trim_end_matches('%').parse::<u8>().map_err(|_| {
+                CommandError::new(&format!("Invalid tax rate '{}'. Expected a percentage like 20.", rate))
+            })?),
+            None => None,
+        };
+
+        Ok(TaxCommand::new(&parsed_command.name, planet, rate))
+    }
+}
+
+#[derive(Debug)]
+pub enum QueueAction {
+    Show(String),
+    // Positions count from 1, the planet can be left out by players with a single one
+    Move { planet: Option<String>, position: usize, new_position: usize },
+}
+
+#[derive(Debug)]
+pub struct QueueCommand {
+    name: String,
+    action: QueueAction,
+}
+
+impl QueueCommand {
+    pub fn new(name: &str, action: QueueAction) -> Self {
+        QueueCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &QueueAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for QueueCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = &parsed_command.args;
+        let usage = || CommandError::new(
+            "Usage: queue <planet> | queue move <position> <new_position> | queue move <planet> <position> <new_position>"
+        );
+        let parse_position = |position: &str| position.parse::<usize>().map_err(|_| {
+            CommandError::new(&format!("Invalid queue position '{}'. Positions count from 1.", position))
+        });
+
+        let action = match args.as_slice() {
+            [planet] => QueueAction::Show(planet.clone()),
+            [action, position, new_position] if action.eq_ignore_ascii_case("move") => QueueAction::Move {
+                planet: None,
+                position: parse_position(position)?,
+                new_position: parse_position(new_position)?,
+            },
+            [action, planet, position, new_position] if action.eq_ignore_ascii_case("move") => QueueAction::Move {
+                planet: Some(planet.clone()),
+                position: parse_position(position)?,
+                new_position: parse_position(new_position)?,
+            },
+            _ => return Err(usage()),
+        };
+
+        Ok(QueueCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug)]
+pub enum GovernorAction {
+    List,
+    Hire(String),
+    Assign { governor: String, planet: String },
+}
+
+#[derive(Debug)]
+pub struct GovernorCommand {
+    name: String,
+    action: GovernorAction,
+}
+
+impl GovernorCommand {
+    pub fn new(name: &str, action: GovernorAction) -> Self {
+        GovernorCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &GovernorAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for GovernorCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = &parsed_command.args;
+        let action = match (parsed_command.definition.name.as_str(), args.len()) {
+            ("governors", 0) => GovernorAction::List,
+            ("hire-governor", 1) => GovernorAction::Hire(args[0].clone()),
+            ("assign-governor", 2) => GovernorAction::Assign { governor: args[0].clone(), planet: args[1].clone() },
+            _ => return Err(CommandError::new(
+                "Usage: governors | hire-governor <governor> | assign-governor <governor> <planet>"
+            )),
+        };
+
+        Ok(GovernorCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug)]
+pub struct EdictCommand {
+    name: String,
+    // Lists the edicts when not set
+    edict: Option<String>,
+}
+
+impl EdictCommand {
+    pub fn new(name: &str, edict: Option<String>) -> Self {
+        EdictCommand {
+            name: name.to_string(),
+            edict,
+        }
+    }
+
+    pub fn get_edict(&self) -> Option<&str> {
+        self.edict.as_deref()
+    }
+}
+
+impl TryFrom<ParsedCommand> for EdictCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        Ok(EdictCommand::new(&parsed_command.name, parsed_command.args.first().cloned()))
+    }
+}
+
+#[derive(Debug)]
+pub struct ArtifactsCommand {
+    name: String,
+}
+
+impl ArtifactsCommand {
+    pub fn new(name: &str) -> Self {
+        ArtifactsCommand {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl TryFrom<ParsedCommand> for ArtifactsCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        if parsed_command.args.is_empty() {
+            Ok(ArtifactsCommand::new(&parsed_command.name))
+        } else {
+            Err(CommandError::new("Artifacts command does not accept any arguments."))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SynergiesCommand {
+    name: String,
+    planet: String,
+}
+
+impl SynergiesCommand {
+    pub fn new(name: &str, planet: &str) -> Self {
+        SynergiesCommand {
+            name: name.to_string(),
+            planet: planet.to_string(),
+        }
+    }
+
+    pub fn get_planet(&self) -> &str {
+        &self.planet
+    }
+}
+
+impl TryFrom<ParsedCommand> for SynergiesCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let planet = parsed_command.args.first().ok_or_else(|| CommandError::new("Planet argument is missing."))?;
+        Ok(SynergiesCommand::new(&parsed_command.name, planet))
+    }
+}
+
+#[derive(Debug)]
+pub struct CompareCommand {
+    name: String,
+    planets: (String, String),
+}
+
+impl CompareCommand {
+    pub fn new(name: &str, first: &str, second: &str) -> Self {
+        CompareCommand {
+            name: name.to_string(),
+            planets: (first.to_string(), second.to_string()),
+        }
+    }
+
+    pub fn get_planets(&self) -> (&str, &str) {
+        (&self.planets.0, &self.planets.1)
+    }
+}
+
+impl TryFrom<ParsedCommand> for CompareCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        match parsed_command.args.as_slice() {
+            [first, second] => Ok(CompareCommand::new(&parsed_command.name, first, second)),
+            _ => Err(CommandError::new("Two planet arguments are required.")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BreakdownCommand {
+    name: String,
+    resource: Resource,
+    planet: String,
+}
+
+impl BreakdownCommand {
+    pub fn new(name: &str, resource: Resource, planet: &str) -> Self {
+        BreakdownCommand {
+            name: name.to_string(),
+            resource,
+            planet: planet.to_string(),
+        }
+    }
+
+    pub fn get_resource(&self) -> Resource {
+        self.resource
+    }
+
+    pub fn get_planet(&self) -> &str {
+        &self.planet
+    }
+}
+
+impl TryFrom<ParsedCommand> for BreakdownCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = parsed_command.args;
+        let (Some(resource), Some(planet)) = (args.first(), args.get(1)) else {
+            return Err(CommandError::new("Usage: breakdown <resource> <planet>"));
+        };
+        let resource = Resource::from_name(resource).ok_or_else(|| {
+            CommandError::new(&format!("Unknown resource '{}'. Expected Energy, Minerals, Gas or Credits.", resource))
+        })?;
+
+        Ok(BreakdownCommand::new(&parsed_command.name, resource, planet))
+    }
+}
+
+#[derive(Debug)]
+pub struct TimelineCommand {
+    name: String,
+}
+
+impl TimelineCommand {
+    pub fn new(name: &str) -> Self {
+        TimelineCommand {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl TryFrom<ParsedCommand> for TimelineCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        if parsed_command.args.is_empty() {
+            Ok(TimelineCommand::new(&parsed_command.name))
+        } else {
+            Err(CommandError::new("Timeline command does not accept any arguments."))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ProductionCommand {
+    name: String,
+    // Column the planets are sorted by, their names when not set
+    sort_by: Option<Resource>,
+}
+
+impl ProductionCommand {
+    pub fn new(name: &str, sort_by: Option<Resource>) -> Self {
+        ProductionCommand {
+            name: name.to_string(),
+            sort_by,
+        }
+    }
+
+    pub fn get_sort_by(&self) -> Option<Resource> {
+        self.sort_by
+    }
+}
+
+impl TryFrom<ParsedCommand> for ProductionCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let sort_by = match parsed_command.args.first() {
+            Some(column) if column.eq_ignore_ascii_case("planet") => None,
+            Some(column) => Some(Resource::from_name(column).ok_or_else(|| {
+                CommandError::new(&format!(
+                    "Unknown column '{}'. Sort by Planet, Energy, Minerals, Gas or Credits.", column
+                ))
+            })?),
+            None => None,
+        };
+
+        Ok(ProductionCommand::new(&parsed_command.name, sort_by))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MerchantAction {
+    Show,
+    Accept,
+    Decline,
+}
+
+#[derive(Debug)]
+pub struct MerchantCommand {
+    name: String,
+    action: MerchantAction,
+}
+
+impl MerchantCommand {
+    pub fn new(name: &str, action: MerchantAction) -> Self {
+        MerchantCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &MerchantAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for MerchantCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let action = match parsed_command.args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => MerchantAction::Show,
+            Some("accept") => MerchantAction::Accept,
+            Some("decline") => MerchantAction::Decline,
+            _ => return Err(CommandError::new("Usage: merchant | merchant accept | merchant decline")),
+        };
+
+        Ok(MerchantCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DisasterAction {
+    Show,
+    // Id of the response
+    Respond(String),
+}
+
+#[derive(Debug)]
+pub struct DisasterCommand {
+    name: String,
+    action: DisasterAction,
+}
+
+impl DisasterCommand {
+    pub fn new(name: &str, action: DisasterAction) -> Self {
+        DisasterCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &DisasterAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for DisasterCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let action = match parsed_command.args.first() {
+            None => DisasterAction::Show,
+            Some(response) => DisasterAction::Respond(response.clone()),
+        };
+
+        Ok(DisasterCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug)]
+pub struct StockpileTarget {
+    pub planet: String,
+    pub resource: Resource,
+    // Removes the target when not set
+    pub amount: Option<ResourceAmount>,
+}
+
+#[derive(Debug)]
+pub struct StockpileCommand {
+    name: String,
+    // Lists the targets when not set
+    target: Option<StockpileTarget>,
+}
+
+impl StockpileCommand {
+    pub fn new(name: &str, target: Option<StockpileTarget>) -> Self {
+        StockpileCommand {
+            name: name.to_string(),
+            target,
+        }
+    }
+
+    pub fn get_target(&self) -> Option<&StockpileTarget> {
+        self.target.as_ref()
+    }
+}
+
+impl TryFrom<ParsedCommand> for StockpileCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let target = match parsed_command.args.as_slice() {
+            [] => None,
+            [planet, resource, amount] => {
+                let resource = Resource::from_name(resource)
+                    .filter(|resource| *resource != Resource::Credits)
+                    .ok_or_else(|| {
+                        CommandError::new(&format!("Unknown resource '{}'. Expected Energy, Minerals or Gas.", resource))
+                    })?;
+                let amount = match amount.to_lowercase().as_str() {
+                    "none" => None,
+                    _ => Some(amount.parse::<u32>().map(ResourceAmount::new).map_err(|_| {
+                        CommandError::new(&format!("Invalid amount '{}'. Expected a number or 'none'.", amount))
+                    })?),
+                };
+                Some(StockpileTarget { planet: planet.clone(), resource, amount })
+            }
+            _ => return Err(CommandError::new("Usage: stockpile [<planet> <resource> <amount>]")),
+        };
+
+        Ok(StockpileCommand::new(&parsed_command.name, target))
+    }
+}
+
+#[derive(Debug)]
+pub struct DesignateCommand {
+    name: String,
+    // Planet and specialization id, "none" to drop the designation. Lists the specializations
+    // when not set.
+    designation: Option<(String, String)>,
+}
+
+impl DesignateCommand {
+    pub fn new(name: &str, designation: Option<(String, String)>) -> Self {
+        DesignateCommand {
+            name: name.to_string(),
+            designation,
+        }
+    }
+
+    pub fn get_designation(&self) -> Option<(&str, &str)> {
+        self.designation.as_ref().map(|(planet, specialization)| (planet.as_str(), specialization.as_str()))
+    }
+}
+
+impl TryFrom<ParsedCommand> for DesignateCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let designation = match parsed_command.args.as_slice() {
+            [] => None,
+            [planet, specialization] => Some((planet.clone(), specialization.clone())),
+            _ => return Err(CommandError::new("Usage: designate [<planet> <specialization>]")),
+        };
+
+        Ok(DesignateCommand::new(&parsed_command.name, designation))
+    }
+}
+
+#[derive(Debug)]
+pub enum MacroAction {
+    Record(String),
+    Stop,
+    List,
+    Play { macro_name: String, planet: Option<String> },
+}
+
+#[derive(Debug)]
+pub struct MacroCommand {
+    name: String,
+    action: MacroAction,
+}
+
+impl MacroCommand {
+    pub fn new(name: &str, action: MacroAction) -> Self {
+        MacroCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &MacroAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for MacroCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = parsed_command.args;
+        let subcommand = args.first().map(|arg| arg.to_lowercase()).unwrap_or_default();
+
+        let action = match (subcommand.as_str(), args.len()) {
+            ("record", 2) => MacroAction::Record(args[1].clone()),
+            ("stop", 1) => MacroAction::Stop,
+            ("list", 1) => MacroAction::List,
+            ("play", 2) => MacroAction::Play { macro_name: args[1].clone(), planet: None },
+            ("play", 3) => MacroAction::Play { macro_name: args[1].clone(), planet: Some(args[2].clone()) },
+            _ => return Err(CommandError::new(
+                "Usage: macro record <name> | macro stop | macro list | macro play <name> [planet]"
+            )),
+        };
+
+        Ok(MacroCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlayerAction {
+    Show,
+    SetName(String),
+    SetColor(String),
+}
+
+#[derive(Debug)]
+pub struct PlayerCommand {
+    name: String,
+    action: PlayerAction,
+}
+
+impl PlayerCommand {
+    pub fn new(name: &str, action: PlayerAction) -> Self {
+        PlayerCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &PlayerAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for PlayerCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let args = &parsed_command.args;
+        let action = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => PlayerAction::Show,
+            Some("name") => PlayerAction::SetName(args[1].clone()),
+            Some("color") => PlayerAction::SetColor(args[1].clone()),
+            _ => return Err(CommandError::new("Usage: player | player name <name> | player color <color>")),
+        };
+
+        Ok(PlayerCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug)]
+pub struct RulesCommand {
+    name: String,
+    // Rule and its new value, shows the rules when not set
+    change: Option<(String, String)>,
+}
+
+impl RulesCommand {
+    pub fn new(name: &str, change: Option<(String, String)>) -> Self {
+        RulesCommand {
+            name: name.to_string(),
+            change,
+        }
+    }
+
+    pub fn get_change(&self) -> Option<(&str, &str)> {
+        self.change.as_ref().map(|(rule, value)| (rule.as_str(), value.as_str()))
+    }
+}
+
+impl TryFrom<ParsedCommand> for RulesCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let change = match parsed_command.args.as_slice() {
+            [] => None,
+            [rule, value] => Some((rule.clone(), value.clone())),
+            _ => return Err(CommandError::new("Usage: rules | rules <rule> <value>")),
+        };
+        Ok(RulesCommand::new(&parsed_command.name, change))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProfileAction {
+    Show,
+    Reset,
+}
+
+#[derive(Debug)]
+pub struct ProfileCommand {
+    name: String,
+    action: ProfileAction,
+}
+
+impl ProfileCommand {
+    pub fn new(name: &str, action: ProfileAction) -> Self {
+        ProfileCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &ProfileAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for ProfileCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let action = match parsed_command.args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => ProfileAction::Show,
+            Some("reset") => ProfileAction::Reset,
+            _ => return Err(CommandError::new("Usage: profile | profile reset")),
+        };
+
+        Ok(ProfileCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TutorialAction {
+    Start,
+    Stop,
+}
+
+#[derive(Debug)]
+pub struct TutorialCommand {
+    name: String,
+    action: TutorialAction,
+}
+
+impl TutorialCommand {
+    pub fn new(name: &str, action: TutorialAction) -> Self {
+        TutorialCommand {
+            name: name.to_string(),
+            action,
+        }
+    }
+
+    pub fn get_action(&self) -> &TutorialAction {
+        &self.action
+    }
+}
+
+impl TryFrom<ParsedCommand> for TutorialCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        let action = match parsed_command.args.first().map(|arg| arg.to_lowercase()).as_deref() {
+            None => TutorialAction::Start,
+            Some("stop") => TutorialAction::Stop,
+            _ => return Err(CommandError::new("Usage: tutorial | tutorial stop")),
+        };
+
+        Ok(TutorialCommand::new(&parsed_command.name, action))
+    }
+}
+
+#[derive(Debug)]
+pub struct ChallengeCommand {
+    name: String,
+    // Shows the running challenge when not set
+    date: Option<String>,
+}
+
+impl ChallengeCommand {
+    pub fn new(name: &str, date: Option<String>) -> Self {
+        ChallengeCommand {
+            name: name.to_string(),
+            date,
+        }
+    }
+
+    pub fn get_date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+}
+
+impl TryFrom<ParsedCommand> for ChallengeCommand {
+    type Error = CommandError;
+
+    fn try_from(parsed_command: ParsedCommand) -> Result<Self, Self::Error> {
+        Ok(ChallengeCommand::new(&parsed_command.name, parsed_command.args.first().cloned()))
+    }
+}
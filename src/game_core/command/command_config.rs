@@ -42,15 +42,77 @@ impl From<toml::de::Error> for CommandLoadError {
 
 // =================================================================================================
 
+// What a command is about, help lists the commands grouped by it
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandCategory {
+    Economy,
+    Military,
+    System,
+    // Added by script files rather than commands.toml
+    Scripted,
+}
+
+impl CommandCategory {
+    pub fn all() -> [CommandCategory; 4] {
+        [CommandCategory::Economy, CommandCategory::Military, CommandCategory::System, CommandCategory::Scripted]
+    }
+}
+
+impl fmt::Display for CommandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandCategory::Economy => write!(f, "Economy"),
+            CommandCategory::Military => write!(f, "Military"),
+            CommandCategory::System => write!(f, "System"),
+            CommandCategory::Scripted => write!(f, "Scripted"),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct CommandDefinition {
     pub name: String,
+    pub category: CommandCategory,
     #[serde(default)]
     pub aliases: Vec<String>,
     pub description: String,
     pub expected_args: usize,
     #[serde(default)]
     pub arg_hints: Vec<String>,
+    // Asks for confirmation before running, for commands that are hard to undo
+    #[serde(default)]
+    pub confirm: bool,
+    // Rhai code run by the command, with `game` and `args` in scope
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+impl CommandDefinition {
+    // The command as it is typed, e.g. `build <building> <planet>`
+    pub fn get_usage(&self) -> String {
+        let mut usage = self.name.clone();
+        for idx in 0..self.expected_args {
+            usage.push(' ');
+            usage.push_str(&self.get_arg_hint(idx));
+        }
+        usage
+    }
+
+    fn get_arg_hint(&self, idx: usize) -> String {
+        match self.arg_hints.get(idx) {
+            Some(hint) if hint.starts_with('<') => hint.clone(),
+            Some(hint) => format!("<{}>", hint),
+            None => "<arg>".to_string(),
+        }
+    }
+
+    fn get_help_description(&self) -> String {
+        if self.aliases.is_empty() {
+            return self.description.clone();
+        }
+        format!("{} (also {})", self.description, self.aliases.join(", "))
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -73,14 +135,14 @@ impl CommandRegistry {
             // --- Handle command name ---
             definitions
                 .entry(cmd_def.name.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(cmd_def.clone());
     
             // --- Handle aliases ---
             for alias in cmd_def.aliases.iter() {
                 definitions
                     .entry(alias.clone())
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(cmd_def.clone());
             }
         }
@@ -91,4 +153,101 @@ impl CommandRegistry {
     pub fn get_command_definitions(&self, command_name: &str) -> Option<&Vec<CommandDefinition>> {
         self.definitions.get(command_name)
     }
+
+    pub fn get_all_definitions(&self) -> Vec<&CommandDefinition> {
+        let mut definitions: Vec<&CommandDefinition> = self.definitions
+            .iter()
+            .flat_map(|(name, defs)| defs.iter().filter(move |def| &def.name == name))
+            .collect();
+        definitions.sort_by(|a, b| a.name.cmp(&b.name).then(a.expected_args.cmp(&b.expected_args)));
+        definitions
+    }
+
+    // Hints for the arguments still missing from a command being typed, e.g. ` <planet_name>`
+    // after `build MineralMine`. The shortest form of the command taking more arguments than
+    // typed so far is the one hinted at.
+    pub fn get_missing_arg_hints(&self, input: &str) -> Option<String> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let (command_name, typed_args) = parts.split_first()?;
+        let definition = self.definitions
+            .get(&command_name.to_lowercase())?
+            .iter()
+            .filter(|definition| definition.expected_args > typed_args.len())
+            .min_by_key(|definition| definition.expected_args)?;
+
+        let hints: Vec<String> = (typed_args.len()..definition.expected_args)
+            .map(|idx| definition.get_arg_hint(idx))
+            .collect();
+        let separator = if input.ends_with(char::is_whitespace) { "" } else { " " };
+        Some(format!("{}{}", separator, hints.join(" ")))
+    }
+
+    // Help table of the commands in the category, or of all of them with a heading line before
+    // each category. Rows are indented and their descriptions line up in one column.
+    pub fn get_help_lines(&self, category: Option<CommandCategory>) -> Vec<String> {
+        let definitions: Vec<&CommandDefinition> = self.get_all_definitions()
+            .into_iter()
+            .filter(|definition| category.is_none_or(|category| definition.category == category))
+            .collect();
+        let width = Self::get_usage_width(&definitions);
+
+        let mut lines = Vec::new();
+        for group in CommandCategory::all() {
+            let rows: Vec<&CommandDefinition> = definitions
+                .iter()
+                .filter(|definition| definition.category == group)
+                .copied()
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            if category.is_none() {
+                lines.push(group.to_string());
+            }
+            lines.extend(Self::format_help_rows(&rows, width));
+        }
+        lines
+    }
+
+    // Help table of every form of a single command, looked up by name or alias
+    pub fn get_command_help_lines(&self, command_name: &str) -> Option<Vec<String>> {
+        let definitions = self.definitions.get(&command_name.to_lowercase())?;
+        let mut definitions: Vec<&CommandDefinition> = definitions.iter().collect();
+        definitions.sort_by_key(|definition| definition.expected_args);
+        Some(Self::format_help_rows(&definitions, Self::get_usage_width(&definitions)))
+    }
+
+    fn get_usage_width(definitions: &[&CommandDefinition]) -> usize {
+        definitions.iter().map(|definition| definition.get_usage().len()).max().unwrap_or(0)
+    }
+
+    fn format_help_rows(definitions: &[&CommandDefinition], width: usize) -> Vec<String> {
+        definitions
+            .iter()
+            .map(|definition| format!("  {:<width$}  {}", definition.get_usage(), definition.get_help_description()))
+            .collect()
+    }
+
+    pub fn has_command(&self, command_name: &str) -> bool {
+        self.definitions.contains_key(command_name)
+    }
+
+    // Whether a command or alias starts with what was typed so far
+    pub fn has_command_starting_with(&self, prefix: &str) -> bool {
+        self.definitions.keys().any(|command_name| command_name.starts_with(prefix))
+    }
+
+    pub fn register(&mut self, cmd_def: CommandDefinition) {
+        for alias in cmd_def.aliases.iter() {
+            self.definitions
+                .entry(alias.clone())
+                .or_default()
+                .push(cmd_def.clone());
+        }
+
+        self.definitions
+            .entry(cmd_def.name.clone())
+            .or_default()
+            .push(cmd_def);
+    }
 }
\ No newline at end of file
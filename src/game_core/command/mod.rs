@@ -1,5 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod command;
 pub mod command_config;
 
-pub use command_config::{CommandRegistry, CommandDefinition, CommandLoadError};
-pub use command::{CommandError, CommandExecution, EndTurnCommand, QuitCommand, BuildCommand, };
\ No newline at end of file
+pub use command_config::{CommandCategory, CommandRegistry, CommandDefinition, CommandLoadError};
+pub use command::{CommandError, CommandExecution, InputCheck, DisasterAction, GovernorAction, MacroAction, MerchantAction, PlayerAction, ProfileAction, QueueAction, TutorialAction};
\ No newline at end of file
@@ -0,0 +1,163 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+
+#[derive(Debug)]
+pub enum CyclesConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateCycle(String),
+    InvalidSchedule { cycle: String, reason: String },
+}
+
+impl fmt::Display for CyclesConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CyclesConfigError::Io(e) => write!(f, "Failed to read cycles file: {}", e),
+            CyclesConfigError::Toml(e) => write!(f, "Failed to parse cycles file (TOML): {}", e),
+            CyclesConfigError::DuplicateCycle(id) => write!(f, "Cycle '{}' is defined twice", id),
+            CyclesConfigError::InvalidSchedule { cycle, reason } => {
+                write!(f, "Invalid schedule of cycle '{}': {}", cycle, reason)
+            }
+        }
+    }
+}
+
+impl Error for CyclesConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CyclesConfigError::Io(e) => Some(e),
+            CyclesConfigError::Toml(e) => Some(e),
+            CyclesConfigError::DuplicateCycle(_) => None,
+            CyclesConfigError::InvalidSchedule { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CyclesConfigError {
+    fn from(err: io::Error) -> Self {
+        CyclesConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for CyclesConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        CyclesConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// Changes to a cycle when a new turn starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleChange {
+    Started,
+    Ended,
+}
+
+// Recurring conditions such as a solar maximum, active for `duration_turns` turns every
+// `period_turns` turns starting on `first_turn`. Their modifiers apply to every planet.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CycleConfig {
+    pub id: String,
+    pub name: String,
+    pub period_turns: u32,
+    pub duration_turns: u32,
+    // The first period when not set
+    pub first_turn: Option<u32>,
+    // Turns ahead of every start it is announced, never when 0
+    #[serde(default)]
+    pub announce_turns: u32,
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+impl CycleConfig {
+    fn get_first_turn(&self) -> u32 {
+        self.first_turn.unwrap_or(self.period_turns)
+    }
+
+    pub fn is_active(&self, turn: u32) -> bool {
+        let first_turn = self.get_first_turn();
+        turn >= first_turn && (turn - first_turn) % self.period_turns < self.duration_turns
+    }
+
+    // Turns until the next start, 0 if it starts on the turn
+    pub fn get_turns_until_start(&self, turn: u32) -> u32 {
+        let first_turn = self.get_first_turn();
+        if turn <= first_turn {
+            return first_turn - turn;
+        }
+        (self.period_turns - (turn - first_turn) % self.period_turns) % self.period_turns
+    }
+
+    // Whether the cycle started or ended when the turn started
+    pub fn get_change(&self, turn: u32) -> Option<CycleChange> {
+        match (self.is_active(turn.saturating_sub(1)), self.is_active(turn)) {
+            (false, true) => Some(CycleChange::Started),
+            (true, false) => Some(CycleChange::Ended),
+            _ => None,
+        }
+    }
+
+    pub fn is_announced(&self, turn: u32) -> bool {
+        self.announce_turns > 0 && self.get_turns_until_start(turn) == self.announce_turns
+    }
+
+    pub fn get_modifier_source(&self) -> ModifierSource {
+        ModifierSource::Event(self.name.clone())
+    }
+
+    pub fn get_modifiers(&self) -> Vec<Modifier> {
+        self.modifiers
+            .iter()
+            .map(|modifier| modifier.to_modifier(self.get_modifier_source(), ModifierScope::Empire))
+            .collect()
+    }
+
+    // E.g. "+20% Energy production for 2 turn(s)"
+    pub fn get_effect(&self) -> String {
+        let modifiers: Vec<String> = self.modifiers.iter().map(ModifierConfig::to_string).collect();
+        format!("{} for {} turn(s)", modifiers.join(", "), self.duration_turns)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CyclesConfig {
+    pub cycles: Vec<CycleConfig>,
+}
+
+impl CyclesConfig {
+    pub fn load(path: &Path) -> Result<Self, CyclesConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: CyclesConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for cycle in &config.cycles {
+            let id = cycle.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(CyclesConfigError::DuplicateCycle(cycle.id.clone()));
+            }
+            seen.push(id);
+
+            let invalid = |reason: &str| CyclesConfigError::InvalidSchedule {
+                cycle: cycle.id.clone(),
+                reason: reason.to_string(),
+            };
+            if cycle.duration_turns == 0 || cycle.duration_turns >= cycle.period_turns {
+                return Err(invalid("duration_turns must be at least 1 and shorter than period_turns"));
+            }
+            // The first turn has no start of turn to apply it on
+            if cycle.get_first_turn() < 2 {
+                return Err(invalid("it can't start before turn 2"));
+            }
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,278 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::edict::ActivationCost;
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+use super::planet::PlanetId;
+use super::ResourceAmount;
+
+#[derive(Debug)]
+pub enum DisastersConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateDisaster(String),
+    InvalidDisaster { disaster: String, reason: String },
+    InvalidSchedule(String),
+}
+
+impl fmt::Display for DisastersConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisastersConfigError::Io(e) => write!(f, "Failed to read disasters file: {}", e),
+            DisastersConfigError::Toml(e) => write!(f, "Failed to parse disasters file (TOML): {}", e),
+            DisastersConfigError::DuplicateDisaster(id) => write!(f, "Disaster '{}' is defined twice", id),
+            DisastersConfigError::InvalidDisaster { disaster, reason } => {
+                write!(f, "Invalid disaster '{}': {}", disaster, reason)
+            }
+            DisastersConfigError::InvalidSchedule(reason) => write!(f, "Invalid disaster schedule: {}", reason),
+        }
+    }
+}
+
+impl Error for DisastersConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DisastersConfigError::Io(e) => Some(e),
+            DisastersConfigError::Toml(e) => Some(e),
+            DisastersConfigError::DuplicateDisaster(_) => None,
+            DisastersConfigError::InvalidDisaster { .. } => None,
+            DisastersConfigError::InvalidSchedule(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for DisastersConfigError {
+    fn from(err: io::Error) -> Self {
+        DisastersConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for DisastersConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        DisastersConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// One step of how a disaster plays out, its modifiers apply to the struck planet
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DisasterStageConfig {
+    pub turns: u32,
+    // Logged when the stage begins
+    pub text: String,
+    #[serde(default)]
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+// A way of dealing with a disaster, paying more upfront usually means milder stages
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DisasterResponseConfig {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub cost: ActivationCost,
+    pub stages: Vec<DisasterStageConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DisasterConfig {
+    pub id: String,
+    pub name: String,
+    pub text: String,
+    // The last one is free and chosen when the turn ends without an answer
+    pub responses: Vec<DisasterResponseConfig>,
+}
+
+impl DisasterConfig {
+    pub fn find_response(&self, id: &str) -> Option<&DisasterResponseConfig> {
+        self.responses.iter().find(|response| response.id.eq_ignore_ascii_case(id))
+    }
+
+    pub fn get_modifier_source(&self) -> ModifierSource {
+        ModifierSource::Event(self.name.clone())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DisastersConfig {
+    pub first_turn: u32,
+    // Turns between two disasters, none strikes while another one is still going on
+    pub interval_turns: u32,
+    pub disasters: Vec<DisasterConfig>,
+}
+
+impl DisastersConfig {
+    pub fn load(path: &Path) -> Result<Self, DisastersConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: DisastersConfig = toml::from_str(&toml_content)?;
+
+        if config.interval_turns == 0 {
+            return Err(DisastersConfigError::InvalidSchedule("interval_turns must be at least 1".to_string()));
+        }
+
+        let mut seen = Vec::new();
+        for disaster in &config.disasters {
+            let id = disaster.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(DisastersConfigError::DuplicateDisaster(disaster.id.clone()));
+            }
+            seen.push(id);
+
+            let invalid = |reason: &str| DisastersConfigError::InvalidDisaster {
+                disaster: disaster.id.clone(),
+                reason: reason.to_string(),
+            };
+            let Some(default_response) = disaster.responses.last() else {
+                return Err(invalid("it needs at least one response"));
+            };
+            if !default_response.cost.credits.is_zero() || default_response.cost.energy > ResourceAmount::ZERO {
+                return Err(invalid("its last response is chosen for unanswered disasters and must be free"));
+            }
+            if disaster.responses.iter().any(|response| {
+                response.stages.is_empty() || response.stages.iter().any(|stage| stage.turns == 0)
+            }) {
+                return Err(invalid("every response needs stages lasting at least 1 turn"));
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn is_strike_turn(&self, turn: u32) -> bool {
+        turn >= self.first_turn && (turn - self.first_turn).is_multiple_of(self.interval_turns)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveDisaster {
+    pub disaster: DisasterConfig,
+    pub planet: PlanetId,
+    // Index of the chosen response, none while the player hasn't answered
+    response: Option<usize>,
+    stage: usize,
+    stage_turns_left: u32,
+}
+
+impl ActiveDisaster {
+    pub fn get_response(&self) -> Option<&DisasterResponseConfig> {
+        self.response.map(|idx| &self.disaster.responses[idx])
+    }
+
+    pub fn get_stage(&self) -> Option<&DisasterStageConfig> {
+        self.get_response().and_then(|response| response.stages.get(self.stage))
+    }
+
+    // What the current stage does to the struck planet
+    pub fn get_modifiers(&self) -> Vec<Modifier> {
+        self.get_stage().map_or_else(Vec::new, |stage| {
+            stage
+                .modifiers
+                .iter()
+                .map(|modifier| {
+                    modifier.to_modifier(
+                        self.disaster.get_modifier_source(),
+                        ModifierScope::Planet(self.planet.clone()),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    fn choose(&mut self, response: usize) {
+        self.response = Some(response);
+        self.stage = 0;
+        self.stage_turns_left = self.disaster.responses[response].stages[0].turns;
+    }
+}
+
+// Changes to the disaster going on when a new turn starts
+#[derive(Debug, Default)]
+pub struct DisasterUpdate {
+    // Left unanswered, the default response was chosen
+    pub defaulted: bool,
+    // The next stage began
+    pub advanced: bool,
+    pub ended: Option<ActiveDisaster>,
+    pub struck: bool,
+}
+
+// Disasters strike the planets in turn, one at a time, each playing out over the stages of the
+// response the player picked
+#[derive(Debug)]
+pub struct Disasters {
+    config: DisastersConfig,
+    active: Option<ActiveDisaster>,
+    strike_count: usize,
+}
+
+impl Disasters {
+    pub fn new(config: DisastersConfig) -> Self {
+        Disasters { config, active: None, strike_count: 0 }
+    }
+
+    pub fn get_active(&self) -> Option<&ActiveDisaster> {
+        self.active.as_ref()
+    }
+
+    // Returns false when there is no disaster waiting for an answer
+    pub fn respond(&mut self, response: &str) -> bool {
+        let Some(active) = self.active.as_mut().filter(|active| active.response.is_none()) else {
+            return false;
+        };
+        match active.disaster.responses.iter().position(|config| config.id.eq_ignore_ascii_case(response)) {
+            Some(idx) => {
+                active.choose(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Plays out the disaster going on and lets a new one strike on one of the planets
+    // TODO: Pick disasters and planets at random once the game has a seeded random number generator
+    pub fn start_turn(&mut self, turn: u32, planets: &[PlanetId]) -> DisasterUpdate {
+        let mut update = DisasterUpdate::default();
+        if let Some(active) = &mut self.active {
+            if active.response.is_none() {
+                active.choose(active.disaster.responses.len() - 1);
+                update.defaulted = true;
+            } else {
+                active.stage_turns_left = active.stage_turns_left.saturating_sub(1);
+                if active.stage_turns_left == 0 {
+                    active.stage += 1;
+                    match active.get_stage() {
+                        Some(stage) => {
+                            active.stage_turns_left = stage.turns;
+                            update.advanced = true;
+                        }
+                        None => update.ended = self.active.take(),
+                    }
+                }
+            }
+        }
+
+        if self.active.is_some()
+            || planets.is_empty()
+            || self.config.disasters.is_empty()
+            || !self.config.is_strike_turn(turn)
+        {
+            return update;
+        }
+
+        // Disasters come in the order they are configured
+        let disaster = self.config.disasters[self.strike_count % self.config.disasters.len()].clone();
+        let planet = planets[self.strike_count % planets.len()].clone();
+        self.strike_count += 1;
+        self.active = Some(ActiveDisaster { disaster, planet, response: None, stage: 0, stage_turns_left: 0 });
+        update.struck = true;
+        update
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::{BuildingTypeId, ResourceAmount};
+
+#[derive(Debug)]
+pub enum EconomyConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    InvalidTaxRate(u8),
+    InvalidMorale(String),
+    UnknownPlanetSize(String),
+    UnknownBuilding(String),
+    UnknownSpeed(String),
+}
+
+impl fmt::Display for EconomyConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EconomyConfigError::Io(e) => write!(f, "Failed to read economy configuration file: {}", e),
+            EconomyConfigError::Toml(e) => write!(f, "Failed to parse economy configuration file (TOML): {}", e),
+            EconomyConfigError::InvalidTaxRate(rate) => write!(
+                f, "Default tax rate {}% is above the maximum tax rate", rate
+            ),
+            EconomyConfigError::InvalidMorale(field) => write!(f, "Morale value '{}' is above 100", field),
+            EconomyConfigError::UnknownPlanetSize(size) => write!(f, "Planet size '{}' has no building slots", size),
+            EconomyConfigError::UnknownBuilding(building) => write!(f, "Unknown building '{}'", building),
+            EconomyConfigError::UnknownSpeed(speed) => {
+                write!(f, "Game speed '{}' is not listed or brings no production ticks", speed)
+            }
+        }
+    }
+}
+
+impl Error for EconomyConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EconomyConfigError::Io(e) => Some(e),
+            EconomyConfigError::Toml(e) => Some(e),
+            EconomyConfigError::InvalidTaxRate(_) => None,
+            EconomyConfigError::InvalidMorale(_) => None,
+            EconomyConfigError::UnknownPlanetSize(_) => None,
+            EconomyConfigError::UnknownBuilding(_) => None,
+            EconomyConfigError::UnknownSpeed(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for EconomyConfigError {
+    fn from(err: io::Error) -> Self {
+        EconomyConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for EconomyConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        EconomyConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PopulationConfig {
+    pub initial: u32,
+    // Housing every planet has, plus what each Command Center level adds
+    pub base_housing: u32,
+    pub housing_per_command_center_level: u32,
+    // Growth per turn in percent when no taxes are collected, it shrinks as taxes rise
+    pub max_growth_percent: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TaxConfig {
+    pub default_rate: u8,
+    pub max_rate: u8,
+    // Credits a single colonist pays per turn at a 100% tax rate
+    pub credits_per_capita: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UpkeepConfig {
+    pub credits_per_building_level: u32,
+}
+
+// Game speeds by name, each one the number of production ticks an ended turn brings
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpeedConfig {
+    // Speed of a new game
+    pub default: String,
+    pub ticks_per_turn: HashMap<String, u32>,
+}
+
+impl SpeedConfig {
+    pub fn get_ticks(&self, speed: &str) -> Option<u32> {
+        self.ticks_per_turn.get(speed).copied()
+    }
+
+    // Speed names, slowest first
+    pub fn get_speeds(&self) -> Vec<(&str, u32)> {
+        let mut speeds: Vec<(&str, u32)> = self.ticks_per_turn
+            .iter()
+            .map(|(name, ticks)| (name.as_str(), *ticks))
+            .collect();
+        speeds.sort_by_key(|(name, ticks)| (*ticks, *name));
+        speeds
+    }
+}
+
+// When a queued construction's cost is set aside from the planet's stored resources
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstructionPayment {
+    // As it is queued, a level that can't be afforded next to the rest of the queue isn't queued
+    Upfront,
+    // Once it reaches the front of the queue, it waits there until it can be afforded
+    OnStart,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConstructionConfig {
+    pub payment: ConstructionPayment,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RushConfig {
+    pub base_premium: u32,
+    pub credits_per_remaining_turn: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LogisticsConfig {
+    // Units of each resource moved between planets every turn, across the whole empire
+    pub freight_per_turn: ResourceAmount,
+}
+
+// All morale values are percentages
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MoraleConfig {
+    pub initial: u8,
+    // Morale a planet settles at before taxes, crowding and buildings are accounted for
+    pub base: u8,
+    pub tolerated_tax_rate: u8,
+    pub tax_penalty_per_percent: u8,
+    // Population above this percent of the housing counts as overcrowded
+    pub crowded_percent: u8,
+    pub overcrowding_penalty: u8,
+    pub change_per_turn: u8,
+    // Below it production drops in proportion, at or below the strike threshold it stops for a turn
+    pub unrest_threshold: u8,
+    pub strike_threshold: u8,
+    // Lowers the morale target of every planet for a few turns
+    pub unpaid_upkeep_penalty: u8,
+    pub unpaid_upkeep_penalty_turns: u32,
+}
+
+// Every surface building with a built or queued level takes up a slot, buildings on moons and
+// asteroid belts don't
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BuildingSlotsConfig {
+    // Size of newly settled planets, one of those listed in slots_by_size
+    pub starting_size: String,
+    pub slots_by_size: HashMap<String, u32>,
+    // Every level of this building adds more slots
+    pub expansion_building: String,
+    pub slots_per_expansion_level: u32,
+}
+
+impl BuildingSlotsConfig {
+    pub fn get_base_slots(&self, size: &str) -> u32 {
+        self.slots_by_size.get(size).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EconomyConfig {
+    pub starting_credits: ResourceAmount,
+    pub population: PopulationConfig,
+    pub taxes: TaxConfig,
+    pub upkeep: UpkeepConfig,
+    pub rush: RushConfig,
+    pub logistics: LogisticsConfig,
+    pub morale: MoraleConfig,
+    pub building_slots: BuildingSlotsConfig,
+    pub speed: SpeedConfig,
+    pub construction: ConstructionConfig,
+}
+
+impl EconomyConfig {
+    pub fn load(path: &Path) -> Result<Self, EconomyConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: EconomyConfig = toml::from_str(&toml_content)?;
+
+        if config.taxes.default_rate > config.taxes.max_rate {
+            return Err(EconomyConfigError::InvalidTaxRate(config.taxes.default_rate));
+        }
+
+        let morale = &config.morale;
+        for (field, value) in [
+            ("initial", morale.initial),
+            ("base", morale.base),
+            ("crowded_percent", morale.crowded_percent),
+            ("unrest_threshold", morale.unrest_threshold),
+            ("strike_threshold", morale.strike_threshold),
+        ] {
+            if value > 100 {
+                return Err(EconomyConfigError::InvalidMorale(field.to_string()));
+            }
+        }
+
+        let slots = &config.building_slots;
+        if !slots.slots_by_size.contains_key(&slots.starting_size) {
+            return Err(EconomyConfigError::UnknownPlanetSize(slots.starting_size.clone()));
+        }
+        if !BuildingTypeId::all().iter().any(|id| id.get_name() == slots.expansion_building) {
+            return Err(EconomyConfigError::UnknownBuilding(slots.expansion_building.clone()));
+        }
+
+        let speed = &config.speed;
+        if let Some((name, _)) = speed.ticks_per_turn.iter().find(|(_, ticks)| **ticks == 0) {
+            return Err(EconomyConfigError::UnknownSpeed(name.clone()));
+        }
+        if speed.get_ticks(&speed.default).is_none() {
+            return Err(EconomyConfigError::UnknownSpeed(speed.default.clone()));
+        }
+
+        Ok(config)
+    }
+
+    pub fn get_tax_income(&self, population: u32, tax_rate: u8) -> u32 {
+        (population as f32 * tax_rate as f32 / 100.0 * self.taxes.credits_per_capita) as u32
+    }
+
+    pub fn get_growth_percent(&self, tax_rate: u8) -> u32 {
+        let max_rate = self.taxes.max_rate.max(1) as u32;
+        let untaxed = max_rate.saturating_sub(tax_rate as u32);
+        self.population.max_growth_percent * untaxed / max_rate
+    }
+
+    pub fn get_rush_price(&self, remaining_turns: u32) -> ResourceAmount {
+        ResourceAmount::new(self.rush.base_premium) + ResourceAmount::new(self.rush.credits_per_remaining_turn) * remaining_turns
+    }
+}
+
+// Credits a player gained and spent at the end of a turn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CreditsReport {
+    pub taxes: ResourceAmount,
+    pub trade: ResourceAmount,
+    pub upkeep: ResourceAmount,
+    // Part of the upkeep that could not be paid
+    pub unpaid: ResourceAmount,
+}
+
+impl CreditsReport {
+    pub fn get_net(&self) -> i64 {
+        i64::from(self.taxes) + i64::from(self.trade) - i64::from(self.upkeep)
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+use super::ResourceAmount;
+
+#[derive(Debug)]
+pub enum EdictsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateEdict(String),
+}
+
+impl fmt::Display for EdictsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdictsConfigError::Io(e) => write!(f, "Failed to read edicts file: {}", e),
+            EdictsConfigError::Toml(e) => write!(f, "Failed to parse edicts file (TOML): {}", e),
+            EdictsConfigError::DuplicateEdict(id) => write!(f, "Edict '{}' is defined twice", id),
+        }
+    }
+}
+
+impl Error for EdictsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EdictsConfigError::Io(e) => Some(e),
+            EdictsConfigError::Toml(e) => Some(e),
+            EdictsConfigError::DuplicateEdict(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for EdictsConfigError {
+    fn from(err: io::Error) -> Self {
+        EdictsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for EdictsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        EdictsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ActivationCost {
+    #[serde(default)]
+    pub credits: ResourceAmount,
+    // Taken from the energy stored on the player's planets
+    #[serde(default)]
+    pub energy: ResourceAmount,
+}
+
+impl fmt::Display for ActivationCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.credits, self.energy) {
+            (ResourceAmount::ZERO, ResourceAmount::ZERO) => write!(f, "nothing"),
+            (credits, ResourceAmount::ZERO) => write!(f, "{} credits", credits),
+            (ResourceAmount::ZERO, energy) => write!(f, "{} energy", energy),
+            (credits, energy) => write!(f, "{} credits and {} energy", credits, energy),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EdictConfig {
+    pub id: String,
+    pub name: String,
+    pub activation_cost: ActivationCost,
+    // Turns before the edict can be toggled again
+    pub cooldown_turns: u32,
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+impl EdictConfig {
+    pub fn get_modifier_source(&self) -> ModifierSource {
+        ModifierSource::Edict(self.name.clone())
+    }
+
+    // Edicts apply to the whole empire
+    pub fn get_modifiers(&self) -> Vec<Modifier> {
+        self.modifiers
+            .iter()
+            .map(|modifier| modifier.to_modifier(self.get_modifier_source(), ModifierScope::Empire))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EdictsConfig {
+    pub edicts: Vec<EdictConfig>,
+}
+
+impl EdictsConfig {
+    pub fn load(path: &Path) -> Result<Self, EdictsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: EdictsConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for edict in &config.edicts {
+            let id = edict.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(EdictsConfigError::DuplicateEdict(edict.id.clone()));
+            }
+            seen.push(id);
+        }
+
+        Ok(config)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&EdictConfig> {
+        self.edicts.iter().find(|edict| edict.id.eq_ignore_ascii_case(id))
+    }
+}
+
+// Edicts a player has enacted and when each of them can be toggled again
+#[derive(Debug, Clone, Default)]
+pub struct Edicts {
+    active: Vec<EdictConfig>,
+    available_from_turn: HashMap<String, u32>,
+}
+
+impl Edicts {
+    pub fn is_active(&self, id: &str) -> bool {
+        self.active.iter().any(|edict| edict.id == id)
+    }
+
+    // Turns left until the edict can be toggled, 0 if it can be toggled now
+    pub fn get_cooldown(&self, id: &str, turn: u32) -> u32 {
+        self.available_from_turn.get(id).map_or(0, |available| available.saturating_sub(turn))
+    }
+
+    // Enacts an inactive edict or repeals an active one, returns whether it is active now
+    pub fn toggle(&mut self, edict: &EdictConfig, turn: u32) -> bool {
+        self.available_from_turn.insert(edict.id.clone(), turn + edict.cooldown_turns);
+        if self.is_active(&edict.id) {
+            self.active.retain(|active| active.id != edict.id);
+            false
+        } else {
+            self.active.push(edict.clone());
+            true
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use super::BuildingTypeId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    // Emitted after any command succeeded, with the name the command is defined under
+    CommandExecuted { command: String },
+    TurnEnded { turn: u32 },
+    BuildingCompleted { planet: String, building: BuildingTypeId, level: u8 },
+}
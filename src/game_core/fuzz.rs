@@ -0,0 +1,200 @@
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::edict::EdictsConfig;
+use super::governor::GovernorsConfig;
+use super::rng::Rng;
+use super::{BuildingTypeId, GameCore, GameCoreError, GameDataPaths};
+
+#[derive(Debug, Clone)]
+pub struct FuzzFailure {
+    pub seed: u64,
+    // The shortest sequence found that still fails
+    pub commands: Vec<String>,
+    pub message: String,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Seed {} failed: {}", self.seed, self.message)?;
+        writeln!(f, "Commands to reproduce ({}):", self.commands.len())?;
+        for command in &self.commands {
+            writeln!(f, "  {}", command)?;
+        }
+        Ok(())
+    }
+}
+
+// Feeds random but well-formed command sequences into new games and checks the world stays
+// consistent after every command. A failing sequence is shrunk before it is reported.
+pub struct CommandFuzzer {
+    paths: GameDataPaths,
+    buildings: Vec<String>,
+    locations: Vec<String>,
+    governors: Vec<String>,
+    edicts: Vec<String>,
+}
+
+impl CommandFuzzer {
+    pub fn new(paths: &GameDataPaths) -> Result<Self, GameCoreError> {
+        let governors_config = GovernorsConfig::load(GameDataPaths::get_or(&paths.governors, "data/governors.toml"))?;
+        let edicts_config = EdictsConfig::load(GameDataPaths::get_or(&paths.edicts, "data/edicts.toml"))?;
+
+        // Planets and their sub-locations as commands address them
+        let game_core = GameCore::new(paths)?;
+        let mut locations = Vec::new();
+        for planet_name in game_core.get_current_player_planet_names() {
+            if let Some(status) = game_core.get_current_player_planet_status(&planet_name) {
+                locations.extend(status.sub_locations.iter().map(|sub| format!("{}/{}", planet_name, sub.id)));
+            }
+            locations.push(planet_name);
+        }
+
+        Ok(CommandFuzzer {
+            paths: paths.clone(),
+            // Second instances too, including of buildings that allow only one
+            buildings: BuildingTypeId::all()
+                .iter()
+                .flat_map(|id| [id.get_name().to_string(), format!("{}#2", id.get_name())])
+                .collect(),
+            locations,
+            governors: governors_config.governors.iter().map(|governor| governor.id.clone()).collect(),
+            edicts: edicts_config.edicts.iter().map(|edict| edict.id.clone()).collect(),
+        })
+    }
+
+    // The same seed always gives the same commands
+    pub fn generate(&self, seed: u64, length: usize) -> Vec<String> {
+        let mut rng = Rng::new(seed);
+        let macros = ["alpha".to_string(), "beta".to_string()];
+        let pick = |rng: &mut Rng, items: &[String]| rng.pick(items).cloned().unwrap_or_default();
+
+        (0..length).map(|_| {
+            // Ending turns is weighted up so games get far enough for anything to happen
+            match rng.below(20) {
+                0..=3 => "endturn".to_string(),
+                4 => format!("wait {}", rng.below(4)),
+                5..=7 => format!("build {} {}", pick(&mut rng, &self.buildings), pick(&mut rng, &self.locations)),
+                8 => format!("build {} {} x{}", pick(&mut rng, &self.buildings), pick(&mut rng, &self.locations), rng.below(5)),
+                9 => match rng.below(2) {
+                    0 => format!("rush {} {}", pick(&mut rng, &self.buildings), pick(&mut rng, &self.locations)),
+                    _ => format!("queue move {} {} {}", pick(&mut rng, &self.locations), rng.below(4), rng.below(4)),
+                },
+                10 => ["confirm", "cancel"][rng.below(2)].to_string(),
+                11 => format!("tax {} {}", pick(&mut rng, &self.locations), rng.below(120)),
+                12 => format!("hire-governor {}", pick(&mut rng, &self.governors)),
+                13 => format!("assign-governor {} {}", pick(&mut rng, &self.governors), pick(&mut rng, &self.locations)),
+                14 => format!("edict {}", pick(&mut rng, &self.edicts)),
+                15 => format!("merchant {}", ["accept", "decline"][rng.below(2)]),
+                16 => match rng.below(3) {
+                    0 => format!("macro record {}", pick(&mut rng, &macros)),
+                    1 => "macro stop".to_string(),
+                    _ => format!("macro play {} {}", pick(&mut rng, &macros), pick(&mut rng, &self.locations)),
+                },
+                17 => ["turn", "artifacts", "timeline", "governors", "edict", "merchant", "production", "production gas"][rng.below(8)].to_string(),
+                18 => [
+                    "tutorial", "tutorial stop", "profile", "profile reset", "speed", "speed fast", "challenge",
+                    "challenge 2026-10-16", "player", "player name Nova", "player color red", "rules",
+                    "rules double_production on", "rules expensive_storage on", "rules event_frequency rare",
+                ][rng.below(15)].to_string(),
+                _ => format!("tax {}", pick(&mut rng, &self.locations)),
+            }
+        }).collect()
+    }
+
+    // Runs the commands on a new game, stopping at the first panic or broken invariant
+    pub fn run(&self, commands: &[String]) -> Result<(), String> {
+        let mut game_core = GameCore::new(&self.paths).map_err(|err| format!("Failed to start a game: {}", err))?;
+
+        for (idx, command) in commands.iter().enumerate() {
+            if !game_core.is_running() {
+                break;
+            }
+
+            // Command errors are expected, random commands are often not allowed. Debug builds
+            // check the invariants themselves and panic when one breaks.
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                let _ = game_core.execute_command(command);
+            }));
+            if let Err(payload) = outcome {
+                let message = Self::get_panic_message(&payload);
+                return Err(format!("Command {} '{}' panicked: {}", idx + 1, command, message));
+            }
+
+            game_core.check_invariants()
+                .map_err(|err| format!("After command {} '{}': {}", idx + 1, command, err))?;
+        }
+        Ok(())
+    }
+
+    pub fn fuzz(&self, seed: u64, length: usize) -> Result<(), FuzzFailure> {
+        let commands = self.generate(seed, length);
+        let Err(message) = self.run(&commands) else {
+            return Ok(());
+        };
+
+        let commands = self.minimize(commands);
+        // The message of the shortest sequence points at the command to look at
+        let message = self.run(&commands).err().unwrap_or(message);
+        Err(FuzzFailure { seed, commands, message })
+    }
+
+    // Drops chunks of commands, then single ones, for as long as the rest still fails
+    fn minimize(&self, mut commands: Vec<String>) -> Vec<String> {
+        let mut chunk_size = commands.len() / 2;
+        while chunk_size > 0 {
+            let mut start = 0;
+            while start < commands.len() {
+                let end = (start + chunk_size).min(commands.len());
+                let candidate: Vec<String> = commands[..start].iter().chain(&commands[end..]).cloned().collect();
+                if self.run(&candidate).is_err() {
+                    commands = candidate;
+                } else {
+                    start += chunk_size;
+                }
+            }
+            chunk_size /= 2;
+        }
+        commands
+    }
+
+    fn get_panic_message(payload: &Box<dyn Any + Send>) -> String {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|message| message.to_string()))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: u64 = 16;
+    const SEQUENCE_LENGTH: usize = 80;
+
+    // FUZZ_SEED reruns a single reported seed
+    #[test]
+    fn random_commands_keep_the_world_consistent() {
+        let fuzzer = CommandFuzzer::new(&GameDataPaths::default()).unwrap();
+        let seeds: Vec<u64> = match std::env::var("FUZZ_SEED").ok().and_then(|seed| seed.parse().ok()) {
+            Some(seed) => vec![seed],
+            None => (0..SEEDS).collect(),
+        };
+
+        for seed in seeds {
+            if let Err(failure) = fuzzer.fuzz(seed, SEQUENCE_LENGTH) {
+                panic!("{}", failure);
+            }
+        }
+    }
+
+    #[test]
+    fn generation_is_reproducible() {
+        let fuzzer = CommandFuzzer::new(&GameDataPaths::default()).unwrap();
+        assert_eq!(fuzzer.generate(7, SEQUENCE_LENGTH), fuzzer.generate(7, SEQUENCE_LENGTH));
+        assert_ne!(fuzzer.generate(7, SEQUENCE_LENGTH), fuzzer.generate(8, SEQUENCE_LENGTH));
+    }
+}
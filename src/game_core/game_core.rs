@@ -1,22 +1,74 @@
-use std::{collections::HashMap, error::Error};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
 use std::fmt::Display;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::de::Error as SerdeError;
 use toml::de::Error as TomlError;
 
-use super::building::BuildingTypeId;
-use super::command::EndTurnCommand;
+use super::alert::Alert;
+use super::artifact::{ArtifactsConfig, ArtifactsConfigError};
+use super::bot::PlayerView;
+use super::building::{BuildingInstanceId, BuildingTypeId};
+use super::choice::{Choice, ChoiceOption};
+use super::command::{CommandCategory, DisasterAction, GovernorAction, InputCheck, MacroAction, MerchantAction, PlayerAction, ProfileAction, QueueAction, TutorialAction};
+use super::event::GameEvent;
+use super::colonypedia::{Colonypedia, ColonypediaError};
+use super::cycle::{CycleChange, CyclesConfig, CyclesConfigError};
+use super::disaster::{Disasters, DisastersConfig, DisastersConfigError};
+use super::economy::{EconomyConfig, EconomyConfigError};
+use super::edict::{EdictsConfig, EdictsConfigError};
+use super::governor::{Governor, GovernorsConfig, GovernorsConfigError};
+use super::hints::{HintEngine, HintSettings};
+use super::macros::MacroRecorder;
+use super::ai::AiConfigError;
+use super::challenge::{self, Challenge, ChallengeConfig, ChallengeConfigError, ColonyTally};
+use super::specialization::{SpecializationsConfig, SpecializationsConfigError};
+use super::rules::GameRules;
+use super::names::{NameGenerator, NameKind, NamesConfig, NamesConfigError};
+use super::merchant::{MerchantDeal, Merchants, MerchantsConfig, MerchantsConfigError};
+use super::modifier::ModifierStat;
+use super::mission::{MissionState, MissionStatus, MissionTracker, MissionsConfig, MissionsConfigError};
+use super::planet::PlanetId;
+use super::profile::Profile;
+use super::replay::Replay;
+use super::scripting::{GameSnapshot, PlanetSnapshot, ScriptEngine, ScriptError, ScriptOutput};
+use super::rng::get_checksum;
+use super::stats::{format_playtime, GameStats, TurnRecord};
+use super::sub_location::{SubLocationsConfig, SubLocationsConfigError};
+use super::synergy::{SynergiesConfig, SynergiesConfigError};
+use super::tutorial::{Tutorial, TutorialConfig, TutorialError, TutorialStatus};
 use super::{
-    command::CommandExecution, planet::PlanetStatus, BuildingsConfig, BuildingsConfigError, CommandError, CommandLoadError, CommandRegistry, Planet, PlanetError, Player, Turn
+    command::CommandExecution, planet::PlanetStatus, BuildingsConfig, BuildingsConfigError, CommandDefinition, CommandError,
+    CommandLoadError, CommandRegistry, Planet, PlanetError, Player, PlayerColor, Resource, ResourceAmount, Turn
 };
 
+// Each variant wraps the error of the part of the game that failed
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum GameCoreError {
     CommandLoadError(CommandLoadError),
     CommandError(CommandError),
     BuildingConfigError(BuildingsConfigError),
     PlanetError(PlanetError),
+    ScriptError(ScriptError),
+    TutorialError(TutorialError),
+    ColonypediaError(ColonypediaError),
+    EconomyConfigError(EconomyConfigError),
+    GovernorsConfigError(GovernorsConfigError),
+    EdictsConfigError(EdictsConfigError),
+    SubLocationsConfigError(SubLocationsConfigError),
+    ArtifactsConfigError(ArtifactsConfigError),
+    MissionsConfigError(MissionsConfigError),
+    MerchantsConfigError(MerchantsConfigError),
+    SynergiesConfigError(SynergiesConfigError),
+    ChallengeConfigError(ChallengeConfigError),
+    SpecializationsConfigError(SpecializationsConfigError),
+    CyclesConfigError(CyclesConfigError),
+    DisastersConfigError(DisastersConfigError),
+    AiConfigError(AiConfigError),
+    NamesConfigError(NamesConfigError),
 }
 
 impl Display for GameCoreError {
@@ -26,6 +78,23 @@ impl Display for GameCoreError {
             GameCoreError::BuildingConfigError(err) => write!(f, "Building Config Error: {}", err),
             GameCoreError::CommandError(err) => write!(f, "Command Error: {}", err),
             GameCoreError::PlanetError(err) => write!(f, "Planet Error: {}", err),
+            GameCoreError::ScriptError(err) => write!(f, "Script Error: {}", err),
+            GameCoreError::TutorialError(err) => write!(f, "Tutorial Error: {}", err),
+            GameCoreError::ColonypediaError(err) => write!(f, "Colonypedia Error: {}", err),
+            GameCoreError::EconomyConfigError(err) => write!(f, "Economy Config Error: {}", err),
+            GameCoreError::GovernorsConfigError(err) => write!(f, "Governors Config Error: {}", err),
+            GameCoreError::EdictsConfigError(err) => write!(f, "Edicts Config Error: {}", err),
+            GameCoreError::SubLocationsConfigError(err) => write!(f, "Sub-locations Config Error: {}", err),
+            GameCoreError::ArtifactsConfigError(err) => write!(f, "Artifacts Config Error: {}", err),
+            GameCoreError::MissionsConfigError(err) => write!(f, "Missions Config Error: {}", err),
+            GameCoreError::MerchantsConfigError(err) => write!(f, "Merchants Config Error: {}", err),
+            GameCoreError::SynergiesConfigError(err) => write!(f, "Synergies Config Error: {}", err),
+            GameCoreError::ChallengeConfigError(err) => write!(f, "Challenge Config Error: {}", err),
+            GameCoreError::SpecializationsConfigError(err) => write!(f, "Specializations Config Error: {}", err),
+            GameCoreError::CyclesConfigError(err) => write!(f, "Cycles Config Error: {}", err),
+            GameCoreError::DisastersConfigError(err) => write!(f, "Disasters Config Error: {}", err),
+            GameCoreError::AiConfigError(err) => write!(f, "AI Config Error: {}", err),
+            GameCoreError::NamesConfigError(err) => write!(f, "Names Config Error: {}", err),
         }
     }
 }
@@ -37,6 +106,23 @@ impl Error for GameCoreError {
             GameCoreError::CommandError(err) => Some(err),
             GameCoreError::BuildingConfigError(_) => None,
             GameCoreError::PlanetError(err) => Some(err),
+            GameCoreError::ScriptError(err) => Some(err),
+            GameCoreError::TutorialError(err) => Some(err),
+            GameCoreError::ColonypediaError(err) => Some(err),
+            GameCoreError::EconomyConfigError(err) => Some(err),
+            GameCoreError::GovernorsConfigError(err) => Some(err),
+            GameCoreError::EdictsConfigError(err) => Some(err),
+            GameCoreError::SubLocationsConfigError(err) => Some(err),
+            GameCoreError::ArtifactsConfigError(err) => Some(err),
+            GameCoreError::MissionsConfigError(err) => Some(err),
+            GameCoreError::MerchantsConfigError(err) => Some(err),
+            GameCoreError::SynergiesConfigError(err) => Some(err),
+            GameCoreError::ChallengeConfigError(err) => Some(err),
+            GameCoreError::SpecializationsConfigError(err) => Some(err),
+            GameCoreError::CyclesConfigError(err) => Some(err),
+            GameCoreError::DisastersConfigError(err) => Some(err),
+            GameCoreError::AiConfigError(err) => Some(err),
+            GameCoreError::NamesConfigError(err) => Some(err),
         }
     }
 }
@@ -65,57 +151,505 @@ impl From<PlanetError> for GameCoreError {
     }
 }
 
+impl From<ScriptError> for GameCoreError {
+    fn from(err: ScriptError) -> Self {
+        GameCoreError::ScriptError(err)
+    }
+}
+
+impl From<TutorialError> for GameCoreError {
+    fn from(err: TutorialError) -> Self {
+        GameCoreError::TutorialError(err)
+    }
+}
+
+impl From<ColonypediaError> for GameCoreError {
+    fn from(err: ColonypediaError) -> Self {
+        GameCoreError::ColonypediaError(err)
+    }
+}
+
+impl From<EconomyConfigError> for GameCoreError {
+    fn from(err: EconomyConfigError) -> Self {
+        GameCoreError::EconomyConfigError(err)
+    }
+}
+
+impl From<GovernorsConfigError> for GameCoreError {
+    fn from(err: GovernorsConfigError) -> Self {
+        GameCoreError::GovernorsConfigError(err)
+    }
+}
+
+impl From<EdictsConfigError> for GameCoreError {
+    fn from(err: EdictsConfigError) -> Self {
+        GameCoreError::EdictsConfigError(err)
+    }
+}
+
+impl From<SubLocationsConfigError> for GameCoreError {
+    fn from(err: SubLocationsConfigError) -> Self {
+        GameCoreError::SubLocationsConfigError(err)
+    }
+}
+
+impl From<ArtifactsConfigError> for GameCoreError {
+    fn from(err: ArtifactsConfigError) -> Self {
+        GameCoreError::ArtifactsConfigError(err)
+    }
+}
+
+impl From<MissionsConfigError> for GameCoreError {
+    fn from(err: MissionsConfigError) -> Self {
+        GameCoreError::MissionsConfigError(err)
+    }
+}
+
+impl From<MerchantsConfigError> for GameCoreError {
+    fn from(err: MerchantsConfigError) -> Self {
+        GameCoreError::MerchantsConfigError(err)
+    }
+}
+
+impl From<SynergiesConfigError> for GameCoreError {
+    fn from(err: SynergiesConfigError) -> Self {
+        GameCoreError::SynergiesConfigError(err)
+    }
+}
+
+impl From<ChallengeConfigError> for GameCoreError {
+    fn from(err: ChallengeConfigError) -> Self {
+        GameCoreError::ChallengeConfigError(err)
+    }
+}
+
+impl From<SpecializationsConfigError> for GameCoreError {
+    fn from(err: SpecializationsConfigError) -> Self {
+        GameCoreError::SpecializationsConfigError(err)
+    }
+}
+
+impl From<CyclesConfigError> for GameCoreError {
+    fn from(err: CyclesConfigError) -> Self {
+        GameCoreError::CyclesConfigError(err)
+    }
+}
+
+impl From<DisastersConfigError> for GameCoreError {
+    fn from(err: DisastersConfigError) -> Self {
+        GameCoreError::DisastersConfigError(err)
+    }
+}
+
+impl From<AiConfigError> for GameCoreError {
+    fn from(err: AiConfigError) -> Self {
+        GameCoreError::AiConfigError(err)
+    }
+}
+
+impl From<NamesConfigError> for GameCoreError {
+    fn from(err: NamesConfigError) -> Self {
+        GameCoreError::NamesConfigError(err)
+    }
+}
+
 // =================================================================================================
 
+// Limits how deep script-issued commands may trigger further script commands
+const MAX_SCRIPT_DEPTH: usize = 4;
+
+// An action that only happens once the player confirms it
+#[derive(Debug)]
+enum PendingAction {
+    Rush { planet: String, sub_location: Option<String>, building: BuildingInstanceId, price: ResourceAmount },
+    // A command flagged with `confirm` in commands.toml, run as typed once confirmed
+    Command(String),
+}
+
+// Locations of the data the game is loaded from, unset paths use the default location
+#[derive(Debug, Clone, Default)]
+pub struct GameDataPaths {
+    pub commands: Option<PathBuf>,
+    pub buildings: Option<PathBuf>,
+    pub economy: Option<PathBuf>,
+    pub scripts: Option<PathBuf>,
+    pub tutorial: Option<PathBuf>,
+    pub lore: Option<PathBuf>,
+    pub governors: Option<PathBuf>,
+    pub edicts: Option<PathBuf>,
+    pub sub_locations: Option<PathBuf>,
+    pub artifacts: Option<PathBuf>,
+    pub missions: Option<PathBuf>,
+    pub merchants: Option<PathBuf>,
+    pub synergies: Option<PathBuf>,
+    pub challenge: Option<PathBuf>,
+    pub cycles: Option<PathBuf>,
+    pub disasters: Option<PathBuf>,
+    pub specializations: Option<PathBuf>,
+    // Only read by the AI seats, see BotRunner
+    pub ai: Option<PathBuf>,
+    pub names: Option<PathBuf>,
+}
+
+impl GameDataPaths {
+    pub fn get_or<'a>(path: &'a Option<PathBuf>, default: &'a str) -> &'a Path {
+        path.as_deref().unwrap_or(Path::new(default))
+    }
+}
+
 pub struct GameCore {
     command_registry: CommandRegistry,
     buildings_config: BuildingsConfig,
+    economy_config: EconomyConfig,
+    governors_config: GovernorsConfig,
+    edicts_config: EdictsConfig,
+    artifacts_config: ArtifactsConfig,
+    synergies_config: SynergiesConfig,
+    challenge_config: ChallengeConfig,
+    specializations_config: SpecializationsConfig,
+    cycles_config: CyclesConfig,
+    // Set once a daily challenge was started, it stays after the challenge is over
+    challenge: Option<Challenge>,
+    name_generator: NameGenerator,
     turn: Turn,
     current_player: String,
     players: HashMap<String, Player>,
+    // TODO: Track reputation between every pair of players (broken treaties, wars, gifts) for AI
+    // diplomacy and a relations screen, once there are AI players
+    // TODO: Gate spy mission tiers and counter-intelligence on an intelligence branch of research
+    // once there are rival players to spy on and a research tree
+    macro_recorder: MacroRecorder,
+    replay: Replay,
+    // Commands run by macros and scripts are nested inside the command that started them
+    command_depth: usize,
+    // One of the speeds in the economy config
+    speed: String,
+    // Counts the commands run and other changes to the world, see get_revision
+    revision: u64,
+    script_engine: ScriptEngine,
+    script_depth: usize,
+    pending_events: Vec<GameEvent>,
+    notifications: Vec<String>,
+    // Only kept while recording turns for the statistics export
+    turn_records: Option<Vec<TurnRecord>>,
+    stats: GameStats,
+    profile: Profile,
+    tutorial_config: TutorialConfig,
+    tutorial: Option<Tutorial>,
+    missions: MissionTracker,
+    merchants: Merchants,
+    disasters: Disasters,
+    rules: GameRules,
+    hint_engine: HintEngine,
+    colonypedia: Colonypedia,
+    // The action together with the question asked about it
+    pending_confirmation: Option<(PendingAction, String)>,
+    // Set while running a command that was confirmed already
+    is_confirmed: bool,
     is_running: bool,
 }
 
 impl GameCore {
-    pub fn new(
-        command_registry_path: Option<&Path>,
-        buildings_config_path: Option<&Path>,
-    ) -> Result<Self, GameCoreError>  {
-        let command_registry = match command_registry_path {
-            Some(path) => CommandRegistry::load(path)?,
-            None => CommandRegistry::load(Path::new("data/commands.toml"))?,
-        };
+    // Player 1 on Planet1, the same in every game
+    pub fn new(paths: &GameDataPaths) -> Result<Self, GameCoreError>  {
+        Self::create(paths, None)
+    }
+
+    // The player and their planet get generated names, the same again for the same seed
+    pub fn new_with_name_seed(paths: &GameDataPaths, name_seed: u64) -> Result<Self, GameCoreError>  {
+        Self::create(paths, Some(name_seed))
+    }
 
-        let buildings_config = match buildings_config_path {
-            Some(path) => BuildingsConfig::load(path)?,
-            None => BuildingsConfig::load(Path::new("data/buildings.toml"))?,
+    fn create(paths: &GameDataPaths, name_seed: Option<u64>) -> Result<Self, GameCoreError>  {
+        let mut command_registry = CommandRegistry::load(
+            GameDataPaths::get_or(&paths.commands, "data/commands.toml")
+        )?;
+        let tutorial_config = TutorialConfig::load(GameDataPaths::get_or(&paths.tutorial, "data/tutorial.toml"))?;
+        let mut script_engine = ScriptEngine::load(GameDataPaths::get_or(&paths.scripts, "scripts"))?;
+
+        // Script files may add new commands, but never replace configured ones
+        for script_command in script_engine.get_declared_commands() {
+            if command_registry.has_command(&script_command.name) {
+                return Err(ScriptError::CommandConflict(script_command.name.clone()).into());
+            }
+            command_registry.register(CommandDefinition {
+                name: script_command.name.clone(),
+                category: CommandCategory::Scripted,
+                aliases: Vec::new(),
+                description: script_command.description.clone(),
+                expected_args: script_command.expected_args,
+                arg_hints: Vec::new(),
+                confirm: false,
+                script: Some(script_command.get_body()),
+            });
+        }
+
+        for definition in command_registry.get_all_definitions() {
+            if let Some(body) = &definition.script {
+                script_engine.add_command(&definition.name, definition.expected_args, body)?;
+            }
+        }
+
+        let mut notifications = Vec::new();
+        if script_engine.get_script_count() > 0 {
+            notifications.push(format!("Loaded {} script(s).", script_engine.get_script_count()));
+        }
+
+        let buildings_config = BuildingsConfig::load(GameDataPaths::get_or(&paths.buildings, "data/buildings.toml"))?;
+        let economy_config = EconomyConfig::load(GameDataPaths::get_or(&paths.economy, "data/economy.toml"))?;
+        let governors_config = GovernorsConfig::load(
+            GameDataPaths::get_or(&paths.governors, "data/governors.toml")
+        )?;
+        let edicts_config = EdictsConfig::load(GameDataPaths::get_or(&paths.edicts, "data/edicts.toml"))?;
+        let sub_locations_config = SubLocationsConfig::load(
+            GameDataPaths::get_or(&paths.sub_locations, "data/sub_locations.toml")
+        )?;
+        let artifacts_config = ArtifactsConfig::load(GameDataPaths::get_or(&paths.artifacts, "data/artifacts.toml"))?;
+        let missions_config = MissionsConfig::load(GameDataPaths::get_or(&paths.missions, "data/missions.toml"))?;
+        let merchants_config = MerchantsConfig::load(GameDataPaths::get_or(&paths.merchants, "data/merchants.toml"))?;
+        let disasters_config = DisastersConfig::load(GameDataPaths::get_or(&paths.disasters, "data/disasters.toml"))?;
+        let synergies_config = SynergiesConfig::load(GameDataPaths::get_or(&paths.synergies, "data/synergies.toml"))?;
+        let challenge_config = ChallengeConfig::load(GameDataPaths::get_or(&paths.challenge, "data/challenge.toml"))?;
+        let specializations_config = SpecializationsConfig::load(
+            GameDataPaths::get_or(&paths.specializations, "data/specializations.toml")
+        )?;
+        let cycles_config = CyclesConfig::load(GameDataPaths::get_or(&paths.cycles, "data/cycles.toml"))?;
+        let names_config = NamesConfig::load(GameDataPaths::get_or(&paths.names, "data/names.toml"))?;
+        let colonypedia = Colonypedia::load(
+            GameDataPaths::get_or(&paths.lore, "data/lore.toml"),
+            &buildings_config,
+            &command_registry,
+        )?;
+
+        // TODO: Generate a galaxy map with planets connected by hyperlanes, fleets should move along
+        // the shortest path with the route and ETA shown in a map pane
+        // TODO: Scatter derelicts on the map for fleets to salvage over several turns, yielding
+        // resources, hulls or artifacts, or setting off a booby trap
+        // TODO: Number of players created should be set by the user via ui, optionally in fixed teams
+        // sharing visibility
+        let mut name_generator = NameGenerator::new(names_config, name_seed.unwrap_or(0));
+        let (player_name, planet_name) = match name_seed {
+            Some(_) => (name_generator.generate(NameKind::Player), name_generator.generate(NameKind::Planet)),
+            None => ("Player 1".to_string(), "Planet1".to_string()),
         };
+        name_generator.reserve(&player_name);
+        name_generator.reserve(&planet_name);
 
-        // TODO: Number of players created should be set by the user via ui
-        let player1 = Player::new(
-            "Player 1", 
-            "Planet1", 
-            &buildings_config
+        let mut player1 = Player::new(
+            &player_name,
+            &planet_name,
+            &buildings_config,
+            &economy_config,
+            &sub_locations_config,
         );
+        let speed = economy_config.speed.default.clone();
+        player1.set_production_ticks(economy_config.speed.get_ticks(&speed).unwrap_or(1));
+
+        let mut stats = GameStats::default();
+        stats.add_milestone(1, &format!("{} founded a colony on {}.", player1.get_name(), planet_name));
+        let mut replay = Replay::default();
+        replay.set_name_seed(name_seed);
+        replay.stamp(env!("CARGO_PKG_VERSION"), Self::get_config_checksums(paths));
 
         Ok(
             GameCore {
                 command_registry,
                 buildings_config,
+                economy_config,
+                governors_config,
+                edicts_config,
+                artifacts_config,
+                synergies_config,
+                challenge_config,
+                specializations_config,
+                cycles_config,
+                challenge: None,
+                name_generator,
                 turn: Turn::new(1),
-                current_player: "Player 1".to_string(),
+                current_player: player_name,
                 players: HashMap::from([
                     (player1.get_name().to_string(), player1),
                 ]),
+                macro_recorder: MacroRecorder::new(),
+                replay,
+                command_depth: 0,
+                speed,
+                revision: 0,
+                script_engine,
+                script_depth: 0,
+                pending_events: Vec::new(),
+                notifications,
+                turn_records: None,
+                stats,
+                profile: Profile::default(),
+                tutorial_config,
+                tutorial: None,
+                missions: MissionTracker::new(&missions_config),
+                merchants: Merchants::new(merchants_config),
+                disasters: Disasters::new(disasters_config),
+                rules: GameRules::default(),
+                hint_engine: HintEngine::default(),
+                colonypedia,
+                pending_confirmation: None,
+                is_confirmed: false,
                 is_running: true,
             }
         )
     }
 
+    // Saves made with other building or economy configs may play out differently when loaded
+    fn get_config_checksums(paths: &GameDataPaths) -> BTreeMap<String, String> {
+        [
+            ("buildings", GameDataPaths::get_or(&paths.buildings, "data/buildings.toml")),
+            ("economy", GameDataPaths::get_or(&paths.economy, "data/economy.toml")),
+        ]
+        .into_iter()
+        .filter_map(|(config, path)| {
+            let content = fs::read_to_string(path).ok()?;
+            Some((config.to_string(), format!("{:016X}", get_checksum(&content))))
+        })
+        .collect()
+    }
+
+    pub fn get_profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = profile;
+        self.revision += 1;
+    }
+
+    // Folds the statistics of the current game into the profile. Called once when the game ends.
+    pub fn finish_game(&mut self) {
+        let stats = std::mem::take(&mut self.stats);
+        self.profile.record_game(&stats);
+        self.revision += 1;
+    }
+
+    pub fn get_colonypedia(&self) -> &Colonypedia {
+        &self.colonypedia
+    }
+
+    pub fn set_hint_settings(&mut self, settings: HintSettings) {
+        self.hint_engine = HintEngine::new(settings);
+        self.revision += 1;
+    }
+
+    pub fn get_tutorial_status(&self) -> Option<TutorialStatus> {
+        self.tutorial.as_ref().and_then(|tutorial| tutorial.get_status())
+    }
+
+    pub fn get_mission_statuses(&self) -> Vec<MissionStatus> {
+        self.missions.get_statuses()
+    }
+
+    // The decision the player is asked to make, shown as a dialog by the interface
+    // Anything waiting for confirmation comes first, the question is asked again after every
+    // command until it is answered
+    pub fn get_pending_choice(&self) -> Option<Choice> {
+        if let Some((_, prompt)) = &self.pending_confirmation {
+            return Some(Choice {
+                id: format!("confirm:{}", self.replay.get_entries().len()),
+                title: "Confirm".to_string(),
+                text: prompt.clone(),
+                options: vec![ChoiceOption::new("Yes", "confirm"), ChoiceOption::new("No", "cancel")],
+            });
+        }
+
+        // A disaster asks for an answer only on the turn it strikes
+        if let Some(active) = self.disasters.get_active()
+            && active.get_response().is_none()
+        {
+            return Some(Choice {
+                id: format!("disaster:{}:{}", active.disaster.id, active.planet),
+                title: format!("Disaster: {}", active.disaster.name),
+                text: format!("{} strikes {}. {}", active.disaster.name, active.planet, active.disaster.text),
+                options: active
+                    .disaster
+                    .responses
+                    .iter()
+                    .map(|response| {
+                        ChoiceOption::new(
+                            &format!("{} (costs {})", response.label, response.cost),
+                            &format!("disaster {}", response.id),
+                        )
+                    })
+                    .collect(),
+            });
+        }
+
+        let visit = self.merchants.get_visit()?;
+        Some(Choice {
+            id: format!("merchant:{}:{}", visit.offer.id, visit.last_turn),
+            title: format!("Merchant: {}", visit.offer.merchant),
+            text: format!(
+                "{}\n\n{}. The offer stands until the end of turn {}.",
+                visit.offer.pitch, visit.offer.deal, visit.last_turn,
+            ),
+            options: vec![
+                ChoiceOption::new("Accept", "merchant accept"),
+                ChoiceOption::new("Decline", "merchant decline"),
+            ],
+        })
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // Real time is measured by the interface, the game itself only keeps the totals
+    pub fn add_playtime(&mut self, secs: u64) {
+        self.stats.add_playtime(secs);
+        self.replay.add_playtime(secs);
+    }
+
+    pub fn get_playtime_secs(&self) -> u64 {
+        self.stats.get_playtime_secs()
+    }
+
+    pub fn record_turn_time(&mut self, turn: u32, secs: u64) {
+        self.stats.record_turn_time(turn, secs);
+    }
+
     pub fn is_running(&self) -> bool {
         self.is_running
     }
 
+    pub fn is_won(&self) -> bool {
+        self.stats.is_won()
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.stats.is_defeated()
+    }
+
+    // Statistics of the game so far followed by its timeline, shown after a defeat
+    pub fn get_defeat_report(&self) -> String {
+        let buildings_built: u32 = self.stats.get_buildings_built().values().sum();
+        format!(
+            "Defeat. {} turn(s) played, {} building level(s) completed.\n{}",
+            self.stats.get_turns_played(),
+            buildings_built,
+            self.get_timeline_listing(),
+        )
+    }
+
+    // Messages produced outside of a command result, e.g. by scripts reacting to events
+    pub fn take_notifications(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    // Off for new games, so games rebuilt from replays don't record the turns again
+    pub fn set_recording_turns(&mut self, enabled: bool) {
+        self.turn_records = enabled.then(Vec::new);
+    }
+
+    // The turns ended since the last call, while recording
+    pub fn take_turn_records(&mut self) -> Vec<TurnRecord> {
+        self.turn_records.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     pub fn get_current_turn(&self) -> u32 {
         self.turn.get_turn_number()
     }
@@ -124,6 +658,10 @@ impl GameCore {
         self.current_player.clone()
     }
 
+    pub fn get_current_player_color(&self) -> PlayerColor {
+        self.players.get(&self.current_player).map_or(PlayerColor::White, Player::get_color)
+    }
+
     pub fn get_current_player_planet_names(&self) -> Vec<String> {
         self.players.get(self.current_player.as_str()).map_or(vec![], |player| {
             player.get_planet_names()
@@ -133,101 +671,1972 @@ impl GameCore {
     pub fn get_current_player_planet_status(&self, planet_name: &str) -> Option<PlanetStatus> {
         self.players.get(self.current_player.as_str()).and_then(|player| {
             player.get_planet(planet_name).map(|planet| {
-                planet.get_status(player.get_planets_count())
+                planet.get_status(player.get_planets_count(), &self.economy_config)
             })
         })
     }
 
+    // Everything the player may see, handed to bots when it is their turn
+    pub fn get_player_view(&self, player_name: &str) -> Option<PlayerView> {
+        let player = self.players.get(player_name)?;
+        let mut planet_names = player.get_planet_names();
+        planet_names.sort();
+        Some(PlayerView {
+            turn: self.turn.get_turn_number(),
+            player_name: player_name.to_string(),
+            credits: player.get_credits(),
+            credits_income: player.get_credits_forecast(&self.economy_config).get_net(),
+            planets: planet_names
+                .iter()
+                .filter_map(|planet_name| player.get_planet(planet_name))
+                .map(|planet| planet.get_status(player.get_planets_count(), &self.economy_config))
+                .collect(),
+            artifacts: player.get_artifacts().iter().map(|artifact| artifact.name.clone()).collect(),
+            pending_choice: self.get_pending_choice(),
+            max_levels: BuildingTypeId::all()
+                .iter()
+                .filter_map(|&id| Some((id, self.buildings_config.buildings.get(id.get_name())?.get_max_level())))
+                .collect(),
+        })
+    }
+
+    // What the command being typed would cost, shown next to the command input. Only
+    // constructions have a preview so far.
+    pub fn get_command_preview(&self, input: &str) -> Option<String> {
+        let Ok(CommandExecution::Build(build_command)) = CommandExecution::parse(&self.command_registry, input) else {
+            return None;
+        };
+        let building_id = Self::find_building_instance(build_command.get_building()).ok()?;
+        let building_config = self.buildings_config.buildings.get(building_id.type_id.get_name())?;
+        let (planet_name, sub_location) = Planet::split_location(build_command.get_planet());
+        let planet = self.players.get(&self.current_player)?.get_planet(planet_name)?;
+        let forecast = planet.forecast_construction(building_id, building_config, sub_location).ok()?;
+
+        let cost: Vec<String> = forecast.cost
+            .iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(resource, amount)| format!("{} {}", amount, resource))
+            .collect();
+        let cost = if cost.is_empty() { "nothing".to_string() } else { cost.join(", ") };
+        let affordable = match forecast.affordable_in {
+            Some(0) => "affordable now".to_string(),
+            Some(turns) => format!("affordable in {} turn(s)", turns),
+            None => "not affordable at the current production".to_string(),
+        };
+        Some(format!("{} level {} costs {}, {}", building_id, forecast.level, cost, affordable))
+    }
+
+    // Dry run of the command being typed: whether it is known, has the right number of arguments
+    // and, for constructions, names a known building and planet. The word still being typed
+    // only needs to be the start of something valid.
+    pub fn check_input(&self, input: &str) -> Option<InputCheck> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let (command_name, args) = parts.split_first()?;
+        let is_typing = !input.ends_with(char::is_whitespace);
+        let command_name = command_name.to_lowercase();
+
+        let Some(definitions) = self.command_registry.get_command_definitions(&command_name) else {
+            if is_typing && args.is_empty() && self.command_registry.has_command_starting_with(&command_name) {
+                return None;
+            }
+            return Some(InputCheck::Invalid(format!("Unknown command '{}'", command_name)));
+        };
+        if matches!(definitions[0].name.as_str(), "build" | "rush")
+            && let Some(check) = self.check_construction_args(args, is_typing)
+        {
+            return Some(check);
+        }
+
+        if definitions.iter().any(|definition| definition.expected_args == args.len()) {
+            return CommandExecution::parse(&self.command_registry, input)
+                .err()
+                .map(|err| InputCheck::Invalid(err.to_string()));
+        }
+        match self.command_registry.get_missing_arg_hints(input) {
+            Some(hints) => Some(InputCheck::Incomplete(format!("Needs {}", hints.trim_start()))),
+            None => {
+                let usages: Vec<String> = definitions.iter().map(|definition| definition.get_usage()).collect();
+                Some(InputCheck::Invalid(format!("Too many arguments, use {}", usages.join(" | "))))
+            }
+        }
+    }
+
+    fn check_construction_args(&self, args: &[&str], is_typing: bool) -> Option<InputCheck> {
+        let starts_with = |name: &str, prefix: &str| name.to_lowercase().starts_with(&prefix.to_lowercase());
+        let planet_names = self.get_current_player_planet_names();
+
+        for (idx, arg) in args.iter().enumerate() {
+            let is_last_typing = is_typing && idx + 1 == args.len();
+            match idx {
+                0 if Self::find_building_instance(arg).is_err() => {
+                    let is_start = is_last_typing && BuildingTypeId::all().iter().any(|id| starts_with(id.get_name(), arg));
+                    if !is_start {
+                        return Some(InputCheck::Invalid(format!("Building '{}' not recognized", arg)));
+                    }
+                }
+                1 => {
+                    let (planet_name, _) = Planet::split_location(arg);
+                    // Planet names are matched exactly
+                    let is_known = planet_names.iter().any(|name| name == planet_name);
+                    let is_start = is_last_typing && planet_names.iter().any(|name| name.starts_with(planet_name));
+                    if !is_known && !is_start {
+                        return Some(InputCheck::Invalid(format!("Planet '{}' not found", planet_name)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // Ghost text for the arguments the command being typed still needs
+    pub fn get_missing_arg_hints(&self, input: &str) -> Option<String> {
+        self.command_registry.get_missing_arg_hints(input)
+    }
+
+    // Help is shown as a table rather than a plain message
+    pub fn is_help_command(&self, input: &str) -> bool {
+        matches!(CommandExecution::parse(&self.command_registry, input), Ok(CommandExecution::Help(_)))
+    }
+
+    // How many turns the command would end at most, the interface shows a progress indicator for
+    // these
+    pub fn get_turns_ended_by(&self, input: &str) -> u32 {
+        match CommandExecution::parse(&self.command_registry, input) {
+            Ok(CommandExecution::EndTurn(_)) => 1,
+            Ok(CommandExecution::Wait(wait_command)) => wait_command.get_turns(),
+            _ => 0,
+        }
+    }
+
+    // Name of the flagged command waiting for confirmation, the interface may confirm it on its own
+    pub fn get_confirmation_command(&self) -> Option<String> {
+        match &self.pending_confirmation {
+            Some((PendingAction::Command(command), _)) => {
+                let name = command.split_whitespace().next()?.to_lowercase();
+                self.get_definition_name(&name)
+            }
+            _ => None,
+        }
+    }
+
+    // Whether the current player still has something to do this turn, the interface ends the turn
+    // on its own otherwise when auto end-turn is enabled
+    // TODO: Count fleets that can still move once there are fleets
+    pub fn has_available_actions(&self) -> bool {
+        if self.get_pending_choice().is_some() || self.pending_confirmation.is_some() {
+            return true;
+        }
+        let Some(player) = self.players.get(&self.current_player) else {
+            return false;
+        };
+        player
+            .get_planet_names()
+            .iter()
+            .filter_map(|planet_name| player.get_planet(planet_name))
+            .any(|planet| planet.has_affordable_construction(&self.buildings_config, &self.economy_config))
+    }
+
+    // Problems on the current player's planets, ordered by planet
+    pub fn get_alerts(&self) -> Vec<Alert> {
+        let mut planet_names = self.get_current_player_planet_names();
+        planet_names.sort();
+        planet_names
+            .iter()
+            .filter_map(|planet_name| self.get_current_player_planet_status(planet_name))
+            .flat_map(|status| Alert::collect(&status))
+            .collect()
+    }
+
+    pub fn get_current_player_credits(&self) -> ResourceAmount {
+        self.players.get(self.current_player.as_str()).map_or(ResourceAmount::ZERO, |player| player.get_credits())
+    }
+
+    pub fn get_current_player_artifact_names(&self) -> Vec<String> {
+        self.players.get(&self.current_player).map_or(vec![], |player| {
+            player.get_artifacts().iter().map(|artifact| artifact.name.clone()).collect()
+        })
+    }
+
+    // Net credits expected at the end of the current turn
+    pub fn get_current_player_credits_income(&self) -> i64 {
+        self.players.get(self.current_player.as_str()).map_or(0, |player| {
+            player.get_credits_forecast(&self.economy_config).get_net()
+        })
+    }
+
     pub fn get_planet_count(&self) -> usize {
         self.players.get(self.current_player.as_str()).map_or(0, |player| {
             player.get_planets_count()
         })
     }
-    
-    pub fn remove_player(&mut self, player_name: &str) {
-        self.players.remove(player_name);
+
+    // Changes whenever the world may have changed, so what the interface derived from it can be
+    // kept until then
+    pub fn get_revision(&self) -> u64 {
+        self.revision
+    }
+
+    // Swaps in another game, e.g. one rebuilt from a replay, with a revision newer than both
+    pub fn replace(&mut self, game_core: GameCore) {
+        let revision = self.revision.max(game_core.revision) + 1;
+        *self = game_core;
+        self.revision = revision;
+    }
+
+    pub fn get_speed(&self) -> &str {
+        &self.speed
     }
 
     pub fn execute_command(
         &mut self,
         command: &str,
     ) -> Result<Option<String>, GameCoreError> {
-        let command = CommandExecution::parse(&self.command_registry, command)?;
-        
-        match command {
-            CommandExecution::Build(build_command) => {
-                let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
-                    GameCoreError::CommandError(CommandError::new("Current player not found."))
-                })?;
+        // Nested commands are replayed by the command that issued them
+        if self.command_depth == 0 {
+            self.replay.record(self.turn.get_turn_number(), command);
+        }
 
-                let planet = player.get_mut_planet(build_command.get_planet()).ok_or_else(|| {
-                    GameCoreError::CommandError(
-                        CommandError::new(&format!("Planet '{}' not found.", build_command.get_planet()))
-                    )
-                })?;
+        self.command_depth += 1;
+        let result = self.run_command(command);
+        self.command_depth -= 1;
+        self.revision += 1;
+        if self.command_depth == 0 && result.is_err() {
+            self.replay.set_last_failed();
+        }
 
-                // Find the BuildingTypeId corresponding to the name
-                let building_name_to_build = build_command.get_building();
-                let target_building_id = BuildingTypeId::all()
-                   .iter()
-                   .find(|&&id| id.get_name().eq_ignore_ascii_case(building_name_to_build))
-                   .cloned()
-                   .ok_or_else(
-                    || GameCoreError::CommandError(
-                            CommandError::new(&format!("Building '{}' not recognized.", building_name_to_build))
-                        )
-                    )?;
+        // Stops a debug build right at the command that left the world broken
+        #[cfg(debug_assertions)]
+        if self.command_depth == 0
+            && let Err(err) = self.check_invariants()
+        {
+            panic!("Command '{}' broke an invariant: {}", command, err);
+        }
+        result
+    }
+
+    // Validates the whole world model, describing the first inconsistency found. Run after every
+    // command in debug builds and by the command fuzzer.
+    // TODO: Check fleets are at existing locations once there are fleets
+    pub fn check_invariants(&self) -> Result<(), String> {
+        if !self.players.contains_key(&self.current_player) {
+            return Err(format!("Current player '{}' doesn't exist", self.current_player));
+        }
 
+        for (player_name, player) in &self.players {
+            for planet_name in player.get_planet_names() {
+                let planet = player
+                    .get_planet(&planet_name)
+                    .ok_or_else(|| format!("{} lists planet '{}' without having it", player_name, planet_name))?;
+                if planet.get_name() != planet_name {
+                    return Err(format!("{}'s planet '{}' is named '{}'", player_name, planet_name, planet.get_name()));
+                }
+                planet.check_invariants(&self.buildings_config, &self.economy_config)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn run_command(&mut self, command: &str) -> Result<Option<String>, GameCoreError> {
+        let parsed_command = CommandExecution::parse(&self.command_registry, command)?;
+        if !self.is_confirmed
+            && let Some(definition) = self.get_confirmed_definition(command)
+        {
+            let prompt = format!(
+                "Run '{}'? {} Type 'confirm' to go ahead or 'cancel'.", command.trim(), definition.description,
+            );
+            self.pending_confirmation = Some((PendingAction::Command(command.to_string()), prompt.clone()));
+            return Ok(Some(prompt));
+        }
+        let is_macro_command = matches!(parsed_command, CommandExecution::Macro(_));
+        let command_name = self.get_definition_name(parsed_command.get_name());
+
+        let result = self.execute_parsed_command(parsed_command);
+        if let (Ok(_), Some(command_name)) = (&result, command_name) {
+            self.pending_events.push(GameEvent::CommandExecuted { command: command_name });
+        }
+        self.dispatch_events();
+        let result = result?;
+
+        // Only commands that succeeded end up in a macro being recorded
+        if !is_macro_command && self.macro_recorder.is_recording() {
+            let planet_names = self.get_current_player_planet_names();
+            self.macro_recorder.record(command, &planet_names);
+        }
+
+        Ok(result)
+    }
+
+    fn end_turn(&mut self) -> Result<String, GameCoreError> {
+        if let Some(challenge) = &self.challenge
+            && challenge.get_final_score().is_some()
+        {
+            return Err(CommandError::new(&format!(
+                "The daily challenge is over after {} turns. Type `challenge` for your result.",
+                challenge.get_turns(),
+            )).into());
+        }
+
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+
+        let report = player.process_turn_end(&self.economy_config)?;
+        let activated = player.update_synergy_modifiers(&self.synergies_config);
+        let credits_report = report.credits;
+
+        for (planet, construction) in report.completed {
+            let stage = self.buildings_config.buildings
+                .get(construction.building.type_id.get_name())
+                .and_then(|config| config.get_stage_name(construction.level));
+            self.notifications.push(match stage {
+                Some(stage) => format!(
+                    "Stage {} of the {}, {}, on {} is complete.",
+                    construction.level,
+                    construction.building.get_name(),
+                    stage,
+                    construction.get_location(planet.as_str()),
+                ),
+                None => format!(
+                    "{} level {} on {} is complete.",
+                    construction.building.get_name(),
+                    construction.level,
+                    construction.get_location(planet.as_str()),
+                ),
+            });
+            self.pending_events.push(GameEvent::BuildingCompleted {
+                planet: planet.to_string(),
+                building: construction.building.type_id,
+                level: construction.level,
+            });
+        }
+
+        for (planet, synergy) in activated {
+            self.notifications.push(format!("{} is now active on {}.", synergy, planet));
+        }
+
+        for (resource, amount, from, to) in report.shipments {
+            self.notifications.push(format!("Logistics shipped {} {} from {} to {}.", amount, resource, from, to));
+        }
+
+        for (governor, level) in report.promotions {
+            self.notifications.push(format!("Governor {} gained experience and reached level {}.", governor, level));
+        }
+
+        for planet in report.strikes {
+            self.notifications.push(format!(
+                "Morale on {} collapsed, its workers go on strike and produce nothing next turn.", planet
+            ));
+        }
+
+        let turn_number = self.turn.get_turn_number();
+        self.turn.next_turn();
+        self.pending_events.push(GameEvent::TurnEnded { turn: turn_number });
+        self.update_merchants();
+        self.update_disasters();
+        self.update_challenge(turn_number);
+        self.update_cycles();
+        if self.turn_records.is_some()
+            && let Some(record) = self.get_turn_record(turn_number)
+            && let Some(turn_records) = &mut self.turn_records
+        {
+            turn_records.push(record);
+        }
+
+        if !credits_report.unpaid.is_zero() {
+            self.notifications.push(format!(
+                "The treasury is empty, {} credits of upkeep could not be paid.", credits_report.unpaid
+            ));
+        }
+
+        // TODO: Handle switching to the next player if multiple players exist
+        // TODO: Eliminate players who lost their last planet here once planets can be lost: take
+        // them out of the rotation, notify everyone, convert their fleets as
+        // configured, and end the game as won when a single player or team is left
+        // TODO: Carry out fleet standing orders (patrols, returning for repairs) according to
+        // each fleet's stance here once fleets exist
+        // TODO: Apply map hazards here once the galaxy generator places them: asteroid fields
+        // damaging unescorted fleets and solar storms cutting a system's energy for a turn, with
+        // nebulae slowing movement and blocking sensors in pathfinding
+
+        Ok(format!(
+            "Turn {} ended. Credits {:+} (taxes {}, trade {}, upkeep {}).",
+            turn_number,
+            credits_report.get_net(),
+            credits_report.taxes,
+            credits_report.trade,
+            credits_report.upkeep,
+        ))
+    }
 
+    // Ends turns in a row until anything worth a message happens, e.g. a construction completes
+    // or a scripted event fires
+    // TODO: Stop as well when an enemy fleet is spotted once there are fleets
+    fn wait_turns(&mut self, turns: u32) -> Result<String, GameCoreError> {
+        let credits = self.get_current_player_credits();
+        let mut waited = 0;
+        let mut stopped_by = None;
+        while waited < turns && self.is_running {
+            let notification_count = self.notifications.len();
+            self.end_turn()?;
+            self.dispatch_events();
+            waited += 1;
+
+            if let Some(notification) = self.notifications.get(notification_count) {
+                stopped_by = Some(notification.clone());
+                break;
+            }
+        }
+
+        let mut summary = format!(
+            "Waited {} of {} turn(s), now turn {}. Credits {:+}.",
+            waited,
+            turns,
+            self.turn.get_turn_number(),
+            i64::from(self.get_current_player_credits()) - i64::from(credits),
+        );
+        if let Some(notification) = stopped_by.filter(|_| waited < turns) {
+            summary.push_str(&format!(" Stopped early: {}", notification));
+        }
+        Ok(summary)
+    }
+
+    // Resolves aliases to the name the command is defined under
+    fn get_definition_name(&self, name: &str) -> Option<String> {
+        self.command_registry
+            .get_command_definitions(name)
+            .and_then(|definitions| definitions.first())
+            .map(|definition| definition.name.clone())
+    }
+
+    // The definition of the command when it is flagged to ask for confirmation first
+    fn get_confirmed_definition(&self, command: &str) -> Option<CommandDefinition> {
+        let mut parts = command.split_whitespace();
+        let name = parts.next()?.to_lowercase();
+        let arg_count = parts.count();
+        self.command_registry
+            .get_command_definitions(&name)?
+            .iter()
+            .find(|definition| definition.expected_args == arg_count && definition.confirm)
+            .cloned()
+    }
+
+    fn execute_parsed_command(
+        &mut self,
+        command: CommandExecution,
+    ) -> Result<Option<String>, GameCoreError> {
+        match command {
+            CommandExecution::Build(build_command) => {
+                let target_building_id = Self::find_building_instance(build_command.get_building())?;
                 let building_config = self.buildings_config.buildings.get(
-                    target_building_id.get_name()
+                    target_building_id.type_id.get_name()
                 ).ok_or_else(|| {
                     // This should ideally not happen if BuildingTypeId::all() is consistent with config keys
                     GameCoreError::BuildingConfigError(
                         BuildingsConfigError::Toml(
                             TomlError::custom(
-                                format!("Building '{}' not found in config.", target_building_id.get_name())
+                                format!("Building '{}' not found in config.", target_building_id.type_id.get_name())
                             )
                         )
                     )
                 })?;
 
-                planet.build(target_building_id, building_config)?;
+                let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+                    GameCoreError::CommandError(CommandError::new("Current player not found."))
+                })?;
+
+                let (planet_name, sub_location) = Planet::split_location(build_command.get_planet());
+                if building_config.is_wonder()
+                    && let Some(wonder_planet) = player.find_wonder_planet(target_building_id.type_id)
+                    && wonder_planet.as_str() != planet_name
+                {
+                    return Err(CommandError::new(&format!(
+                        "The {} is a wonder, your empire builds it only once and it stands on {}.",
+                        target_building_id.get_name(), wonder_planet,
+                    )).into());
+                }
+                let planet = player.get_mut_planet(planet_name).ok_or_else(|| {
+                    GameCoreError::CommandError(
+                        CommandError::new(&format!("Planet '{}' not found.", planet_name))
+                    )
+                })?;
 
-                // TODO: Deduct resources from the planet AFTER successful build/upgrade call
-                // This part is complex as it needs access to upgrade costs based on the *next* level
-                // and mutable access to storage buildings. Needs further implementation.
+                let construction = planet.queue_construction(
+                    target_building_id,
+                    building_config,
+                    &self.economy_config,
+                    sub_location,
+                )?;
+                let (first_level, location) = (construction.level, construction.get_location(planet_name));
+                let (mut last_level, mut turns) = (construction.level, construction.remaining_turns);
 
-                Ok(Some(format!("Build command successful for {} on {}.",
-                    build_command.get_building(),
-                    build_command.get_planet()
-                )))
+                // Further levels queue behind the first one until one can't be afforded
+                let mut stopped_by = None;
+                for _ in 1..build_command.get_count() {
+                    match planet.queue_construction(target_building_id, building_config, &self.economy_config, sub_location) {
+                        Ok(construction) => {
+                            last_level = construction.level;
+                            turns += construction.remaining_turns;
+                        }
+                        Err(err) => {
+                            stopped_by = Some(err);
+                            break;
+                        }
+                    }
+                }
+
+                let mut message = if last_level == first_level {
+                    format!("Construction of {} level {} on {} queued, it takes {} turn(s).",
+                        target_building_id.get_name(), first_level, location, turns)
+                } else {
+                    format!("Construction of {} levels {} to {} on {} queued, they take {} turn(s) in all.",
+                        target_building_id.get_name(), first_level, last_level, location, turns)
+                };
+                if let Some(err) = stopped_by {
+                    message.push_str(&format!(" Level {} was not queued: {}", last_level + 1, err));
+                }
+                Ok(Some(message))
             }
-            CommandExecution::EndTurn(_end_turn_command) => { //
-                let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            CommandExecution::Rush(rush_command) => {
+                let target_building_id = Self::find_building_instance(rush_command.get_building())?;
+                let player = self.players.get(&self.current_player).ok_or_else(|| {
                     GameCoreError::CommandError(CommandError::new("Current player not found."))
                 })?;
 
-                player.process_turn_end()?;
+                let (planet_name, sub_location) = Planet::split_location(rush_command.get_planet());
+                let planet = player.get_planet(planet_name).ok_or_else(|| {
+                    GameCoreError::CommandError(
+                        CommandError::new(&format!("Planet '{}' not found.", planet_name))
+                    )
+                })?;
 
-                let turn_number = self.turn.get_turn_number();
-                self.turn.next_turn();
+                let construction = planet.get_construction(target_building_id, sub_location)
+                    .ok_or(PlanetError::NotUnderConstruction(target_building_id))?;
+                let price = self.economy_config.get_rush_price(construction.remaining_turns);
+                let message = format!(
+                    "Rushing {} level {} on {} ({} turn(s) left) costs {} credits. Type 'confirm' to pay or 'cancel'.",
+                    target_building_id.get_name(),
+                    construction.level,
+                    construction.get_location(planet.get_name()),
+                    construction.remaining_turns,
+                    price,
+                );
 
-                // TODO: Handle switching to the next player if multiple players exist
+                let action = PendingAction::Rush {
+                    planet: planet.get_name().to_string(),
+                    sub_location: construction.sub_location.clone(),
+                    building: target_building_id,
+                    price,
+                };
+                self.pending_confirmation = Some((action, message.clone()));
+                Ok(Some(message))
+            }
+            CommandExecution::Confirm(confirm_command) => {
+                let (pending_action, _) = self.pending_confirmation.take().ok_or_else(|| {
+                    CommandError::new("Nothing is waiting for confirmation.")
+                })?;
 
-                Ok(Some(format!("Turn {} ended.", turn_number)))
+                if !confirm_command.is_accepted() {
+                    return Ok(Some("Cancelled.".to_string()));
+                }
+                self.execute_pending_action(pending_action)
             }
+            CommandExecution::EndTurn(_end_turn_command) => self.end_turn().map(Some),
+            CommandExecution::Wait(wait_command) => self.wait_turns(wait_command.get_turns()).map(Some),
             CommandExecution::Quit(_) => {
                 self.is_running = false;
                 Ok(Some("Quit command recognized.".to_string()))
             }
-            CommandExecution::Help(_) => {
-                // TODO: Implement help command
-                Ok(Some("Help command recognized.".to_string()))
+            // Asks for confirmation first, see commands.toml
+            // TODO: Hand the empire to a bot instead of ending the game once games seat several
+            // players, so the others can play on
+            CommandExecution::Surrender(_) => {
+                let turn = self.turn.get_turn_number();
+                self.stats.add_milestone(turn, &format!("{} surrendered.", self.current_player));
+                self.stats.set_defeated();
+                self.is_running = false;
+                Ok(Some(format!("{} surrendered on turn {}.", self.current_player, turn)))
+            }
+            CommandExecution::Player(player_command) => self.execute_player_action(player_command.get_action()).map(Some),
+            CommandExecution::Rules(rules_command) => match rules_command.get_change() {
+                Some((rule, value)) => self.set_rule(rule, value).map(Some),
+                None => Ok(Some(self.rules.get_listing())),
+            },
+            CommandExecution::Help(parsed_command) => match parsed_command.get_args().first() {
+                Some(command_name) => self.command_registry
+                    .get_command_help_lines(command_name)
+                    .map(|lines| Some(lines.join("\n")))
+                    .ok_or_else(|| CommandError::new(&format!(
+                        "Unknown command: '{}'. Type 'help' for available commands.", command_name
+                    )).into()),
+                None => Ok(Some(self.command_registry.get_help_lines(None).join("\n"))),
+            },
+            CommandExecution::Macro(macro_command) => {
+                self.execute_macro_action(macro_command.get_action())
+            }
+            CommandExecution::Profile(profile_command) => match profile_command.get_action() {
+                ProfileAction::Show => Ok(Some(self.profile.get_summary())),
+                ProfileAction::Reset => {
+                    self.profile.reset();
+                    Ok(Some("Profile statistics reset.".to_string()))
+                }
+            },
+            CommandExecution::Tax(tax_command) => {
+                let economy_config = &self.economy_config;
+                let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+                    GameCoreError::CommandError(CommandError::new("Current player not found."))
+                })?;
+
+                let planet = player.get_mut_planet(tax_command.get_planet()).ok_or_else(|| {
+                    GameCoreError::CommandError(
+                        CommandError::new(&format!("Planet '{}' not found.", tax_command.get_planet()))
+                    )
+                })?;
+
+                if let Some(tax_rate) = tax_command.get_rate() {
+                    planet.set_tax_rate(tax_rate, economy_config)?;
+                }
+
+                Ok(Some(format!(
+                    "Tax rate on {} is {}%, bringing in {} credits and {}% population growth per turn. Morale heads towards {}%.",
+                    planet.get_name(),
+                    planet.get_tax_rate(),
+                    planet.get_tax_income(economy_config),
+                    economy_config.get_growth_percent(planet.get_tax_rate()),
+                    planet.get_morale_target(economy_config),
+                )))
+            }
+            CommandExecution::Queue(queue_command) => self.execute_queue_action(queue_command.get_action()).map(Some),
+            CommandExecution::Governor(governor_command) => {
+                self.execute_governor_action(governor_command.get_action())
+            }
+            CommandExecution::Speed(speed_command) => match speed_command.get_speed() {
+                Some(speed) => self.set_speed(speed),
+                None => Ok(Some(self.get_speed_listing())),
+            },
+            CommandExecution::Edict(edict_command) => match edict_command.get_edict() {
+                Some(edict_id) => self.toggle_edict(edict_id),
+                None => Ok(Some(self.get_edicts_listing())),
+            },
+            CommandExecution::Stockpile(stockpile_command) => match stockpile_command.get_target() {
+                Some(target) => self.set_stockpile_target(&target.planet, target.resource, target.amount).map(Some),
+                None => Ok(Some(self.get_stockpiles_listing())),
+            },
+            CommandExecution::Designate(designate_command) => match designate_command.get_designation() {
+                Some((planet, specialization)) => self.designate_planet(planet, specialization).map(Some),
+                None => Ok(Some(self.get_specializations_listing())),
+            },
+            CommandExecution::Artifacts(_) => Ok(Some(self.get_artifacts_listing())),
+            CommandExecution::Breakdown(breakdown_command) => {
+                self.get_breakdown_listing(breakdown_command.get_resource(), breakdown_command.get_planet())
+            }
+            CommandExecution::Synergies(synergies_command) => self.get_synergies_listing(synergies_command.get_planet()),
+            CommandExecution::Compare(compare_command) => {
+                let (first, second) = compare_command.get_planets();
+                self.get_comparison_listing(first, second)
+            }
+            CommandExecution::Timeline(_) => Ok(Some(self.get_timeline_listing())),
+            CommandExecution::Production(production_command) => {
+                self.get_production_listing(production_command.get_sort_by()).map(Some)
+            }
+            CommandExecution::Disaster(disaster_command) => {
+                self.execute_disaster_action(disaster_command.get_action())
+            }
+            CommandExecution::Merchant(merchant_command) => {
+                self.execute_merchant_action(merchant_command.get_action())
+            }
+            CommandExecution::Tutorial(tutorial_command) => match tutorial_command.get_action() {
+                TutorialAction::Start => {
+                    let planet = self.get_current_player_planet_names().into_iter().next().unwrap_or_default();
+                    self.tutorial = Some(Tutorial::new(&self.tutorial_config, &planet));
+                    Ok(Some("Tutorial started. Follow the instructions in the tutorial box.".to_string()))
+                }
+                TutorialAction::Stop => {
+                    if self.tutorial.take().is_none() {
+                        return Err(CommandError::new("No tutorial is running.").into());
+                    }
+                    Ok(Some("Tutorial stopped.".to_string()))
+                }
+            },
+            CommandExecution::Challenge(challenge_command) => match challenge_command.get_date() {
+                Some(date) => self.start_challenge(date).map(Some),
+                None => self.get_challenge_listing().map(Some),
+            },
+            CommandExecution::Script(parsed_command) => {
+                let (message, output) = self.script_engine.run_command(
+                    &parsed_command.get_definition().name,
+                    parsed_command.get_args(),
+                    self.get_script_snapshot(),
+                )?;
+                self.run_script_output(output)?;
+                Ok(message)
             }
             CommandExecution::UnknownInternal(_) => {
                 Err(GameCoreError::CommandError(CommandError::new("Parsed command is unknown internally.")))
             }
         }
     }
-}
+
+    fn execute_pending_action(&mut self, action: PendingAction) -> Result<Option<String>, GameCoreError> {
+        match action {
+            PendingAction::Command(command) => {
+                self.is_confirmed = true;
+                let result = self.run_command(&command);
+                self.is_confirmed = false;
+                result
+            }
+            PendingAction::Rush { planet: planet_name, sub_location, building, price } => {
+                let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+                    GameCoreError::CommandError(CommandError::new("Current player not found."))
+                })?;
+
+                let planet = player.get_planet(&planet_name).ok_or_else(|| {
+                    GameCoreError::CommandError(CommandError::new(&format!("Planet '{}' not found.", planet_name)))
+                })?;
+
+                // The quote is stale if turns passed or the construction finished since it was made
+                let construction = planet.get_construction(building, sub_location.as_deref())
+                    .ok_or(PlanetError::NotUnderConstruction(building))?;
+                if self.economy_config.get_rush_price(construction.remaining_turns) != price {
+                    return Err(CommandError::new(&format!(
+                        "The rush price of {} changed, rush it again for a new quote.", building.get_name()
+                    )).into());
+                }
+
+                if player.get_credits() < price {
+                    return Err(CommandError::new(&format!(
+                        "Not enough credits, rushing {} costs {} but the treasury holds {}.",
+                        building.get_name(), price, player.get_credits(),
+                    )).into());
+                }
+
+                let planet = player.get_mut_planet(&planet_name).ok_or_else(|| {
+                    GameCoreError::CommandError(CommandError::new(&format!("Planet '{}' not found.", planet_name)))
+                })?;
+                let construction = planet.rush_construction(building, sub_location.as_deref())?;
+                player.spend_credits(price);
+                for (planet, synergy) in player.update_synergy_modifiers(&self.synergies_config) {
+                    self.notifications.push(format!("{} is now active on {}.", synergy, planet));
+                }
+                self.pending_events.push(GameEvent::BuildingCompleted {
+                    planet: planet_name.clone(),
+                    building: building.type_id,
+                    level: construction.level,
+                });
+
+                Ok(Some(format!(
+                    "Rushed {} level {} on {} for {} credits.",
+                    building.get_name(),
+                    construction.level,
+                    construction.get_location(&planet_name),
+                    price,
+                )))
+            }
+        }
+    }
+
+    fn execute_queue_action(&mut self, action: &QueueAction) -> Result<String, GameCoreError> {
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+        let planet_name = match action {
+            QueueAction::Show(planet) | QueueAction::Move { planet: Some(planet), .. } => planet.clone(),
+            QueueAction::Move { planet: None, .. } => match player.get_planet_names().as_slice() {
+                [planet] => planet.clone(),
+                _ => return Err(GameCoreError::CommandError(CommandError::new(
+                    "You have more than one planet, name it: queue move <planet> <position> <new_position>."
+                ))),
+            },
+        };
+        let planet = player.get_mut_planet(&planet_name).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new(&format!("Planet '{}' not found.", planet_name)))
+        })?;
+
+        match action {
+            QueueAction::Show(_) => {
+                let queue = planet.get_construction_queue();
+                if queue.is_empty() {
+                    return Ok(format!("Nothing is being built on {}.", planet_name));
+                }
+                let mut lines = vec![format!("Construction queue of {}:", planet_name)];
+                for (idx, construction) in queue.iter().enumerate() {
+                    lines.push(format!(
+                        "  {}. {} level {} on {}, {} turn(s){}",
+                        idx + 1,
+                        construction.building.get_name(),
+                        construction.level,
+                        construction.get_location(&planet_name),
+                        construction.remaining_turns,
+                        if construction.is_reserved { "" } else { ", not paid for yet" },
+                    ));
+                }
+                Ok(lines.join("\n"))
+            }
+            QueueAction::Move { position, new_position, .. } => {
+                let construction = planet.move_construction(*position, *new_position)?;
+                Ok(format!(
+                    "{} level {} on {} moved to position {} of the queue.",
+                    construction.building.get_name(),
+                    construction.level,
+                    construction.get_location(&planet_name),
+                    new_position,
+                ))
+            }
+        }
+    }
+
+    fn execute_governor_action(&mut self, action: &GovernorAction) -> Result<Option<String>, GameCoreError> {
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+
+        match action {
+            GovernorAction::List => {
+                let mut lines = vec!["Governors:".to_string()];
+                for config in &self.governors_config.governors {
+                    let governors = player.get_governors();
+                    let hired = governors.iter().find(|(governor, _)| governor.get_id() == config.id);
+                    let status = match hired {
+                        Some((governor, Some(planet))) => format!(
+                            "governing {}, level {} ({} turns)", planet, governor.get_level(), governor.get_experience()
+                        ),
+                        Some((governor, None)) => format!("idle, level {}", governor.get_level()),
+                        None => format!("hire for {} credits", config.hire_cost),
+                    };
+                    let traits: Vec<String> = config.traits.iter().map(|governor_trait| governor_trait.to_string()).collect();
+                    lines.push(format!("  {}: {} - {}", config.id, traits.join(", "), status));
+                }
+                Ok(Some(lines.join("\n")))
+            }
+            GovernorAction::Hire(governor_id) => {
+                let config = self.governors_config.find(governor_id).ok_or_else(|| {
+                    CommandError::new(&format!("Governor '{}' not found. Type 'governors' to list them.", governor_id))
+                })?;
+                if player.has_governor(&config.id) {
+                    return Err(CommandError::new(&format!("{} is already hired.", config.name)).into());
+                }
+                if !player.spend_credits(config.hire_cost) {
+                    return Err(CommandError::new(&format!(
+                        "Not enough credits, hiring {} costs {} but the treasury holds {}.",
+                        config.name, config.hire_cost, player.get_credits(),
+                    )).into());
+                }
+
+                player.add_idle_governor(Governor::new(config, &self.governors_config));
+                Ok(Some(format!(
+                    "Hired {} for {} credits. Assign them with 'assign-governor {} <planet>'.",
+                    config.name, config.hire_cost, config.id,
+                )))
+            }
+            GovernorAction::Assign { governor: governor_id, planet: planet_name } => {
+                if player.get_planet(planet_name).is_none() {
+                    return Err(CommandError::new(&format!("Planet '{}' not found.", planet_name)).into());
+                }
+                let governor = player.take_governor(governor_id).ok_or_else(|| {
+                    CommandError::new(&format!("Governor '{}' is not hired. Hire them with 'hire-governor'.", governor_id))
+                })?;
+
+                let governor_name = governor.get_name().to_string();
+                let replaced = player.assign_governor(governor, planet_name)?;
+
+                match replaced {
+                    Some(replaced) => {
+                        let message = format!(
+                            "{} now governs {}, {} is idle.", governor_name, planet_name, replaced.get_name()
+                        );
+                        player.add_idle_governor(replaced);
+                        Ok(Some(message))
+                    }
+                    None => Ok(Some(format!("{} now governs {}.", governor_name, planet_name))),
+                }
+            }
+        }
+    }
+
+    fn execute_merchant_action(&mut self, action: &MerchantAction) -> Result<Option<String>, GameCoreError> {
+        let visit = self.merchants.get_visit().ok_or_else(|| {
+            CommandError::new("No merchant is visiting right now.")
+        })?;
+
+        match action {
+            MerchantAction::Show => Ok(Some(format!(
+                "{} offers: {}. The offer stands until the end of turn {}. Type 'merchant accept' or 'merchant decline'.",
+                visit.offer.merchant, visit.offer.deal, visit.last_turn,
+            ))),
+            MerchantAction::Decline => {
+                let visit = self.merchants.take_visit().expect("a merchant is visiting");
+                Ok(Some(format!("{} leaves to look for better customers.", visit.offer.merchant)))
+            }
+            MerchantAction::Accept => {
+                let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+                    GameCoreError::CommandError(CommandError::new("Current player not found."))
+                })?;
+
+                // A deal the empire can't fulfil keeps the merchant waiting
+                match &visit.offer.deal {
+                    MerchantDeal::SellResource { resource, amount, credits } => {
+                        if !player.spend_resource(*resource, *amount)? {
+                            return Err(CommandError::new(&format!(
+                                "Not enough {}, the deal needs {} but your planets have {} free.",
+                                resource, amount, player.get_free_resource_total(*resource),
+                            )).into());
+                        }
+                        player.add_credits(*credits);
+                    }
+                    MerchantDeal::BuyResource { resource, amount, credits } => {
+                        if player.get_credits() < *credits {
+                            return Err(CommandError::new(&format!(
+                                "Not enough credits, the deal costs {} but the treasury holds {}.",
+                                credits, player.get_credits(),
+                            )).into());
+                        }
+                        if !player.store_resource(*resource, *amount)? {
+                            return Err(CommandError::new(&format!(
+                                "Not enough storage, the deal delivers {} {} but your planets only have room for {}.",
+                                amount, resource, player.get_free_capacity(*resource),
+                            )).into());
+                        }
+                        player.spend_credits(*credits);
+                    }
+                }
+
+                let visit = self.merchants.take_visit().expect("a merchant is visiting");
+                Ok(Some(format!("Deal struck with {}: {}.", visit.offer.merchant, visit.offer.deal)))
+            }
+        }
+    }
+
+    fn execute_disaster_action(&mut self, action: &DisasterAction) -> Result<Option<String>, GameCoreError> {
+        let active = self.disasters.get_active().ok_or_else(|| {
+            CommandError::new("No disaster is going on right now.")
+        })?;
+
+        let response_id = match action {
+            DisasterAction::Show => {
+                let mut lines = vec![format!("{} on {}: {}", active.disaster.name, active.planet, active.disaster.text)];
+                match (active.get_response(), active.get_stage()) {
+                    (Some(response), Some(stage)) => {
+                        let modifiers: Vec<String> = active.get_modifiers().iter().map(|modifier| modifier.to_string()).collect();
+                        lines.push(format!("  You chose to {}. {}", response.label.to_lowercase(), stage.text));
+                        if !modifiers.is_empty() {
+                            lines.push(format!("  {}", modifiers.join(", ")));
+                        }
+                    }
+                    _ => {
+                        for response in &active.disaster.responses {
+                            let turns: u32 = response.stages.iter().map(|stage| stage.turns).sum();
+                            lines.push(format!(
+                                "  disaster {} - {}, costs {}, lasts {} turn(s)",
+                                response.id, response.label, response.cost, turns,
+                            ));
+                        }
+                    }
+                }
+                return Ok(Some(lines.join("\n")));
+            }
+            DisasterAction::Respond(response_id) => response_id,
+        };
+
+        if active.get_response().is_some() {
+            return Err(CommandError::new(&format!(
+                "You already responded to the {}, it has to run its course.", active.disaster.name
+            )).into());
+        }
+        let response = active.disaster.find_response(response_id).ok_or_else(|| {
+            CommandError::new(&format!("Unknown response '{}'. Type 'disaster' to list them.", response_id))
+        })?;
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+
+        let cost = &response.cost;
+        if player.get_credits() < cost.credits {
+            return Err(CommandError::new(&format!(
+                "Not enough credits, this response costs {} but the treasury holds {}.",
+                cost.credits, player.get_credits(),
+            )).into());
+        }
+        if !player.spend_resource(Resource::Energy, cost.energy)? {
+            return Err(CommandError::new(&format!(
+                "Not enough energy, this response costs {} but your planets have {} free.",
+                cost.energy, player.get_free_resource_total(Resource::Energy),
+            )).into());
+        }
+        player.spend_credits(cost.credits);
+
+        let response_id = response.id.clone();
+        self.disasters.respond(&response_id);
+        let message = self.apply_disaster_stage();
+        Ok(Some(message))
+    }
+
+    // Hands the struck planet the modifiers of the disaster's current stage, returns its text
+    fn apply_disaster_stage(&mut self) -> String {
+        let Some(active) = self.disasters.get_active() else {
+            return String::new();
+        };
+        let modifiers = active.get_modifiers();
+        if let Some(player) = self.players.values_mut().find(|player| player.get_planet(active.planet.as_str()).is_some()) {
+            player.set_rule_modifiers(active.disaster.get_modifier_source(), modifiers);
+        }
+        active.get_stage().map_or_else(String::new, |stage| format!("{}: {}", active.disaster.name, stage.text))
+    }
+
+    fn update_disasters(&mut self) {
+        let mut planet_names = self.get_current_player_planet_names();
+        planet_names.sort();
+        let planets: Vec<PlanetId> = planet_names.iter().map(|name| PlanetId::new(name)).collect();
+        let update = self.disasters.start_turn(self.turn.get_turn_number(), &planets);
+
+        if let Some(ended) = update.ended {
+            for player in self.players.values_mut() {
+                player.set_rule_modifiers(ended.disaster.get_modifier_source(), Vec::new());
+            }
+            self.notifications.push(format!("{} on {} is over.", ended.disaster.name, ended.planet));
+        }
+        if update.defaulted || update.advanced {
+            let message = self.apply_disaster_stage();
+            if update.defaulted {
+                self.notifications.push(format!("Nobody responded in time. {}", message));
+            } else {
+                self.notifications.push(message);
+            }
+        }
+        if update.struck
+            && let Some(active) = self.disasters.get_active()
+        {
+            self.notifications.push(format!(
+                "{} strikes {}! {} Type 'disaster' to see how to respond before the turn ends.",
+                active.disaster.name, active.planet, active.disaster.text,
+            ));
+        }
+    }
+
+    // Called once a new turn has started
+    // TODO: Show the color next to the player on the map and the diplomacy screen once they exist
+    fn execute_player_action(&mut self, action: &PlayerAction) -> Result<String, GameCoreError> {
+        let mut player = self.players.remove(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+
+        let result = match action {
+            PlayerAction::Show => Ok(format!("You are {}, playing in {}.", player.get_name(), player.get_color())),
+            // The generator knows every name given so far, a new one is unique
+            PlayerAction::SetName(name) if name.eq_ignore_ascii_case("random") => {
+                let name = self.name_generator.generate(NameKind::Player);
+                player.set_name(&name);
+                Ok(format!("You are now known as {}.", name))
+            }
+            PlayerAction::SetName(name) => {
+                let valid = (1..=20).contains(&name.chars().count())
+                    && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+                if !valid {
+                    Err(CommandError::new("A name has 1 to 20 letters, digits, dashes or underscores."))
+                } else if self.players.keys().any(|other| other.eq_ignore_ascii_case(name)) {
+                    Err(CommandError::new(&format!("The name {} is taken.", name)))
+                } else {
+                    self.name_generator.reserve(name);
+                    player.set_name(name);
+                    Ok(format!("You are now known as {}.", name))
+                }
+            }
+            PlayerAction::SetColor(color_name) => match PlayerColor::from_name(color_name) {
+                Some(color) => {
+                    player.set_color(color);
+                    Ok(format!("Your color is now {}.", color))
+                }
+                None => {
+                    let colors: Vec<String> = PlayerColor::all().iter().map(PlayerColor::to_string).collect();
+                    Err(CommandError::new(&format!(
+                        "Unknown color '{}'. Choose one of: {}.", color_name, colors.join(", ")
+                    )))
+                }
+            },
+        };
+
+        // The player is keyed by name, so a new name moves them
+        self.current_player = player.get_name().to_string();
+        self.players.insert(self.current_player.clone(), player);
+        Ok(result?)
+    }
+
+    // Rules apply to every player from the start, so they can only change before the first turn ended
+    fn set_rule(&mut self, rule: &str, value: &str) -> Result<String, GameCoreError> {
+        if self.turn.get_turn_number() != 1 {
+            return Err(CommandError::new("Rules can only be changed on turn 1.").into());
+        }
+        let message = self.rules.set(rule, value)?;
+
+        for player in self.players.values_mut() {
+            player.set_rule_modifiers(GameRules::get_modifier_source(), self.rules.get_modifiers());
+            player.set_storage_cost_percent(self.rules.get_storage_cost_percent());
+        }
+        self.merchants.set_interval_percent(self.rules.event_frequency.get_interval_percent());
+        Ok(message)
+    }
+
+    // Sets up the scenario of the date for the current player, only before the first turn ended
+    fn start_challenge(&mut self, date: &str) -> Result<String, GameCoreError> {
+        if self.challenge.is_some() {
+            return Err(CommandError::new("This game already is a daily challenge.").into());
+        }
+        if self.turn.get_turn_number() != 1 {
+            return Err(CommandError::new("A daily challenge can only be started on turn 1.").into());
+        }
+        let challenge = Challenge::new(&self.challenge_config, date).ok_or_else(|| {
+            CommandError::new(&format!("Invalid date '{}', use YYYY-MM-DD.", date))
+        })?;
+
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+        player.set_credits(challenge.get_starting_credits());
+        for (resource, amount) in challenge.get_starting_resources() {
+            player.store_resource(*resource, *amount)?;
+        }
+        player.set_rule_modifiers(challenge.get_modifier_source(), challenge.get_modifiers());
+
+        self.stats.add_milestone(1, &format!("The daily challenge of {} began.", challenge.get_date()));
+        let summary = challenge.get_summary();
+        self.challenge = Some(challenge);
+        Ok(summary)
+    }
+
+    fn get_challenge_listing(&self) -> Result<String, GameCoreError> {
+        let Some(challenge) = &self.challenge else {
+            return Err(CommandError::new(&format!(
+                "This game is no daily challenge. Start today's with `challenge {}` on turn 1.",
+                challenge::get_todays_date(),
+            )).into());
+        };
+
+        if let Some(result) = challenge.get_result_line() {
+            return Ok(format!("The daily challenge is over, share your result: {}", result));
+        }
+        Ok(format!(
+            "Daily challenge {}: turn {} of {}, score so far {}.",
+            challenge.get_date(),
+            self.turn.get_turn_number(),
+            challenge.get_turns(),
+            challenge.get_score(&self.get_colony_tally()),
+        ))
+    }
+
+    // Starts and ends the cycles for the turn that just started and announces upcoming ones
+    fn update_cycles(&mut self) {
+        let turn = self.turn.get_turn_number();
+        for cycle in &self.cycles_config.cycles {
+            let modifiers = match cycle.get_change(turn) {
+                Some(CycleChange::Started) => {
+                    self.notifications.push(format!("{} has begun: {}.", cycle.name, cycle.get_effect()));
+                    cycle.get_modifiers()
+                }
+                Some(CycleChange::Ended) => {
+                    self.notifications.push(format!("{} is over.", cycle.name));
+                    Vec::new()
+                }
+                None => {
+                    if cycle.is_announced(turn) {
+                        self.notifications.push(format!(
+                            "{} begins in {} turn(s): {}.", cycle.name, cycle.announce_turns, cycle.get_effect()
+                        ));
+                    }
+                    continue;
+                }
+            };
+            for player in self.players.values_mut() {
+                player.set_rule_modifiers(cycle.get_modifier_source(), modifiers.clone());
+            }
+        }
+    }
+
+    // Scores the colony once the last turn of the challenge ended
+    fn update_challenge(&mut self, ended_turn: u32) {
+        let tally = self.get_colony_tally();
+        let Some(challenge) = &mut self.challenge else {
+            return;
+        };
+        if ended_turn < challenge.get_turns() || challenge.get_final_score().is_some() {
+            return;
+        }
+
+        challenge.finish(challenge.get_score(&tally));
+        if let Some(result) = challenge.get_result_line() {
+            self.notifications.push(format!("The daily challenge is over! Share your result: {}", result));
+            self.stats.add_milestone(ended_turn, &format!("Finished the daily challenge: {}", result));
+        }
+    }
+
+    fn get_turn_record(&self, turn: u32) -> Option<TurnRecord> {
+        let player = self.players.get(&self.current_player)?;
+        let mut production: Vec<(Resource, i64)> = Resource::all().iter().map(|resource| (*resource, 0)).collect();
+        for planet_name in player.get_planet_names() {
+            let Some(planet) = player.get_planet(&planet_name) else {
+                continue;
+            };
+            let planet_production = planet.get_net_production(&self.economy_config);
+            for (resource, amount) in production.iter_mut() {
+                *amount += planet_production.get(resource).copied().unwrap_or(0);
+            }
+        }
+
+        Some(TurnRecord {
+            turn,
+            player: self.current_player.clone(),
+            credits: player.get_credits(),
+            resources: Resource::all()
+                .iter()
+                .filter(|resource| **resource != Resource::Credits)
+                .map(|resource| (*resource, player.get_resource_total(*resource)))
+                .collect(),
+            production,
+            score: self.challenge_config.score.get_score(&self.get_colony_tally()),
+            planet_count: player.get_planets_count(),
+        })
+    }
+
+    // What the current player's colony is worth for the challenge score
+    fn get_colony_tally(&self) -> ColonyTally {
+        let Some(player) = self.players.get(&self.current_player) else {
+            return ColonyTally::default();
+        };
+
+        let mut tally = ColonyTally {
+            credits: player.get_credits(),
+            ..ColonyTally::default()
+        };
+        for resource in Resource::all().iter().filter(|resource| **resource != Resource::Credits) {
+            tally.resources += player.get_resource_total(*resource);
+        }
+        for planet_name in player.get_planet_names() {
+            let Some(status) = self.get_current_player_planet_status(&planet_name) else {
+                continue;
+            };
+            let sub_location_buildings = status.sub_locations.iter().flat_map(|sub_location| &sub_location.buildings);
+            tally.building_levels += status
+                .buildings
+                .iter()
+                .chain(sub_location_buildings)
+                .map(|(_, level)| *level as u32)
+                .sum::<u32>();
+            tally.population += status.population;
+        }
+        tally
+    }
+
+    fn update_merchants(&mut self) {
+        let update = self.merchants.start_turn(self.turn.get_turn_number());
+        if let Some(offer) = update.departed {
+            self.notifications.push(format!("{} left without a deal.", offer.merchant));
+        }
+        if let Some(visit) = update.arrived {
+            self.notifications.push(format!(
+                "{} arrived in orbit: {}. Type 'merchant' to see the offer again, it stands until the end of turn {}.",
+                visit.offer.merchant, visit.offer.deal, visit.last_turn,
+            ));
+        }
+    }
+
+    fn get_edicts_listing(&self) -> String {
+        let turn = self.turn.get_turn_number();
+        let edicts = self.players.get(&self.current_player).map(|player| player.get_edicts());
+
+        let mut lines = vec!["Edicts:".to_string()];
+        for edict in &self.edicts_config.edicts {
+            let modifiers: Vec<String> = edict.modifiers.iter().map(|modifier| modifier.to_string()).collect();
+            let is_active = edicts.is_some_and(|edicts| edicts.is_active(&edict.id));
+            let cooldown = edicts.map_or(0, |edicts| edicts.get_cooldown(&edict.id, turn));
+
+            let mut status = if is_active {
+                "active".to_string()
+            } else {
+                format!("costs {}", edict.activation_cost)
+            };
+            if cooldown > 0 {
+                status.push_str(&format!(", locked for {} turn(s)", cooldown));
+            }
+            lines.push(format!("  {}: {} - {}", edict.id, modifiers.join(", "), status));
+        }
+        lines.join("\n")
+    }
+
+    fn get_stockpiles_listing(&self) -> String {
+        let Some(player) = self.players.get(&self.current_player) else {
+            return "Current player not found.".to_string();
+        };
+        let mut planet_names = player.get_planet_names();
+        planet_names.sort();
+
+        let mut lines = vec![format!(
+            "Stockpile targets (freight {} of each resource per turn):",
+            self.economy_config.logistics.freight_per_turn,
+        )];
+        for planet in planet_names.iter().filter_map(|name| player.get_planet(name)) {
+            for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+                let Some(target) = planet.get_stockpile_target(resource) else {
+                    continue;
+                };
+                let balance = match planet.get_stockpile_balance(resource) {
+                    (surplus, _) if !surplus.is_zero() => format!("{} to spare", surplus),
+                    (_, deficit) if !deficit.is_zero() => format!("{} short", deficit),
+                    _ => "on target".to_string(),
+                };
+                lines.push(format!(
+                    "  {} {}: {} of {} stored, {}",
+                    planet.get_name(), resource, planet.get_resource_amount(resource), target, balance,
+                ));
+            }
+        }
+        if lines.len() == 1 {
+            lines.push("  None. Set one with 'stockpile <planet> <resource> <amount>'.".to_string());
+        }
+        lines.join("\n")
+    }
+
+    fn set_stockpile_target(
+        &mut self,
+        planet_name: &str,
+        resource: Resource,
+        amount: Option<ResourceAmount>,
+    ) -> Result<String, GameCoreError> {
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+        let planet = player.get_mut_planet(planet_name).ok_or_else(|| {
+            CommandError::new(&format!("Planet '{}' not found.", planet_name))
+        })?;
+
+        planet.set_stockpile_target(resource, amount);
+        Ok(match amount {
+            Some(amount) => format!("{} keeps {} {} stored from now on.", planet_name, amount, resource),
+            None => format!("{} no longer has a {} target.", planet_name, resource),
+        })
+    }
+
+    fn get_specializations_listing(&self) -> String {
+        let turn = self.turn.get_turn_number();
+        let mut lines = vec!["Specializations:".to_string()];
+        for specialization in &self.specializations_config.specializations {
+            let modifiers: Vec<String> = specialization.modifiers.iter().map(|modifier| modifier.to_string()).collect();
+            let focus = specialization.focus.map(|resource| format!(", focus {}", resource)).unwrap_or_default();
+            lines.push(format!("  {}: {}{}", specialization.id, modifiers.join(", "), focus));
+        }
+
+        let Some(player) = self.players.get(&self.current_player) else {
+            return lines.join("\n");
+        };
+        lines.push("Planets:".to_string());
+        let mut planet_names = player.get_planet_names();
+        planet_names.sort();
+        for planet in planet_names.iter().filter_map(|name| player.get_planet(name)) {
+            let mut status = planet.get_specialization().map_or("undesignated".to_string(), |specialization| specialization.name.clone());
+            let cooldown = planet.get_respec_cooldown(turn);
+            if cooldown > 0 {
+                status.push_str(&format!(", locked for {} turn(s)", cooldown));
+            }
+            lines.push(format!("  {}: {}", planet.get_name(), status));
+        }
+        lines.join("\n")
+    }
+
+    fn get_artifacts_listing(&self) -> String {
+        let artifacts = self.players.get(&self.current_player).map_or(&[][..], |player| player.get_artifacts());
+        if artifacts.is_empty() {
+            return "Your empire holds no artifacts yet. They are found through discoveries.".to_string();
+        }
+
+        let mut lines = vec!["Artifacts:".to_string()];
+        for artifact in artifacts {
+            let modifiers: Vec<String> = artifact.modifiers.iter().map(|modifier| modifier.to_string()).collect();
+            lines.push(format!("  {}: {}", artifact.name, modifiers.join(", ")));
+        }
+        lines.join("\n")
+    }
+
+    // A tree of everything the production adds up from, with taxes and upkeep for credits
+    fn get_breakdown_listing(&self, resource: Resource, planet_name: &str) -> Result<Option<String>, GameCoreError> {
+        let planet = self.players
+            .get(&self.current_player)
+            .and_then(|player| player.get_planet(planet_name))
+            .ok_or_else(|| CommandError::new(&format!("Planet '{}' not found.", planet_name)))?;
+        let breakdown = planet.get_production_breakdown(resource);
+
+        let buildings: Vec<String> = breakdown.buildings
+            .iter()
+            .map(|(name, level, location, rate)| match location {
+                Some(location) => format!("{} Lvl {} on {}: +{}", name, level, location, rate),
+                None => format!("{} Lvl {}: +{}", name, level, rate),
+            })
+            .collect();
+        let modifiers: Vec<String> = breakdown.modifiers
+            .iter()
+            .map(|(source, percent)| format!("{}: {:+}%", source, percent))
+            .collect();
+        let production = if breakdown.on_strike {
+            "0, the workers are on strike".to_string()
+        } else {
+            format!("{} at {}% = +{}", breakdown.get_base(), breakdown.get_percent().max(0), breakdown.total)
+        };
+
+        let mut lines = Vec::new();
+        if resource != Resource::Credits {
+            lines.push(format!("{} on {}: +{}/t", resource, planet.get_name(), breakdown.total));
+            Self::push_tree_branch(&mut lines, format!("Buildings: +{}", breakdown.get_base()), &buildings, false);
+            Self::push_tree_branch(&mut lines, format!("Modifiers: {:+}%", breakdown.get_percent() - 100), &modifiers, false);
+            Self::push_tree_branch(&mut lines, format!("Total: {}", production), &[], true);
+            return Ok(Some(lines.join("\n")));
+        }
+
+        // Credits go to the treasury, so taxes and upkeep make up most of them
+        let economy_config = &self.economy_config;
+        let taxes = planet.get_tax_income(economy_config);
+        let upkeep = planet.get_upkeep(economy_config);
+        let net = i64::from(breakdown.total) + i64::from(taxes) - i64::from(upkeep);
+        let upkeep_modifiers: Vec<String> = planet.get_modifiers()
+            .get_matching(ModifierStat::Upkeep)
+            .iter()
+            .map(|modifier| format!("{}: {:+}%", modifier.source, modifier.magnitude))
+            .collect();
+
+        lines.push(format!("Credits from {}: {:+}/t", planet.get_name(), net));
+        Self::push_tree_branch(&mut lines, format!("Buildings: +{}", breakdown.get_base()), &buildings, false);
+        Self::push_tree_branch(&mut lines, format!("Modifiers: {:+}%", breakdown.get_percent() - 100), &modifiers, false);
+        Self::push_tree_branch(&mut lines, format!("Trade: {}", production), &[], false);
+        Self::push_tree_branch(
+            &mut lines,
+            format!("Taxes: +{} ({} colonists at {}%)", taxes, planet.get_population(), planet.get_tax_rate()),
+            &[],
+            false,
+        );
+        Self::push_tree_branch(
+            &mut lines,
+            format!(
+                "Upkeep: -{} ({} building levels at {} each)",
+                upkeep, planet.get_building_levels(), economy_config.upkeep.credits_per_building_level,
+            ),
+            &upkeep_modifiers,
+            false,
+        );
+        Self::push_tree_branch(&mut lines, format!("Net: {:+}/t", net), &[], true);
+        Ok(Some(lines.join("\n")))
+    }
+
+    fn push_tree_branch(lines: &mut Vec<String>, label: String, children: &[String], is_last: bool) {
+        let (branch, indent) = if is_last { ("└─", "   ") } else { ("├─", "│  ") };
+        lines.push(format!("{} {}", branch, label));
+        for (idx, child) in children.iter().enumerate() {
+            let twig = if idx + 1 == children.len() { "└─" } else { "├─" };
+            lines.push(format!("{}{} {}", indent, twig, child));
+        }
+    }
+
+    fn get_synergies_listing(&self, planet_name: &str) -> Result<Option<String>, GameCoreError> {
+        let planet = self.players
+            .get(&self.current_player)
+            .and_then(|player| player.get_planet(planet_name))
+            .ok_or_else(|| CommandError::new(&format!("Planet '{}' not found.", planet_name)))?;
+
+        let mut lines = vec![format!("Synergies on {}:", planet.get_name())];
+        for synergy in &self.synergies_config.synergies {
+            let modifiers: Vec<String> = synergy.modifiers.iter().map(|modifier| modifier.to_string()).collect();
+            let missing: Vec<String> = synergy.get_missing(planet).iter().map(|building| building.to_string()).collect();
+            let status = if missing.is_empty() {
+                "active".to_string()
+            } else {
+                format!("needs {} at level {}", missing.join(" and "), synergy.min_level)
+            };
+            lines.push(format!("  {}: {} - {}", synergy.name, modifiers.join(", "), status));
+        }
+        Ok(Some(lines.join("\n")))
+    }
+
+    // Two planets side by side, one row per building either of them has and per resource
+    // Net production of every planet and the empire as a table, each value followed by its change
+    // since the last turn
+    fn get_production_listing(&self, sort_by: Option<Resource>) -> Result<String, GameCoreError> {
+        let player = self.players.get(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+        let columns = Resource::all();
+
+        let mut planets: Vec<(String, HashMap<Resource, i64>, &Planet)> = player.get_planet_names()
+            .into_iter()
+            .filter_map(|planet_name| {
+                let planet = player.get_planet(&planet_name)?;
+                Some((planet_name, planet.get_net_production(&self.economy_config), planet))
+            })
+            .collect();
+        match sort_by {
+            // Highest production first
+            Some(resource) => planets.sort_by(|(first_name, first, _), (second_name, second, _)| {
+                let get = |production: &HashMap<Resource, i64>| production.get(&resource).copied().unwrap_or(0);
+                get(second).cmp(&get(first)).then_with(|| first_name.cmp(second_name))
+            }),
+            None => planets.sort_by(|(first_name, _, _), (second_name, _, _)| first_name.cmp(second_name)),
+        }
+
+        let get_cell = |production: &HashMap<Resource, i64>, last_turn: &HashMap<Resource, i64>, resource: &Resource| {
+            let value = production.get(resource).copied().unwrap_or(0);
+            match last_turn.get(resource).map(|last| value - last) {
+                Some(delta) if delta != 0 => format!("{:+} ({:+})", value, delta),
+                _ => format!("{:+}", value),
+            }
+        };
+        let mut rows = vec![
+            std::iter::once("Planet".to_string()).chain(columns.iter().map(Resource::to_string)).collect::<Vec<String>>(),
+        ];
+        let (mut total, mut total_last_turn) = (HashMap::new(), HashMap::new());
+        for (planet_name, production, planet) in &planets {
+            let last_turn = planet.get_last_turn_production();
+            rows.push(std::iter::once(planet_name.clone())
+                .chain(columns.iter().map(|resource| get_cell(production, last_turn, resource)))
+                .collect());
+            for resource in columns {
+                *total.entry(*resource).or_insert(0) += production.get(resource).copied().unwrap_or(0);
+                if let Some(last) = last_turn.get(resource) {
+                    *total_last_turn.entry(*resource).or_insert(0) += last;
+                }
+            }
+        }
+        rows.push(std::iter::once("Empire".to_string())
+            .chain(columns.iter().map(|resource| get_cell(&total, &total_last_turn, resource)))
+            .collect());
+
+        let widths: Vec<usize> = (0..=columns.len())
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+            .collect();
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell)).collect();
+                format!("  {}", cells.join("  ")).trim_end().to_string()
+            })
+            .collect();
+        let sorted_by = sort_by.map_or("planet".to_string(), |resource| resource.to_string().to_lowercase());
+        Ok(format!(
+            "Net production per turn, sorted by {}. Changes since the last turn in brackets.\n{}",
+            sorted_by,
+            lines.join("\n"),
+        ))
+    }
+
+    fn get_comparison_listing(&self, first: &str, second: &str) -> Result<Option<String>, GameCoreError> {
+        let get_status = |planet_name: &str| {
+            self.get_current_player_planet_status(planet_name)
+                .ok_or_else(|| CommandError::new(&format!("Planet '{}' not found.", planet_name)))
+        };
+        let (first, second) = (get_status(first)?, get_status(second)?);
+        if first.planet_name == second.planet_name {
+            return Err(CommandError::new("Pick two different planets to compare.").into());
+        }
+
+        let mut rows = vec![(String::new(), first.planet_name.clone(), second.planet_name.clone())];
+        let mut building_names: Vec<&str> = first.buildings
+            .iter()
+            .chain(&second.buildings)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        building_names.sort();
+        building_names.dedup();
+        let get_level = |status: &PlanetStatus, name: &str| {
+            status.buildings.iter().find(|(building, _)| building == name).map_or(0, |(_, level)| *level)
+        };
+        for name in building_names {
+            let (first_level, second_level) = (get_level(&first, name), get_level(&second, name));
+            rows.push((name.to_string(), format!("Lvl {}", first_level), format!("Lvl {}", second_level)));
+        }
+
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            let get_production = |status: &PlanetStatus| {
+                let (current, capacity) = status.storage.get(&resource).copied().unwrap_or_default();
+                format!("+{}/t {}/{}", status.production.get(&resource).copied().unwrap_or_default(), current, capacity)
+            };
+            rows.push((resource.to_string(), get_production(&first), get_production(&second)));
+        }
+
+        let get_queue = |status: &PlanetStatus| match status.construction_queue.first() {
+            Some(construction) => format!(
+                "{} Lvl {} ({}t){}",
+                construction.building,
+                construction.level,
+                construction.remaining_turns,
+                match status.construction_queue.len() {
+                    1 => String::new(),
+                    count => format!(" +{}", count - 1),
+                },
+            ),
+            None => "empty".to_string(),
+        };
+        rows.push(("Queue".to_string(), get_queue(&first), get_queue(&second)));
+
+        let label_width = rows.iter().map(|(label, _, _)| label.len()).max().unwrap_or(0);
+        let first_width = rows.iter().map(|(_, value, _)| value.len()).max().unwrap_or(0);
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|(label, first_value, second_value)| {
+                format!("  {:<label_width$}  {:<first_width$}  {}", label, first_value, second_value)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        Ok(Some(format!("{} vs {}:\n{}", first.planet_name, second.planet_name, lines.join("\n"))))
+    }
+
+    // TODO: Add colonies founded and wars declared to the timeline once they exist
+    fn get_timeline_listing(&self) -> String {
+        let mut lines = vec!["Timeline:".to_string()];
+        for milestone in self.stats.get_timeline() {
+            lines.push(format!("  Turn {}: {}", milestone.turn, milestone.text));
+        }
+        lines.push(format!("Played for {}.", format_playtime(self.stats.get_playtime_secs())));
+        if let Some((turn, secs)) = self.stats.get_longest_turn() {
+            lines.push(format!("Longest turn: turn {} took {}.", turn, format_playtime(secs)));
+        }
+        lines.join("\n")
+    }
+
+    // Artifacts are unique, none can be granted while any player holds it
+    // TODO: Let diplomacy deals bundle artifacts, techs, maps and lump sums of resources, valued by the
+    // AI to decide whether it accepts, once there are AI players and diplomacy
+    fn grant_artifact(&mut self, artifact_id: &str) -> Result<String, GameCoreError> {
+        let artifact = self.artifacts_config.find(artifact_id).ok_or_else(|| {
+            CommandError::new(&format!("Artifact '{}' not found.", artifact_id))
+        })?;
+        if self.players.values().any(|player| player.has_artifact(&artifact.id)) {
+            return Err(CommandError::new(&format!("{} is already held.", artifact.name)).into());
+        }
+
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+        player.add_artifact(artifact);
+        self.stats.add_milestone(self.turn.get_turn_number(), &format!("{} found {}.", self.current_player, artifact.name));
+        Ok(format!("Artifact found: {}. {}", artifact.name, artifact.description))
+    }
+
+    // Takes effect on the turn being played, its production included
+    fn set_speed(&mut self, speed: &str) -> Result<Option<String>, GameCoreError> {
+        let ticks = self.economy_config.speed.get_ticks(speed).ok_or_else(|| {
+            let speeds: Vec<&str> = self.economy_config.speed.get_speeds().into_iter().map(|(name, _)| name).collect();
+            CommandError::new(&format!("Unknown speed '{}'. Choose one of: {}.", speed, speeds.join(", ")))
+        })?;
+
+        for player in self.players.values_mut() {
+            player.set_production_ticks(ticks);
+        }
+        self.speed = speed.to_string();
+        Ok(Some(format!("Game speed set to {}, every turn brings {} production tick(s).", speed, ticks)))
+    }
+
+    fn get_speed_listing(&self) -> String {
+        let speeds: Vec<String> = self.economy_config.speed
+            .get_speeds()
+            .into_iter()
+            .map(|(name, ticks)| format!("{} ({} tick(s))", name, ticks))
+            .collect();
+        format!("Game speed: {}. Speeds: {}.", self.speed, speeds.join(", "))
+    }
+
+    fn toggle_edict(&mut self, edict_id: &str) -> Result<Option<String>, GameCoreError> {
+        let turn = self.turn.get_turn_number();
+        let edict = self.edicts_config.find(edict_id).ok_or_else(|| {
+            CommandError::new(&format!("Edict '{}' not found. Type 'edict' to list them.", edict_id))
+        })?;
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+
+        let cooldown = player.get_edicts().get_cooldown(&edict.id, turn);
+        if cooldown > 0 {
+            return Err(CommandError::new(&format!(
+                "{} was changed recently, it can be changed again in {} turn(s).", edict.name, cooldown
+            )).into());
+        }
+
+        // Repealing an edict is free, only enacting it costs
+        let cost = &edict.activation_cost;
+        if !player.get_edicts().is_active(&edict.id) {
+            if player.get_credits() < cost.credits {
+                return Err(CommandError::new(&format!(
+                    "Not enough credits, enacting {} costs {} but the treasury holds {}.",
+                    edict.name, cost.credits, player.get_credits(),
+                )).into());
+            }
+            if !player.spend_resource(Resource::Energy, cost.energy)? {
+                return Err(CommandError::new(&format!(
+                    "Not enough energy, enacting {} costs {} but your planets have {} free.",
+                    edict.name, cost.energy, player.get_free_resource_total(Resource::Energy),
+                )).into());
+            }
+            player.spend_credits(cost.credits);
+        }
+
+        if player.toggle_edict(edict, turn) {
+            Ok(Some(format!("{} enacted for {}.", edict.name, cost)))
+        } else {
+            Ok(Some(format!("{} repealed.", edict.name)))
+        }
+    }
+
+    fn designate_planet(&mut self, planet_name: &str, specialization_id: &str) -> Result<String, GameCoreError> {
+        let turn = self.turn.get_turn_number();
+        let specialization = match specialization_id.to_lowercase().as_str() {
+            "none" => None,
+            _ => Some(self.specializations_config.find(specialization_id).ok_or_else(|| {
+                CommandError::new(&format!(
+                    "Specialization '{}' not found. Type 'designate' to list them.", specialization_id
+                ))
+            })?),
+        };
+        let player = self.players.get_mut(&self.current_player).ok_or_else(|| {
+            GameCoreError::CommandError(CommandError::new("Current player not found."))
+        })?;
+        let planet = player.get_planet(planet_name).ok_or_else(|| {
+            CommandError::new(&format!("Planet '{}' not found.", planet_name))
+        })?;
+
+        let current = planet.get_specialization();
+        if current.map(|current| &current.id) == specialization.map(|specialization| &specialization.id) {
+            return Err(CommandError::new(&match current {
+                Some(current) => format!("{} is a {} already.", planet_name, current.name),
+                None => format!("{} has no designation.", planet_name),
+            }).into());
+        }
+        let cooldown = planet.get_respec_cooldown(turn);
+        if cooldown > 0 {
+            return Err(CommandError::new(&format!(
+                "{} was designated recently, it can be designated again in {} turn(s).", planet_name, cooldown
+            )).into());
+        }
+
+        let available_from_turn = turn + self.specializations_config.respec_cooldown_turns;
+        player.designate_planet(planet_name, specialization, available_from_turn)?;
+        Ok(match specialization {
+            Some(specialization) => format!(
+                "{} is now a {}, it can be designated again in {} turn(s).",
+                planet_name, specialization.name, self.specializations_config.respec_cooldown_turns,
+            ),
+            None => format!("{} is no longer designated.", planet_name),
+        })
+    }
+
+    // A building type, optionally followed by the number of one of several, e.g. MineralMine#2
+    fn find_building_instance(building_name: &str) -> Result<BuildingInstanceId, GameCoreError> {
+        let (type_name, number) = match building_name.split_once('#') {
+            Some((type_name, number)) => (type_name, number.parse::<u8>().ok().filter(|number| *number >= 1)),
+            None => (building_name, Some(1)),
+        };
+        let type_id = BuildingTypeId::all()
+            .iter()
+            .find(|&&id| id.get_name().eq_ignore_ascii_case(type_name))
+            .cloned();
+
+        match (type_id, number) {
+            (Some(type_id), Some(number)) => Ok(BuildingInstanceId { type_id, number }),
+            _ => Err(GameCoreError::CommandError(
+                CommandError::new(&format!("Building '{}' not recognized.", building_name))
+            )),
+        }
+    }
+
+    fn execute_macro_action(&mut self, action: &MacroAction) -> Result<Option<String>, GameCoreError> {
+        match action {
+            MacroAction::Record(macro_name) => {
+                self.macro_recorder.start(macro_name)?;
+                Ok(Some(format!("Recording macro '{}'. Use 'macro stop' to finish.", macro_name)))
+            }
+            MacroAction::Stop => {
+                let (macro_name, commands_count) = self.macro_recorder.stop()?;
+                Ok(Some(format!("Macro '{}' recorded with {} commands.", macro_name, commands_count)))
+            }
+            MacroAction::List => {
+                let macro_names = self.macro_recorder.get_macro_names();
+                if macro_names.is_empty() {
+                    Ok(Some("No macros recorded.".to_string()))
+                } else {
+                    Ok(Some(format!("Macros: {}", macro_names.join(", "))))
+                }
+            }
+            MacroAction::Play { macro_name, planet } => {
+                let recorded_macro = self.macro_recorder.get_macro(macro_name).ok_or_else(|| {
+                    CommandError::new(&format!("Macro '{}' not found.", macro_name))
+                })?;
+                let commands = recorded_macro.get_commands_for(planet.as_deref());
+                let target = planet.as_deref().or(recorded_macro.get_planet()).map(str::to_string);
+
+                for (step, command) in commands.iter().enumerate() {
+                    self.execute_command(command).map_err(|err| {
+                        CommandError::new(&format!(
+                            "Macro '{}' stopped at step {} ('{}'): {}", macro_name, step + 1, command, err
+                        ))
+                    })?;
+                }
+
+                Ok(Some(match target {
+                    Some(target) => format!("Macro '{}' played on {} ({} commands).", macro_name, target, commands.len()),
+                    None => format!("Macro '{}' played ({} commands).", macro_name, commands.len()),
+                }))
+            }
+        }
+    }
+
+    fn get_script_snapshot(&self) -> GameSnapshot {
+        let mut planets = HashMap::new();
+        if let Some(player) = self.players.get(&self.current_player) {
+            for planet_name in player.get_planet_names() {
+                let Some(planet) = player.get_planet(&planet_name) else {
+                    continue;
+                };
+
+                let building_levels = BuildingTypeId::all()
+                    .iter()
+                    .map(|id| (id.get_name().to_string(), planet.get_building_level(*id)))
+                    .collect();
+                let storage = Resource::all()
+                    .iter()
+                    .map(|resource| (
+                        *resource,
+                        (planet.get_resource_amount(*resource), planet.get_resource_capacity(*resource)),
+                    ))
+                    .collect();
+
+                planets.insert(planet_name, PlanetSnapshot {
+                    building_levels,
+                    production: planet.get_production_rates(),
+                    storage,
+                    population: planet.get_population(),
+                    tax_rate: planet.get_tax_rate(),
+                    morale: planet.get_morale(),
+                });
+            }
+        }
+
+        GameSnapshot {
+            turn: self.turn.get_turn_number(),
+            player_name: self.current_player.clone(),
+            credits: self.get_current_player_credits(),
+            artifacts: self.players.get(&self.current_player).map_or(vec![], |player| {
+                player.get_artifacts().iter().map(|artifact| artifact.id.clone()).collect()
+            }),
+            planets,
+        }
+    }
+
+    // Scripts don't see events caused by commands they issued themselves, otherwise a hook
+    // running e.g. `endturn` would trigger itself forever
+    fn dispatch_events(&mut self) {
+        let events = std::mem::take(&mut self.pending_events);
+        for event in &events {
+            self.record_milestones(event);
+            self.stats.record(event);
+            self.advance_tutorial(event);
+            self.update_missions(event);
+            if let GameEvent::TurnEnded { .. } = event {
+                let hints = self.hint_engine.evaluate(&self.get_script_snapshot(), &self.buildings_config);
+                self.notifications.extend(hints);
+            }
+        }
+        if self.script_depth > 0 {
+            return;
+        }
+
+        for event in events {
+            let (output, errors) = self.script_engine.handle_event(&event, self.get_script_snapshot());
+            self.notifications.extend(errors.iter().map(|err| err.to_string()));
+            if let Err(err) = self.run_script_output(output) {
+                self.notifications.push(err.to_string());
+            }
+        }
+    }
+
+    // Must run before the event is recorded in the statistics, which tell what is built for the first time
+    fn record_milestones(&mut self, event: &GameEvent) {
+        let GameEvent::BuildingCompleted { planet, building, level } = event else {
+            return;
+        };
+
+        let turn = self.turn.get_turn_number();
+        let config = self.buildings_config.buildings.get(building.get_name());
+        // Every stage of a wonder is a milestone
+        // TODO: Announce the stages galaxy-wide, including those of rivals, and count a finished
+        // wonder towards the Wonder victory once there are rivals and victory conditions
+        if let Some(stage) = config.and_then(|config| config.get_stage_name(*level)) {
+            self.stats.add_milestone(turn, &format!(
+                "{} completed stage {} of the {}, {}, on {}.", self.current_player, level, building, stage, planet
+            ));
+            return;
+        }
+        if !self.stats.get_buildings_built().contains_key(building) {
+            self.stats.add_milestone(turn, &format!("First {} completed on {}.", building, planet));
+        }
+        if config.is_some_and(|config| config.get_max_level() == *level)
+        {
+            self.stats.add_milestone(turn, &format!("{} on {} reached its highest level {}.", building, planet, level));
+        }
+    }
+
+    fn advance_tutorial(&mut self, event: &GameEvent) {
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
+
+        if tutorial.handle_event(event) && tutorial.is_finished() {
+            self.tutorial = None;
+            self.notifications.push("Tutorial complete! You are ready to lead your colony.".to_string());
+        }
+    }
+
+    fn update_missions(&mut self, event: &GameEvent) {
+        for (mission, state) in self.missions.handle_event(event, &self.get_script_snapshot()) {
+            if state == MissionState::Failed {
+                self.notifications.push(format!("Mission failed: {}.", mission.title));
+                continue;
+            }
+
+            self.notifications.push(format!("Mission complete: {}!", mission.title));
+            self.stats.add_milestone(self.turn.get_turn_number(), &format!("Mission {} completed.", mission.title));
+            let reward = &mission.reward;
+            if !reward.credits.is_zero()
+                && let Some(player) = self.players.get_mut(&self.current_player)
+            {
+                player.add_credits(reward.credits);
+                self.notifications.push(format!("Received {} credits.", reward.credits));
+            }
+            if let Some(artifact_id) = &reward.artifact {
+                let message = self.grant_artifact(artifact_id).unwrap_or_else(|err| err.to_string());
+                self.notifications.push(message);
+            }
+        }
+    }
+
+    fn run_script_output(&mut self, output: ScriptOutput) -> Result<(), GameCoreError> {
+        self.notifications.extend(output.logs);
+        for artifact_id in &output.artifacts {
+            let message = self.grant_artifact(artifact_id).unwrap_or_else(|err| err.to_string());
+            self.notifications.push(message);
+        }
+        if output.commands.is_empty() {
+            return Ok(());
+        }
+
+        if self.script_depth >= MAX_SCRIPT_DEPTH {
+            return Err(GameCoreError::CommandError(CommandError::new("Script commands are nested too deeply.")));
+        }
+
+        self.script_depth += 1;
+        let mut result = Ok(());
+        for command in &output.commands {
+            match self.execute_command(command) {
+                Ok(Some(message)) => self.notifications.push(message),
+                Ok(None) => {}
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+        self.script_depth -= 1;
+
+        result
+    }
+}
\ No newline at end of file
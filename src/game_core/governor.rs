@@ -0,0 +1,184 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierScope, ModifierSource, ModifierStat};
+use super::planet::PlanetId;
+use super::{Resource, ResourceAmount};
+
+#[derive(Debug)]
+pub enum GovernorsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateGovernor(String),
+}
+
+impl fmt::Display for GovernorsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GovernorsConfigError::Io(e) => write!(f, "Failed to read governors file: {}", e),
+            GovernorsConfigError::Toml(e) => write!(f, "Failed to parse governors file (TOML): {}", e),
+            GovernorsConfigError::DuplicateGovernor(id) => write!(f, "Governor '{}' is defined twice", id),
+        }
+    }
+}
+
+impl Error for GovernorsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GovernorsConfigError::Io(e) => Some(e),
+            GovernorsConfigError::Toml(e) => Some(e),
+            GovernorsConfigError::DuplicateGovernor(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for GovernorsConfigError {
+    fn from(err: io::Error) -> Self {
+        GovernorsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for GovernorsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        GovernorsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// Effect a governor has on the planet they govern, at level 1. Higher levels multiply it.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum GovernorTrait {
+    Production { resource: Resource, percent: u32 },
+    ConstructionTime { turns: u32 },
+    Morale { points: u32 },
+}
+
+impl GovernorTrait {
+    fn get_stat_and_magnitude(&self) -> (ModifierStat, i32) {
+        match self {
+            GovernorTrait::Production { resource, percent } => (ModifierStat::Production(Some(*resource)), *percent as i32),
+            GovernorTrait::ConstructionTime { turns } => (ModifierStat::ConstructionTime, -(*turns as i32)),
+            GovernorTrait::Morale { points } => (ModifierStat::Morale, *points as i32),
+        }
+    }
+}
+
+impl fmt::Display for GovernorTrait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GovernorTrait::Production { resource, percent } => write!(f, "+{}% {} production", percent, resource),
+            GovernorTrait::ConstructionTime { turns } => write!(f, "-{} turn(s) construction time", turns),
+            GovernorTrait::Morale { points } => write!(f, "+{} morale", points),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GovernorConfig {
+    pub id: String,
+    pub name: String,
+    pub hire_cost: ResourceAmount,
+    pub traits: Vec<GovernorTrait>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GovernorsConfig {
+    // Turns a governor has to govern a planet to gain a level
+    pub turns_per_level: u32,
+    pub max_level: u32,
+    pub governors: Vec<GovernorConfig>,
+}
+
+impl GovernorsConfig {
+    pub fn load(path: &Path) -> Result<Self, GovernorsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: GovernorsConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for governor in &config.governors {
+            let id = governor.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(GovernorsConfigError::DuplicateGovernor(governor.id.clone()));
+            }
+            seen.push(id);
+        }
+
+        Ok(config)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&GovernorConfig> {
+        self.governors.iter().find(|governor| governor.id.eq_ignore_ascii_case(id))
+    }
+}
+
+// A hired governor, keeping a copy of their configuration like buildings do
+#[derive(Debug, Clone)]
+pub struct Governor {
+    config: GovernorConfig,
+    experience: u32,
+    turns_per_level: u32,
+    max_level: u32,
+}
+
+impl Governor {
+    pub fn new(config: &GovernorConfig, governors_config: &GovernorsConfig) -> Self {
+        Governor {
+            config: config.clone(),
+            experience: 0,
+            turns_per_level: governors_config.turns_per_level.max(1),
+            max_level: governors_config.max_level.max(1),
+        }
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.config.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn get_experience(&self) -> u32 {
+        self.experience
+    }
+
+    pub fn get_level(&self) -> u32 {
+        (1 + self.experience / self.turns_per_level).min(self.max_level)
+    }
+
+    // TODO: Ships and troops should rank up the same way from battles won, with combat bonuses per
+    // rank shown in the fleet details, once units and combat exist
+    // Returns true when the governor reached a new level
+    pub fn gain_experience(&mut self) -> bool {
+        let level = self.get_level();
+        self.experience += 1;
+        self.get_level() > level
+    }
+
+    pub fn get_modifier_source(&self) -> ModifierSource {
+        ModifierSource::Governor(self.config.name.clone())
+    }
+
+    // The traits at the current level, applied to the governed planet
+    pub fn get_modifiers(&self, planet: &PlanetId) -> Vec<Modifier> {
+        self.config.traits
+            .iter()
+            .map(|governor_trait| {
+                let (stat, magnitude) = governor_trait.get_stat_and_magnitude();
+                Modifier::new(
+                    self.get_modifier_source(),
+                    ModifierScope::Planet(planet.clone()),
+                    stat,
+                    magnitude * self.get_level() as i32,
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::building::BuildingConfig;
+use super::scripting::GameSnapshot;
+use super::{BuildingsConfig, Resource};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HintSettings {
+    pub enabled: bool,
+    // Turns before the same hint may be shown again
+    pub cooldown_turns: u32,
+    pub max_per_turn: usize,
+    // Storage fill in percent from which it counts as nearly full
+    pub storage_threshold: u32,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        HintSettings {
+            enabled: true,
+            cooldown_turns: 5,
+            max_per_turn: 1,
+            storage_threshold: 90,
+        }
+    }
+}
+
+// Watches the game state at the end of each turn and gives advice
+#[derive(Debug, Default)]
+pub struct HintEngine {
+    settings: HintSettings,
+    // Turn each hint was last shown on, keyed by what it is about
+    last_shown: HashMap<String, u32>,
+}
+
+impl HintEngine {
+    pub fn new(settings: HintSettings) -> Self {
+        HintEngine {
+            settings,
+            last_shown: HashMap::new(),
+        }
+    }
+
+    pub fn evaluate(&mut self, snapshot: &GameSnapshot, buildings_config: &BuildingsConfig) -> Vec<String> {
+        if !self.settings.enabled {
+            return Vec::new();
+        }
+
+        let mut candidates = Vec::new();
+        let mut planet_names: Vec<&String> = snapshot.planets.keys().collect();
+        planet_names.sort();
+
+        for planet_name in planet_names {
+            let planet = &snapshot.planets[planet_name];
+            for resource in Resource::all() {
+                let (amount, capacity) = planet.storage.get(resource).cloned().unwrap_or_default();
+                if !capacity.is_zero() && amount.get_percent_of(capacity) >= self.settings.storage_threshold {
+                    let storage_name = Self::find_building_name(buildings_config, |config| {
+                        config.get_storage().is_some_and(|storage| storage.resource == *resource)
+                    });
+                    candidates.push((
+                        format!("storage:{}:{}", planet_name, resource),
+                        format!(
+                            "Your {} on {} is nearly full ({}/{}), consider upgrading it or building something.",
+                            storage_name.unwrap_or("storage"), planet_name, amount, capacity
+                        ),
+                    ));
+                }
+
+                // Only resources stored on the planet, credits come from taxes as well
+                if !capacity.is_zero() && planet.production.get(resource).is_none_or(|rate| rate.is_zero()) {
+                    let producer_name = Self::find_building_name(buildings_config, |config| {
+                        config.get_production().is_some_and(|production| production.resource == *resource)
+                    });
+                    if let Some(producer_name) = producer_name {
+                        candidates.push((
+                            format!("production:{}:{}", planet_name, resource),
+                            format!("{} produces no {}, build a {} to start.", planet_name, resource, producer_name),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let turn = snapshot.turn;
+        let mut hints = Vec::new();
+        for (key, hint) in candidates {
+            if hints.len() >= self.settings.max_per_turn {
+                break;
+            }
+
+            let on_cooldown = self.last_shown.get(&key).is_some_and(|shown| {
+                turn < shown + self.settings.cooldown_turns
+            });
+            if !on_cooldown {
+                self.last_shown.insert(key, turn);
+                hints.push(format!("Hint: {}", hint));
+            }
+        }
+        hints
+    }
+
+    fn find_building_name(
+        buildings_config: &BuildingsConfig,
+        predicate: impl Fn(&BuildingConfig) -> bool,
+    ) -> Option<&str> {
+        let mut matching: Vec<_> = buildings_config.buildings
+            .iter()
+            .filter(|(_, config)| predicate(config))
+            .collect();
+        // Several buildings may qualify, prefer the same one every time
+        matching.sort_by_key(|(id, _)| *id);
+        matching.first().map(|(_, config)| config.get_name())
+    }
+}
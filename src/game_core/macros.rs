@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use super::CommandError;
+
+const PLANET_PLACEHOLDER: &str = "{planet}";
+
+#[derive(Debug, Clone, Default)]
+pub struct Macro {
+    commands: Vec<String>,
+    // Planet the macro was recorded on, its name is replaced by a placeholder in commands
+    planet: Option<String>,
+}
+
+impl Macro {
+    pub fn get_commands_count(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn get_planet(&self) -> Option<&str> {
+        self.planet.as_deref()
+    }
+
+    // Commands with the recorded planet substituted by the given one
+    pub fn get_commands_for(&self, planet: Option<&str>) -> Vec<String> {
+        let planet = planet.or(self.planet.as_deref()).unwrap_or_default();
+        self.commands
+            .iter()
+            .map(|command| command.replace(PLANET_PLACEHOLDER, planet))
+            .collect()
+    }
+
+    fn record(&mut self, command: &str, planet_names: &[String]) {
+        // Sub-locations are addressed as <planet>/<id>, only the planet part is replaced
+        let get_planet_part = |token: &str| token.split('/').next().unwrap_or_default().to_string();
+
+        if self.planet.is_none() {
+            self.planet = command
+                .split_whitespace()
+                .map(get_planet_part)
+                .find(|token| planet_names.iter().any(|name| name == token));
+        }
+
+        let template = command
+            .split_whitespace()
+            .map(|token| match &self.planet {
+                Some(planet) if *planet == get_planet_part(token) => {
+                    token.replacen(planet.as_str(), PLANET_PLACEHOLDER, 1)
+                }
+                _ => token.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        self.commands.push(template);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MacroRecorder {
+    macros: HashMap<String, Macro>,
+    recording: Option<(String, Macro)>,
+}
+
+impl MacroRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn start(&mut self, macro_name: &str) -> Result<(), CommandError> {
+        if let Some((recording_name, _)) = &self.recording {
+            return Err(CommandError::new(&format!(
+                "Already recording macro '{}'. Use 'macro stop' first.", recording_name
+            )));
+        }
+
+        self.recording = Some((macro_name.to_string(), Macro::default()));
+        Ok(())
+    }
+
+    pub fn stop(&mut self) -> Result<(String, usize), CommandError> {
+        let (macro_name, recorded) = self.recording.take().ok_or_else(|| {
+            CommandError::new("No macro is being recorded.")
+        })?;
+
+        let commands_count = recorded.get_commands_count();
+        self.macros.insert(macro_name.clone(), recorded);
+        Ok((macro_name, commands_count))
+    }
+
+    pub fn record(&mut self, command: &str, planet_names: &[String]) {
+        if let Some((_, recording)) = &mut self.recording {
+            recording.record(command, planet_names);
+        }
+    }
+
+    pub fn get_macro(&self, macro_name: &str) -> Option<&Macro> {
+        self.macros.get(macro_name)
+    }
+
+    pub fn get_macro_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.macros.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
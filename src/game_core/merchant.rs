@@ -0,0 +1,198 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::{Resource, ResourceAmount};
+
+#[derive(Debug)]
+pub enum MerchantsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateOffer(String),
+    InvalidSchedule(String),
+}
+
+impl fmt::Display for MerchantsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerchantsConfigError::Io(e) => write!(f, "Failed to read merchants file: {}", e),
+            MerchantsConfigError::Toml(e) => write!(f, "Failed to parse merchants file (TOML): {}", e),
+            MerchantsConfigError::DuplicateOffer(id) => write!(f, "Merchant offer '{}' is defined twice", id),
+            MerchantsConfigError::InvalidSchedule(reason) => write!(f, "Invalid merchant schedule: {}", reason),
+        }
+    }
+}
+
+impl Error for MerchantsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MerchantsConfigError::Io(e) => Some(e),
+            MerchantsConfigError::Toml(e) => Some(e),
+            MerchantsConfigError::DuplicateOffer(_) => None,
+            MerchantsConfigError::InvalidSchedule(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for MerchantsConfigError {
+    fn from(err: io::Error) -> Self {
+        MerchantsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for MerchantsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        MerchantsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// What the player gives and gets when accepting an offer
+// TODO: Offer ships at a discount once fleets exist
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MerchantDeal {
+    // The merchant buys resources from the player's planets
+    SellResource { resource: Resource, amount: ResourceAmount, credits: ResourceAmount },
+    // The merchant sells resources, delivered to the player's storage
+    BuyResource { resource: Resource, amount: ResourceAmount, credits: ResourceAmount },
+}
+
+impl fmt::Display for MerchantDeal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerchantDeal::SellResource { resource, amount, credits } => {
+                write!(f, "Sell {} {} for {} credits", amount, resource, credits)
+            }
+            MerchantDeal::BuyResource { resource, amount, credits } => {
+                write!(f, "Buy {} {} for {} credits", amount, resource, credits)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MerchantOfferConfig {
+    pub id: String,
+    pub merchant: String,
+    pub pitch: String,
+    pub deal: MerchantDeal,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MerchantsConfig {
+    pub first_visit_turn: u32,
+    // Turns between two visits
+    pub visit_interval: u32,
+    // Turns a merchant waits for an answer before leaving
+    pub offer_turns: u32,
+    pub offers: Vec<MerchantOfferConfig>,
+}
+
+impl MerchantsConfig {
+    pub fn load(path: &Path) -> Result<Self, MerchantsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: MerchantsConfig = toml::from_str(&toml_content)?;
+
+        if config.visit_interval == 0 || config.offer_turns == 0 {
+            return Err(MerchantsConfigError::InvalidSchedule(
+                "visit_interval and offer_turns must be at least 1".to_string()
+            ));
+        }
+        if config.offer_turns > config.visit_interval {
+            return Err(MerchantsConfigError::InvalidSchedule(
+                "offer_turns must not be longer than visit_interval".to_string()
+            ));
+        }
+
+        let mut seen = Vec::new();
+        for offer in &config.offers {
+            let id = offer.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(MerchantsConfigError::DuplicateOffer(offer.id.clone()));
+            }
+            seen.push(id);
+        }
+
+        Ok(config)
+    }
+
+    fn is_visit_turn(&self, turn: u32, visit_interval: u32) -> bool {
+        turn >= self.first_visit_turn && (turn - self.first_visit_turn).is_multiple_of(visit_interval)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MerchantVisit {
+    pub offer: MerchantOfferConfig,
+    // Last turn the offer can be accepted on
+    pub last_turn: u32,
+}
+
+// Changes to the merchant in orbit when a new turn starts
+#[derive(Debug, Default)]
+pub struct MerchantUpdate {
+    pub departed: Option<MerchantOfferConfig>,
+    pub arrived: Option<MerchantVisit>,
+}
+
+// TODO: Minor factions holding a few planets of their own could trade like merchants, hand out
+// missions and grant bonuses for gifts, and be conquered, once there is a map with planets owned
+// by others and combat
+#[derive(Debug)]
+pub struct Merchants {
+    config: MerchantsConfig,
+    visit: Option<MerchantVisit>,
+    visit_count: usize,
+    // Turns between visits compared to the configured ones, in percent
+    interval_percent: u32,
+}
+
+impl Merchants {
+    pub fn new(config: MerchantsConfig) -> Self {
+        Merchants { config, visit: None, visit_count: 0, interval_percent: 100 }
+    }
+
+    pub fn set_interval_percent(&mut self, percent: u32) {
+        self.interval_percent = percent;
+    }
+
+    // Never shorter than a visit lasts, so merchants don't arrive while one is still waiting
+    fn get_visit_interval(&self) -> u32 {
+        (self.config.visit_interval * self.interval_percent / 100).max(self.config.offer_turns)
+    }
+
+    pub fn get_visit(&self) -> Option<&MerchantVisit> {
+        self.visit.as_ref()
+    }
+
+    // Ends the current visit, whether the offer was accepted or declined
+    pub fn take_visit(&mut self) -> Option<MerchantVisit> {
+        self.visit.take()
+    }
+
+    // Lets unanswered merchants leave and new ones arrive at the start of a turn
+    pub fn start_turn(&mut self, turn: u32) -> MerchantUpdate {
+        let mut update = MerchantUpdate::default();
+        if self.visit.as_ref().is_some_and(|visit| turn > visit.last_turn) {
+            update.departed = self.visit.take().map(|visit| visit.offer);
+        }
+
+        if self.config.offers.is_empty() || !self.config.is_visit_turn(turn, self.get_visit_interval()) {
+            return update;
+        }
+
+        // Merchants come by in the order they are configured
+        let offer = self.config.offers[self.visit_count % self.config.offers.len()].clone();
+        self.visit_count += 1;
+        let visit = MerchantVisit { offer, last_turn: turn + self.config.offer_turns - 1 };
+        self.visit = Some(visit.clone());
+        update.arrived = Some(visit);
+        update
+    }
+}
@@ -0,0 +1,241 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::event::GameEvent;
+use super::scripting::GameSnapshot;
+use super::{BuildingTypeId, Resource, ResourceAmount};
+
+#[derive(Debug)]
+pub enum MissionsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateMission(String),
+    UnknownBuilding(String),
+}
+
+impl fmt::Display for MissionsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MissionsConfigError::Io(e) => write!(f, "Failed to read missions file: {}", e),
+            MissionsConfigError::Toml(e) => write!(f, "Failed to parse missions file (TOML): {}", e),
+            MissionsConfigError::DuplicateMission(id) => write!(f, "Mission '{}' is defined twice", id),
+            MissionsConfigError::UnknownBuilding(name) => write!(f, "Mission refers to unknown building '{}'", name),
+        }
+    }
+}
+
+impl Error for MissionsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MissionsConfigError::Io(e) => Some(e),
+            MissionsConfigError::Toml(e) => Some(e),
+            MissionsConfigError::DuplicateMission(_) => None,
+            MissionsConfigError::UnknownBuilding(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for MissionsConfigError {
+    fn from(err: io::Error) -> Self {
+        MissionsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for MissionsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        MissionsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MissionGoal {
+    // Stored on all planets together
+    StoreResource { resource: Resource, amount: u32 },
+    // On any planet
+    BuildingLevel { building: String, level: u8 },
+    // On all planets together
+    Population { amount: u32 },
+    Credits { amount: u32 },
+    // Building levels completed while the mission is active
+    CompleteBuildings { count: u32 },
+}
+
+impl MissionGoal {
+    pub fn get_target(&self) -> u32 {
+        match self {
+            MissionGoal::StoreResource { amount, .. } => *amount,
+            MissionGoal::BuildingLevel { level, .. } => *level as u32,
+            MissionGoal::Population { amount } => *amount,
+            MissionGoal::Credits { amount } => *amount,
+            MissionGoal::CompleteBuildings { count } => *count,
+        }
+    }
+
+    // Progress towards the target after the event, given the progress before it
+    fn get_progress(&self, progress: u32, event: &GameEvent, snapshot: &GameSnapshot) -> u32 {
+        let planets = snapshot.planets.values();
+        match self {
+            MissionGoal::StoreResource { resource, .. } => planets
+                .map(|planet| planet.storage.get(resource).map_or(ResourceAmount::ZERO, |(amount, _)| *amount))
+                .sum::<ResourceAmount>()
+                .get(),
+            MissionGoal::BuildingLevel { building, .. } => planets
+                .map(|planet| planet.building_levels.get(building).cloned().unwrap_or(0) as u32)
+                .max()
+                .unwrap_or(0),
+            MissionGoal::Population { .. } => planets.map(|planet| planet.population).sum(),
+            MissionGoal::Credits { .. } => snapshot.credits.get(),
+            MissionGoal::CompleteBuildings { .. } => match event {
+                GameEvent::BuildingCompleted { .. } => progress + 1,
+                _ => progress,
+            },
+        }
+    }
+}
+
+impl fmt::Display for MissionGoal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MissionGoal::StoreResource { resource, amount } => write!(f, "Store {} {}", amount, resource),
+            MissionGoal::BuildingLevel { building, level } => write!(f, "Raise a {} to level {}", building, level),
+            MissionGoal::Population { amount } => write!(f, "Reach a population of {}", amount),
+            MissionGoal::Credits { amount } => write!(f, "Hold {} credits", amount),
+            MissionGoal::CompleteBuildings { count } => write!(f, "Complete {} building levels", count),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MissionReward {
+    #[serde(default)]
+    pub credits: ResourceAmount,
+    // Id of an artifact, lost if another empire already holds it
+    #[serde(default)]
+    pub artifact: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MissionConfig {
+    pub id: String,
+    pub title: String,
+    pub goal: MissionGoal,
+    // The mission fails if the goal is not met by the end of this turn
+    #[serde(default)]
+    pub deadline_turn: Option<u32>,
+    #[serde(default)]
+    pub reward: MissionReward,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MissionsConfig {
+    pub missions: Vec<MissionConfig>,
+}
+
+impl MissionsConfig {
+    pub fn load(path: &Path) -> Result<Self, MissionsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: MissionsConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for mission in &config.missions {
+            let id = mission.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(MissionsConfigError::DuplicateMission(mission.id.clone()));
+            }
+            seen.push(id);
+
+            if let MissionGoal::BuildingLevel { building, .. } = &mission.goal
+                && !BuildingTypeId::all().iter().any(|id| id.get_name() == building)
+            {
+                return Err(MissionsConfigError::UnknownBuilding(building.clone()));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissionState {
+    Active,
+    Completed,
+    Failed,
+}
+
+// What the UI shows of a mission
+#[derive(Debug, Clone)]
+pub struct MissionStatus {
+    pub title: String,
+    pub goal: String,
+    pub progress: u32,
+    pub target: u32,
+    pub deadline_turn: Option<u32>,
+    pub state: MissionState,
+}
+
+#[derive(Debug, Clone)]
+struct Mission {
+    config: MissionConfig,
+    progress: u32,
+    state: MissionState,
+}
+
+#[derive(Debug, Default)]
+pub struct MissionTracker {
+    missions: Vec<Mission>,
+}
+
+impl MissionTracker {
+    pub fn new(config: &MissionsConfig) -> Self {
+        MissionTracker {
+            missions: config.missions
+                .iter()
+                .map(|mission| Mission { config: mission.clone(), progress: 0, state: MissionState::Active })
+                .collect(),
+        }
+    }
+
+    // Updates the active missions, returns the ones the event completed or failed
+    pub fn handle_event(&mut self, event: &GameEvent, snapshot: &GameSnapshot) -> Vec<(MissionConfig, MissionState)> {
+        let mut finished = Vec::new();
+        for mission in self.missions.iter_mut().filter(|mission| mission.state == MissionState::Active) {
+            let goal = &mission.config.goal;
+            mission.progress = goal.get_progress(mission.progress, event, snapshot).min(goal.get_target());
+
+            if mission.progress >= goal.get_target() {
+                mission.state = MissionState::Completed;
+            } else if let (GameEvent::TurnEnded { turn }, Some(deadline_turn)) = (event, mission.config.deadline_turn)
+                && *turn >= deadline_turn
+            {
+                mission.state = MissionState::Failed;
+            } else {
+                continue;
+            }
+            finished.push((mission.config.clone(), mission.state));
+        }
+        finished
+    }
+
+    pub fn get_statuses(&self) -> Vec<MissionStatus> {
+        self.missions
+            .iter()
+            .map(|mission| MissionStatus {
+                title: mission.config.title.clone(),
+                goal: mission.config.goal.to_string(),
+                progress: mission.progress,
+                target: mission.config.goal.get_target(),
+                deadline_turn: mission.config.deadline_turn,
+                state: mission.state,
+            })
+            .collect()
+    }
+}
@@ -1,4 +1,7 @@
+#[allow(clippy::module_inception)]
 pub mod game_core;
+// Public so bots can be written against it without reaching into the rest of the core
+pub mod bot;
 
 mod command;
 mod resource;
@@ -6,25 +9,67 @@ mod turn;
 mod building;
 mod planet;
 mod player;
+mod macros;
+mod event;
+mod scripting;
+mod stats;
+mod profile;
+mod tutorial;
+mod hints;
+mod colonypedia;
+mod economy;
+mod governor;
+mod edict;
+mod modifier;
+mod sub_location;
+mod artifact;
+mod synergy;
+mod specialization;
+mod mission;
+mod merchant;
+mod disaster;
+mod choice;
+mod alert;
+mod challenge;
+mod cycle;
+mod ai;
+mod names;
+mod rules;
+mod replay;
+mod rng;
+mod simulation;
+// Randomized command sequences checked against world invariants, run by `cargo test`
+#[cfg(test)]
+mod fuzz;
 
 // =================================================================================================
 
-pub use game_core::{GameCore, GameCoreError};
-pub use command::CommandLoadError;
+pub use game_core::{GameCore, GameCoreError, GameDataPaths};
+pub use command::{CommandLoadError, InputCheck};
 pub use planet::PlanetStatus;
-pub use resource::Resource;
+pub use player::PlayerColor;
+pub use resource::{Resource, ResourceAmount};
+pub use profile::{Profile, ProfileError};
+pub use tutorial::TutorialStatus;
+pub use mission::{MissionState, MissionStatus};
+pub use choice::{Choice, ChoiceOption};
+pub use alert::{Alert, AlertKind};
+pub use replay::{Replay, ReplayEntry, ReplayError};
+pub use bot::{BotRunner, EconomyBot};
+pub use ai::AiConfig;
+pub use simulation::BalanceSimulation;
+pub use building::BuildingsConfig;
+pub use hints::HintSettings;
+pub use stats::{format_playtime, TurnRecord};
+pub use challenge::get_todays_date;
+pub use colonypedia::{Colonypedia, PediaCategory, PediaEntry};
 
-use command::{CommandRegistry, CommandExecution, CommandError};
+use command::{CommandRegistry, CommandDefinition, CommandError};
 use turn::Turn;
 use building::{
-    BuildingsConfig,
-    BuildingConfig,
     BuildingsConfigError,
     BuildingTypeId,
     BuildingType,
-    BuildingBase,
-    Productor,
-    Storage,
     BuildingError
 };
 use planet::{Planet, PlanetError};
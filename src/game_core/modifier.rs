@@ -0,0 +1,219 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use super::planet::PlanetId;
+use super::Resource;
+
+// Where a modifier comes from, a source replaces all of its modifiers at once
+// TODO: Research and planet types feed in here once they exist
+// TODO: War exhaustion from losses and blockades should lower morale through here once wars exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifierSource {
+    Governor(String),
+    Edict(String),
+    Artifact(String),
+    Morale(PlanetId),
+    Event(String),
+    Synergy { name: String, planet: PlanetId },
+    Specialization { name: String, planet: PlanetId },
+}
+
+impl fmt::Display for ModifierSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierSource::Governor(name) => write!(f, "Governor {}", name),
+            ModifierSource::Edict(name) => write!(f, "Edict {}", name),
+            ModifierSource::Artifact(name) => write!(f, "{}", name),
+            ModifierSource::Morale(planet) => write!(f, "Morale on {}", planet),
+            ModifierSource::Event(name) => write!(f, "{}", name),
+            ModifierSource::Synergy { name, planet } => write!(f, "{} on {}", name, planet),
+            ModifierSource::Specialization { name, planet } => write!(f, "{} designation of {}", name, planet),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifierScope {
+    Empire,
+    Planet(PlanetId),
+}
+
+impl ModifierScope {
+    pub fn applies_to(&self, planet: &str) -> bool {
+        match self {
+            ModifierScope::Empire => true,
+            ModifierScope::Planet(id) => id.as_str() == planet,
+        }
+    }
+}
+
+// What a modifier changes. Production and upkeep magnitudes are percentages, morale is in points,
+// construction time in turns and building slots in slots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierStat {
+    // Applies to every resource when no resource is given
+    Production(Option<Resource>),
+    Morale,
+    Upkeep,
+    ConstructionTime,
+    BuildingSlots,
+}
+
+impl ModifierStat {
+    fn matches(&self, stat: ModifierStat) -> bool {
+        match (self, stat) {
+            (ModifierStat::Production(None), ModifierStat::Production(_)) => true,
+            _ => *self == stat,
+        }
+    }
+}
+
+impl fmt::Display for ModifierStat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierStat::Production(Some(resource)) => write!(f, "{} production", resource),
+            ModifierStat::Production(None) => write!(f, "production"),
+            ModifierStat::Morale => write!(f, "morale"),
+            ModifierStat::Upkeep => write!(f, "upkeep"),
+            ModifierStat::ConstructionTime => write!(f, "construction time"),
+            ModifierStat::BuildingSlots => write!(f, "building slots"),
+        }
+    }
+}
+
+// A modifier as written in data files, e.g. `{ type = "production", resource = "Gas", percent = 10 }`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ModifierConfig {
+    // Applies to every resource when no resource is given
+    Production { resource: Option<Resource>, percent: i32 },
+    Morale { points: i32 },
+    Upkeep { percent: i32 },
+    BuildingSlots { slots: i32 },
+}
+
+impl ModifierConfig {
+    pub fn to_modifier(&self, source: ModifierSource, scope: ModifierScope) -> Modifier {
+        let (stat, magnitude) = match self {
+            ModifierConfig::Production { resource, percent } => (ModifierStat::Production(*resource), *percent),
+            ModifierConfig::Morale { points } => (ModifierStat::Morale, *points),
+            ModifierConfig::Upkeep { percent } => (ModifierStat::Upkeep, *percent),
+            ModifierConfig::BuildingSlots { slots } => (ModifierStat::BuildingSlots, *slots),
+        };
+        Modifier::new(source, scope, stat, magnitude)
+    }
+}
+
+impl fmt::Display for ModifierConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierConfig::Production { resource: Some(resource), percent } => {
+                write!(f, "{:+}% {} production", percent, resource)
+            }
+            ModifierConfig::Production { resource: None, percent } => write!(f, "{:+}% production", percent),
+            ModifierConfig::Morale { points } => write!(f, "{:+} morale", points),
+            ModifierConfig::Upkeep { percent } => write!(f, "{:+}% upkeep", percent),
+            ModifierConfig::BuildingSlots { slots } => write!(f, "{:+} building slots", slots),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modifier {
+    pub source: ModifierSource,
+    pub scope: ModifierScope,
+    pub stat: ModifierStat,
+    pub magnitude: i32,
+    // Turns left, lasts as long as its source when not set
+    pub duration: Option<u32>,
+}
+
+impl Modifier {
+    pub fn new(source: ModifierSource, scope: ModifierScope, stat: ModifierStat, magnitude: i32) -> Self {
+        Modifier { source, scope, stat, magnitude, duration: None }
+    }
+
+    pub fn with_duration(mut self, turns: u32) -> Self {
+        self.duration = Some(turns);
+        self
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.stat {
+            ModifierStat::Production(_) | ModifierStat::Upkeep => write!(f, "{:+}% {}", self.magnitude, self.stat)?,
+            ModifierStat::Morale | ModifierStat::BuildingSlots => write!(f, "{:+} {}", self.magnitude, self.stat)?,
+            ModifierStat::ConstructionTime => write!(f, "{:+} turn(s) {}", self.magnitude, self.stat)?,
+        }
+        write!(f, " ({}", self.source)?;
+        if let Some(turns) = self.duration {
+            write!(f, ", {} turn(s) left", turns)?;
+        }
+        write!(f, ")")
+    }
+}
+
+// Every modifier affecting an empire, the only place production, morale, upkeep, construction
+// time and building slots are adjusted
+#[derive(Debug, Clone, Default)]
+pub struct ModifierSet {
+    modifiers: Vec<Modifier>,
+}
+
+impl ModifierSet {
+    // Replaces everything the source contributed so far
+    pub fn set_source(&mut self, source: ModifierSource, modifiers: Vec<Modifier>) {
+        self.remove_source(&source);
+        self.modifiers.extend(modifiers);
+    }
+
+    pub fn has_source(&self, source: &ModifierSource) -> bool {
+        self.modifiers.iter().any(|modifier| modifier.source == *source)
+    }
+
+    pub fn remove_source(&mut self, source: &ModifierSource) {
+        self.modifiers.retain(|modifier| modifier.source != *source);
+    }
+
+    // Counts down the modifiers with a duration and drops the expired ones
+    pub fn tick(&mut self) {
+        for modifier in self.modifiers.iter_mut() {
+            if let Some(turns) = &mut modifier.duration {
+                *turns = turns.saturating_sub(1);
+            }
+        }
+        self.modifiers.retain(|modifier| modifier.duration != Some(0));
+    }
+
+    // The modifiers that apply to a single planet
+    pub fn for_planet(&self, planet: &str) -> ModifierSet {
+        ModifierSet {
+            modifiers: self.modifiers
+                .iter()
+                .filter(|modifier| modifier.scope.applies_to(planet))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    // The modifiers that change the stat, resource-wide production ones included
+    pub fn get_matching(&self, stat: ModifierStat) -> Vec<&Modifier> {
+        self.modifiers.iter().filter(|modifier| modifier.stat.matches(stat)).collect()
+    }
+
+    pub fn resolve(&self, stat: ModifierStat) -> i32 {
+        self.get_matching(stat).iter().map(|modifier| modifier.magnitude).sum()
+    }
+
+    // Applies the summed percentage of the stat to a base value, never going below zero
+    pub fn apply_percent(&self, base: u32, stat: ModifierStat) -> u32 {
+        base * self.get_percent(stat) / 100
+    }
+
+    // What the modifiers of the stat make of 100%, never below nothing
+    pub fn get_percent(&self, stat: ModifierStat) -> u32 {
+        (100 + self.resolve(stat)).max(0) as u32
+    }
+}
@@ -0,0 +1,153 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::rng::Rng;
+
+#[derive(Debug)]
+pub enum NamesConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    EmptyTable(&'static str),
+}
+
+impl fmt::Display for NamesConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamesConfigError::Io(e) => write!(f, "Failed to read names file: {}", e),
+            NamesConfigError::Toml(e) => write!(f, "Failed to parse names file (TOML): {}", e),
+            NamesConfigError::EmptyTable(table) => write!(f, "Name table '{}' needs first and last syllables", table),
+        }
+    }
+}
+
+impl Error for NamesConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NamesConfigError::Io(e) => Some(e),
+            NamesConfigError::Toml(e) => Some(e),
+            NamesConfigError::EmptyTable(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for NamesConfigError {
+    fn from(err: io::Error) -> Self {
+        NamesConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for NamesConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        NamesConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// Syllables a name is put together from, one of each list in order. The middle one is left out
+// half of the time.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SyllableTable {
+    pub first: Vec<String>,
+    #[serde(default)]
+    pub middle: Vec<String>,
+    pub last: Vec<String>,
+}
+
+// TODO: Add a table for NPC factions once there are any besides the merchants
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NamesConfig {
+    pub planets: SyllableTable,
+    pub players: SyllableTable,
+}
+
+impl NamesConfig {
+    pub fn load(path: &Path) -> Result<Self, NamesConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: NamesConfig = toml::from_str(&toml_content)?;
+
+        for (table, syllables) in [("planets", &config.planets), ("players", &config.players)] {
+            if syllables.first.is_empty() || syllables.last.is_empty() {
+                return Err(NamesConfigError::EmptyTable(table));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NameKind {
+    Planet,
+    Player,
+}
+
+// Names for everything a game creates, never the same one twice within a game. Seeded, so a
+// replay that records the seed gets the same names again.
+#[derive(Debug, Clone)]
+pub struct NameGenerator {
+    config: NamesConfig,
+    rng: Rng,
+    // Lowercase, generated or given otherwise
+    used: Vec<String>,
+}
+
+impl NameGenerator {
+    // Attempts before falling back to numbering a name that was taken
+    const MAX_ATTEMPTS: usize = 100;
+
+    pub fn new(config: NamesConfig, seed: u64) -> Self {
+        NameGenerator { config, rng: Rng::new(seed), used: Vec::new() }
+    }
+
+    // Keeps a name from being generated, e.g. one the player chose
+    pub fn reserve(&mut self, name: &str) {
+        let name = name.to_lowercase();
+        if !self.used.contains(&name) {
+            self.used.push(name);
+        }
+    }
+
+    pub fn generate(&mut self, kind: NameKind) -> String {
+        let mut name = self.compose(kind);
+        for _ in 1..Self::MAX_ATTEMPTS {
+            if !self.is_used(&name) {
+                break;
+            }
+            name = self.compose(kind);
+        }
+
+        let mut unique = name.clone();
+        let mut number = 2;
+        while self.is_used(&unique) {
+            unique = format!("{}{}", name, number);
+            number += 1;
+        }
+        self.reserve(&unique);
+        unique
+    }
+
+    fn is_used(&self, name: &str) -> bool {
+        self.used.iter().any(|used| used.eq_ignore_ascii_case(name))
+    }
+
+    fn compose(&mut self, kind: NameKind) -> String {
+        let table = match kind {
+            NameKind::Planet => &self.config.planets,
+            NameKind::Player => &self.config.players,
+        };
+        let mut name = self.rng.pick(&table.first).cloned().unwrap_or_default();
+        if self.rng.below(2) == 0
+            && let Some(middle) = self.rng.pick(&table.middle)
+        {
+            name.push_str(middle);
+        }
+        name.push_str(self.rng.pick(&table.last).map_or("", String::as_str));
+        name
+    }
+}
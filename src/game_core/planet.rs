@@ -1,10 +1,17 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::{HashMap, VecDeque}, fmt};
+use std::borrow::Borrow;
+use std::rc::Rc;
 use std::error::Error;
 
 use super::building::building::Building;
-use super::building::{building_config, BuildingConfig, BuildingsConfig, BuildingsConfigError, Storage};
+use super::building::{BuildingConfig, BuildingInstanceId, BuildingsConfig, BuildingsConfigError, Storage};
+use super::economy::{ConstructionPayment, EconomyConfig};
+use super::governor::Governor;
+use super::modifier::{ModifierSet, ModifierStat};
+use super::specialization::SpecializationConfig;
+use super::sub_location::{SubLocation, SubLocationStatus, SubLocationsConfig};
 use super::{
-    resource, BuildingError, BuildingType, BuildingTypeId, Resource
+    BuildingError, BuildingType, BuildingTypeId, Resource, ResourceAmount
 };
 
 #[derive(Debug)]
@@ -12,6 +19,18 @@ pub enum PlanetError {
     BuildingNotBuilt,
     InsufficientResources,
     IncorrectBuildingType,
+    TaxRateTooHigh { max: u8 },
+    NotUnderConstruction(BuildingInstanceId),
+    PlanetNotFound(String),
+    SubLocationNotFound(String),
+    NotBuildableAt { building: BuildingTypeId, location: String },
+    // The number the next building of the type gets
+    InstanceNotFound { building: BuildingInstanceId, next: u8 },
+    TooManyInstances { building: BuildingTypeId, max: u8 },
+    NoFreeBuildingSlot { used: u32, total: u32 },
+    // Positions count from 1
+    QueuePositionNotFound { position: usize, len: usize },
+    ConstructionOrder { building: BuildingInstanceId, level: u8 },
     BuildingError(BuildingError),
     BuildingsConfigError(BuildingsConfigError),
 }
@@ -22,6 +41,28 @@ impl fmt::Display for PlanetError {
             PlanetError::BuildingNotBuilt => write!(f, "Building not built"),
             PlanetError::InsufficientResources => write!(f, "Insufficient resources"),
             PlanetError::IncorrectBuildingType => write!(f, "Incorrect building type"),
+            PlanetError::TaxRateTooHigh { max } => write!(f, "Tax rate cannot exceed {}%", max),
+            PlanetError::NotUnderConstruction(building) => write!(f, "{} is not under construction", building),
+            PlanetError::PlanetNotFound(name) => write!(f, "Planet '{}' not found", name),
+            PlanetError::SubLocationNotFound(name) => write!(f, "Sub-location '{}' not found", name),
+            PlanetError::NotBuildableAt { building, location } => {
+                write!(f, "{} cannot be built on {}", building, location)
+            }
+            PlanetError::InstanceNotFound { building, next } => {
+                write!(f, "There is no {}, the next {} gets number {}", building, building.type_id, next)
+            }
+            PlanetError::TooManyInstances { building, max } => {
+                write!(f, "A planet can have at most {} {} building(s)", max, building)
+            }
+            PlanetError::NoFreeBuildingSlot { used, total } => {
+                write!(f, "No free building slot, {} of {} are in use", used, total)
+            }
+            PlanetError::QueuePositionNotFound { position, len } => {
+                write!(f, "There is no construction at position {}, the queue holds {}", position, len)
+            }
+            PlanetError::ConstructionOrder { building, level } => {
+                write!(f, "{} level {} cannot be built before level {}", building, level, level - 1)
+            }
             PlanetError::BuildingError(err) => write!(f, "Building error: {}", err),
             PlanetError::BuildingsConfigError(err) => write!(f, "Building config error: {}", err),
         }
@@ -34,6 +75,16 @@ impl Error for PlanetError {
             PlanetError::BuildingNotBuilt => None,
             PlanetError::InsufficientResources => None,
             PlanetError::IncorrectBuildingType => None,
+            PlanetError::TaxRateTooHigh { .. } => None,
+            PlanetError::NotUnderConstruction(_) => None,
+            PlanetError::PlanetNotFound(_) => None,
+            PlanetError::SubLocationNotFound(_) => None,
+            PlanetError::NotBuildableAt { .. } => None,
+            PlanetError::InstanceNotFound { .. } => None,
+            PlanetError::TooManyInstances { .. } => None,
+            PlanetError::NoFreeBuildingSlot { .. } => None,
+            PlanetError::QueuePositionNotFound { .. } => None,
+            PlanetError::ConstructionOrder { .. } => None,
             PlanetError::BuildingError(err) => Some(err),
             PlanetError::BuildingsConfigError(err) => Some(err),
         }
@@ -51,45 +102,556 @@ impl From<BuildingError> for PlanetError {
 #[derive(Debug, Clone, Default)]
 pub struct PlanetStatus {
     pub planet_name: String,
+    pub size: String,
+    // Used and total
+    pub building_slots: (u32, u32),
     pub buildings: Vec<(String, u8)>,
-    pub production: HashMap<Resource, u32>,
-    pub storage: HashMap<Resource, (u32, u32)>,
+    pub production: HashMap<Resource, ResourceAmount>,
+    pub storage: HashMap<Resource, (ResourceAmount, ResourceAmount)>,
+    // Part of the storage set aside for queued constructions, resources with nothing reserved are
+    // left out
+    pub reserved: HashMap<Resource, ResourceAmount>,
+    // Turns until each resource's storage is full, resources that never fill are left out
+    pub turns_until_full: HashMap<Resource, u32>,
+    pub population: u32,
+    pub housing: u32,
+    pub tax_rate: u8,
+    pub morale: u8,
+    pub on_strike: bool,
+    // Name and level
+    pub governor: Option<(String, u32)>,
+    // Name of the designation and the resource the AI favors for it
+    pub specialization: Option<(String, Option<Resource>)>,
+    pub construction_queue: Vec<Construction>,
+    pub sub_locations: Vec<SubLocationStatus>,
     pub planet_count: usize,
 }
 
+// A building level being constructed, only the first one in a planet's queue makes progress
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Construction {
+    pub building: BuildingInstanceId,
+    pub level: u8,
+    pub remaining_turns: u32,
+    // Id of the moon or asteroid belt it is built on, the planet's surface if not set
+    pub sub_location: Option<String>,
+    // What the level costs, kept aside from the stored resources from the time it is reserved
+    // and taken from them once it is done
+    pub cost: [(Resource, ResourceAmount); 3],
+    pub is_reserved: bool,
+}
+
+// How a planet's production of a resource comes together
+#[derive(Debug, Clone, Default)]
+pub struct ProductionBreakdown {
+    // Name, level, where it stands and base rate of every producing building
+    pub buildings: Vec<(String, u8, Option<String>, ResourceAmount)>,
+    // Source and percent of every modifier applied to the base
+    pub modifiers: Vec<(String, i32)>,
+    pub on_strike: bool,
+    pub total: ResourceAmount,
+}
+
+impl ProductionBreakdown {
+    pub fn get_base(&self) -> ResourceAmount {
+        self.buildings.iter().map(|(_, _, _, rate)| *rate).sum()
+    }
+
+    pub fn get_percent(&self) -> i32 {
+        100 + self.modifiers.iter().map(|(_, percent)| percent).sum::<i32>()
+    }
+}
+
+// What the next level of a building would cost and when the planet can pay for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstructionForecast {
+    pub level: u8,
+    pub cost: [(Resource, ResourceAmount); 3],
+    // Turns of production until the stored resources cover the cost, 0 if they do already and
+    // none if they never will at the current production
+    pub affordable_in: Option<u32>,
+}
+
+impl Construction {
+    // Where the construction takes place, in the form commands address it
+    pub fn get_location(&self, planet_name: &str) -> String {
+        match &self.sub_location {
+            Some(sub_location) => format!("{}/{}", planet_name, sub_location),
+            None => planet_name.to_string(),
+        }
+    }
+}
+
+// Identity of a planet, cheap to clone and compare so it can be handed around in reports and
+// queues. Looks up like the planet's name in maps keyed by it.
+// TODO: Keep a display name apart from the id once planets can be renamed
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlanetId(Rc<str>);
+
+impl PlanetId {
+    pub fn new(name: &str) -> Self {
+        PlanetId(Rc::from(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for PlanetId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PlanetId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Planet {
-    name: String,
-    buildings: HashMap<BuildingTypeId, BuildingType>,
+    id: PlanetId,
+    // One of the sizes in the economy config, it decides how many building slots the planet has
+    size: String,
+    buildings: HashMap<BuildingInstanceId, BuildingType>,
+    population: u32,
+    // Percent of the population's wealth collected as credits every turn
+    tax_rate: u8,
+    // Percentage, low morale cuts production and can lead to strikes
+    morale: u8,
+    on_strike: bool,
+    governor: Option<Governor>,
+    specialization: Option<SpecializationConfig>,
+    // Amounts of resources the empire's logistics keep stored here, see Player::balance_stockpiles
+    stockpile_targets: HashMap<Resource, ResourceAmount>,
+    // Turn from which the planet can be designated again
+    respec_available_from_turn: u32,
+    // The owner's modifiers that apply to this planet, kept up to date by the owner
+    modifiers: ModifierSet,
+    construction_queue: VecDeque<Construction>,
+    sub_locations: Vec<SubLocation>,
+    // Production ticks every turn brings at the game speed, kept up to date by the owner
+    production_ticks: u32,
+    // What storage buildings cost compared to their configuration in percent, kept up to date by
+    // the owner
+    storage_cost_percent: u32,
+    // Net production of the last ended turn, the production overview shows the changes since
+    last_turn_production: HashMap<Resource, i64>,
 }
 
 impl Planet {
-    pub fn new(name: &str, buildings_config: &BuildingsConfig) -> Result<Self, PlanetError> {
+    pub fn new(
+        name: &str,
+        buildings_config: &BuildingsConfig,
+        economy_config: &EconomyConfig,
+        sub_locations_config: &SubLocationsConfig,
+    ) -> Result<Self, PlanetError> {
         let buildings = Self::init_all_buildings_zero(buildings_config)?;
+        let sub_locations = sub_locations_config.starting
+            .iter()
+            .filter_map(|kind| sub_locations_config.find(kind))
+            .map(|kind| SubLocation::new(kind, buildings_config))
+            .collect::<Result<Vec<SubLocation>, BuildingsConfigError>>()
+            .map_err(PlanetError::BuildingsConfigError)?;
 
         Ok(
             Self {
-                name: name.to_string(),
-                buildings
+                id: PlanetId::new(name),
+                size: economy_config.building_slots.starting_size.clone(),
+                buildings,
+                population: economy_config.population.initial,
+                tax_rate: economy_config.taxes.default_rate,
+                morale: economy_config.morale.initial,
+                on_strike: false,
+                governor: None,
+                specialization: None,
+                stockpile_targets: HashMap::new(),
+                respec_available_from_turn: 0,
+                modifiers: ModifierSet::default(),
+                construction_queue: VecDeque::new(),
+                sub_locations,
+                production_ticks: 1,
+                storage_cost_percent: 100,
+                last_turn_production: HashMap::new(),
             }
         )
     }
 
+    // Splits a location such as "Planet1/Moon" into the planet name and the sub-location id
+    pub fn split_location(location: &str) -> (&str, Option<&str>) {
+        match location.split_once('/') {
+            Some((planet_name, sub_location)) => (planet_name, Some(sub_location)),
+            None => (location, None),
+        }
+    }
+
+    pub fn get_population(&self) -> u32 {
+        self.population
+    }
+
+    pub fn get_housing(&self, economy_config: &EconomyConfig) -> u32 {
+        let population_config = &economy_config.population;
+        population_config.base_housing
+            + self.get_building_level(BuildingTypeId::CommandCenter) as u32
+                * population_config.housing_per_command_center_level
+    }
+
+    pub fn get_tax_rate(&self) -> u8 {
+        self.tax_rate
+    }
+
+    pub fn set_tax_rate(&mut self, tax_rate: u8, economy_config: &EconomyConfig) -> Result<(), PlanetError> {
+        if tax_rate > economy_config.taxes.max_rate {
+            return Err(PlanetError::TaxRateTooHigh { max: economy_config.taxes.max_rate });
+        }
+
+        self.tax_rate = tax_rate;
+        Ok(())
+    }
+
+    pub fn get_tax_income(&self, economy_config: &EconomyConfig) -> ResourceAmount {
+        economy_config.get_tax_income(self.population, self.tax_rate).into()
+    }
+
+    // What the planet adds to its owner's stock per turn. Credits are its taxes and trade less its
+    // upkeep, so they can fall below zero.
+    pub fn get_net_production(&self, economy_config: &EconomyConfig) -> HashMap<Resource, i64> {
+        let mut production: HashMap<Resource, i64> = self.get_production_rates()
+            .into_iter()
+            .map(|(resource, rate)| (resource, i64::from(rate)))
+            .collect();
+        *production.entry(Resource::Credits).or_default() +=
+            i64::from(self.get_tax_income(economy_config)) - i64::from(self.get_upkeep(economy_config));
+        production
+    }
+
+    // Empty until the first turn ended
+    pub fn get_last_turn_production(&self) -> &HashMap<Resource, i64> {
+        &self.last_turn_production
+    }
+
+    pub fn record_production(&mut self, economy_config: &EconomyConfig) {
+        self.last_turn_production = self.get_net_production(economy_config);
+    }
+
+    // Built levels of every building, sub-locations included
+    pub fn get_building_levels(&self) -> u32 {
+        self.buildings.values().map(|building| building.get_level() as u32).sum::<u32>()
+            + self.sub_locations.iter().map(|sub_location| sub_location.get_building_levels()).sum::<u32>()
+    }
+
+    pub fn get_upkeep(&self, economy_config: &EconomyConfig) -> ResourceAmount {
+        self.modifiers.apply_percent(
+            self.get_building_levels() * economy_config.upkeep.credits_per_building_level,
+            ModifierStat::Upkeep,
+        ).into()
+    }
+
+    // Population grows slower the higher the taxes are, but never beyond the available housing
+    pub fn grow_population(&mut self, economy_config: &EconomyConfig) {
+        let housing = self.get_housing(economy_config);
+        let growth_percent = economy_config.get_growth_percent(self.tax_rate);
+        let growth = if growth_percent > 0 {
+            (self.population * growth_percent / 100).max(1)
+        } else {
+            0
+        };
+        self.population = (self.population + growth).min(housing.max(self.population));
+    }
+
+    pub fn get_morale(&self) -> u8 {
+        self.morale
+    }
+
+    pub fn is_on_strike(&self) -> bool {
+        self.on_strike
+    }
+
+    // Morale the planet drifts towards under the current taxes, crowding and buildings
+    pub fn get_morale_target(&self, economy_config: &EconomyConfig) -> u8 {
+        let morale_config = &economy_config.morale;
+        let mut target = morale_config.base as i32;
+
+        let taxed_above = self.tax_rate.saturating_sub(morale_config.tolerated_tax_rate) as i32;
+        target -= taxed_above * morale_config.tax_penalty_per_percent as i32;
+
+        let housing = self.get_housing(economy_config).max(1);
+        if self.population * 100 / housing > morale_config.crowded_percent as u32 {
+            target -= morale_config.overcrowding_penalty as i32;
+        }
+
+        target += self.buildings.values().map(|building| building.get_morale_bonus() as i32).sum::<i32>();
+        target += self.modifiers.resolve(ModifierStat::Morale);
+        target.clamp(0, 100) as u8
+    }
+
+    // Moves morale a step towards its target, a planet whose morale hits the strike threshold
+    // produces nothing during the next turn
+    pub fn update_morale(&mut self, economy_config: &EconomyConfig) {
+        let step = economy_config.morale.change_per_turn as i32;
+        let target = self.get_morale_target(economy_config) as i32;
+        let morale = self.morale as i32;
+        self.morale = (morale + (target - morale).clamp(-step, step)) as u8;
+        self.on_strike = self.morale <= economy_config.morale.strike_threshold;
+    }
+
+    // Production percent lost to unrest, shrinking with morale below the unrest threshold
+    pub fn get_unrest_penalty(&self, economy_config: &EconomyConfig) -> i32 {
+        let unrest_threshold = economy_config.morale.unrest_threshold as i32;
+        let morale = self.morale as i32;
+        if morale < unrest_threshold {
+            morale * 100 / unrest_threshold - 100
+        } else {
+            0
+        }
+    }
+
+    pub fn get_governor(&self) -> Option<&Governor> {
+        self.governor.as_ref()
+    }
+
+    pub fn get_mut_governor(&mut self) -> Option<&mut Governor> {
+        self.governor.as_mut()
+    }
+
+    pub fn get_stockpile_target(&self, resource: Resource) -> Option<ResourceAmount> {
+        self.stockpile_targets.get(&resource).copied()
+    }
+
+    // No target when none is given
+    pub fn set_stockpile_target(&mut self, resource: Resource, target: Option<ResourceAmount>) {
+        match target {
+            Some(target) => self.stockpile_targets.insert(resource, target),
+            None => self.stockpile_targets.remove(&resource),
+        };
+    }
+
+    // What is stored beyond the target and the reserved resources, or missing to reach the target
+    // as far as the storage holds it. Nothing without a target.
+    pub fn get_stockpile_balance(&self, resource: Resource) -> (ResourceAmount, ResourceAmount) {
+        let Some(target) = self.get_stockpile_target(resource) else {
+            return (ResourceAmount::ZERO, ResourceAmount::ZERO);
+        };
+        let amount = self.get_resource_amount(resource);
+        let keep = target.max(self.get_reserved_amount(resource));
+        (amount - keep, target.min(self.get_resource_capacity(resource)) - amount)
+    }
+
+    pub fn get_specialization(&self) -> Option<&SpecializationConfig> {
+        self.specialization.as_ref()
+    }
+
+    // Turns left until the planet can be designated again, 0 if it can be now
+    pub fn get_respec_cooldown(&self, turn: u32) -> u32 {
+        self.respec_available_from_turn.saturating_sub(turn)
+    }
+
+    // Returns the designation that was replaced, if any
+    pub fn set_specialization(
+        &mut self,
+        specialization: Option<SpecializationConfig>,
+        available_from_turn: u32,
+    ) -> Option<SpecializationConfig> {
+        self.respec_available_from_turn = available_from_turn;
+        std::mem::replace(&mut self.specialization, specialization)
+    }
+
+    // Returns the governor who was replaced, if any
+    pub fn set_governor(&mut self, governor: Option<Governor>) -> Option<Governor> {
+        std::mem::replace(&mut self.governor, governor)
+    }
+
+    pub fn get_modifiers(&self) -> &ModifierSet {
+        &self.modifiers
+    }
+
+    pub fn set_modifiers(&mut self, modifiers: ModifierSet) {
+        self.modifiers = modifiers;
+    }
+
+    pub fn set_production_ticks(&mut self, ticks: u32) {
+        self.production_ticks = ticks.max(1);
+    }
+
+    pub fn set_storage_cost_percent(&mut self, percent: u32) {
+        self.storage_cost_percent = percent;
+    }
+
+    pub fn get_id(&self) -> &PlanetId {
+        &self.id
+    }
+
     pub fn get_name(&self) -> &str {
-        &self.name 
+        self.id.as_str()
+    }
+
+    // Highest level of any building of the type on the surface
+    pub fn get_building_level(&self, building_id: BuildingTypeId) -> u8 {
+        self.buildings
+            .iter()
+            .filter(|(instance, _)| instance.type_id == building_id)
+            .map(|(_, building)| building.get_level())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Surface buildings that have a level built or queued
+    pub fn get_used_building_slots(&self) -> u32 {
+        self.buildings
+            .iter()
+            .filter(|(instance, building)| building.get_level() > 0 || self.is_queued_on_surface(**instance))
+            .count() as u32
+    }
+
+    pub fn get_building_slots(&self, economy_config: &EconomyConfig) -> u32 {
+        let slots_config = &economy_config.building_slots;
+        let expansion_level = BuildingTypeId::all()
+            .iter()
+            .find(|id| id.get_name() == slots_config.expansion_building)
+            .map_or(0, |&id| self.get_building_level(id));
+        let slots = slots_config.get_base_slots(&self.size)
+            + expansion_level as u32 * slots_config.slots_per_expansion_level;
+        (slots as i32 + self.modifiers.resolve(ModifierStat::BuildingSlots)).max(0) as u32
+    }
+
+    fn is_queued_on_surface(&self, building: BuildingInstanceId) -> bool {
+        self.construction_queue
+            .iter()
+            .any(|construction| Self::is_construction_at(construction, building, None))
+    }
+
+    fn get_next_instance_number(&self, building_id: BuildingTypeId) -> u8 {
+        self.buildings.keys().filter(|instance| instance.type_id == building_id).count() as u8 + 1
+    }
+
+    // Buildings of a sub-location that have a level built or queued
+    fn get_used_sub_location_slots(&self, sub_location: &SubLocation) -> u32 {
+        let mut used = sub_location.get_built();
+        for construction in &self.construction_queue {
+            let is_queued_here = construction.sub_location
+                .as_ref()
+                .is_some_and(|id| id.eq_ignore_ascii_case(sub_location.get_id()));
+            if is_queued_here && !used.contains(&construction.building.type_id) {
+                used.push(construction.building.type_id);
+            }
+        }
+        used.len() as u32
+    }
+
+    // Whether a new building fits on the surface or on a sub-location when one is given
+    fn has_free_slot_at(&self, sub_location: Option<&str>, economy_config: &EconomyConfig) -> bool {
+        match sub_location.and_then(|id| self.get_sub_location(id).ok()) {
+            Some(sub_location) => sub_location
+                .get_slots()
+                .is_none_or(|total| self.get_used_sub_location_slots(sub_location) < total),
+            None => self.get_used_building_slots() < self.get_building_slots(economy_config),
+        }
+    }
+
+    // Buildings hosted by the planet's orbit are only built there
+    fn is_orbital_building(&self, building_id: BuildingTypeId) -> bool {
+        self.sub_locations
+            .iter()
+            .any(|sub_location| sub_location.is_orbital() && sub_location.allows(building_id))
     }
 
-    fn get_mut_building(&mut self, building_id: BuildingTypeId) -> Result<&mut BuildingType, PlanetError> {
-        self.buildings.get_mut(&building_id).ok_or(PlanetError::BuildingNotBuilt)
+    fn get_sub_location(&self, id: &str) -> Result<&SubLocation, PlanetError> {
+        self.sub_locations
+            .iter()
+            .find(|sub_location| sub_location.get_id().eq_ignore_ascii_case(id))
+            .ok_or_else(|| PlanetError::SubLocationNotFound(format!("{}/{}", self.id, id)))
+    }
+
+    // Level of a building on the surface, or on a sub-location when one is given. Sub-locations
+    // only hold one building of each type.
+    fn get_building_level_at(&self, building: BuildingInstanceId, sub_location: Option<&str>) -> Result<u8, PlanetError> {
+        match sub_location {
+            Some(id) => {
+                let sub_location = self.get_sub_location(id)?;
+                if !sub_location.allows(building.type_id) || building.number != 1 {
+                    return Err(PlanetError::NotBuildableAt {
+                        building: building.type_id,
+                        location: format!("{}/{}", self.id, sub_location.get_id()),
+                    });
+                }
+                Ok(sub_location.get_building_level(building.type_id))
+            }
+            None if self.is_orbital_building(building.type_id) => Err(PlanetError::NotBuildableAt {
+                building: building.type_id,
+                location: format!("the surface of {}", self.id),
+            }),
+            None => Ok(self.get_building(building)?.get_level()),
+        }
+    }
+
+    fn upgrade_building_at(&mut self, building: BuildingInstanceId, sub_location: Option<&str>) -> Result<(), PlanetError> {
+        self.get_mut_building_at(building, sub_location)?.upgrade()?;
+        Ok(())
+    }
+
+    // Tries the construction's level on a copy of the building, so a level that can't be built
+    // fails before the construction leaves the queue and is paid for
+    fn check_construction(&mut self, construction: &Construction) -> Result<(), PlanetError> {
+        let mut building = self.get_mut_building_at(construction.building, construction.sub_location.as_deref())?.clone();
+        building.upgrade()?;
+        Ok(())
+    }
+
+    fn get_mut_building_at(
+        &mut self,
+        building: BuildingInstanceId,
+        sub_location: Option<&str>,
+    ) -> Result<&mut BuildingType, PlanetError> {
+        match sub_location {
+            Some(id) => {
+                let name = self.id.clone();
+                self.sub_locations
+                    .iter_mut()
+                    .find(|sub_location| sub_location.get_id().eq_ignore_ascii_case(id))
+                    .ok_or_else(|| PlanetError::SubLocationNotFound(format!("{}/{}", name, id)))?
+                    .get_mut_building(building.type_id)
+                    .ok_or(PlanetError::BuildingNotBuilt)
+            }
+            None => self.get_mut_building(building),
+        }
+    }
+
+    fn get_mut_building(&mut self, building: BuildingInstanceId) -> Result<&mut BuildingType, PlanetError> {
+        self.buildings.get_mut(&building).ok_or(PlanetError::BuildingNotBuilt)
+    }
+
+    // Adds the next building of a type at level 0, as many as the type allows
+    fn add_instance(&mut self, building: BuildingInstanceId, building_config: &BuildingConfig) -> Result<(), PlanetError> {
+        let next = self.get_next_instance_number(building.type_id);
+        if building.number != next {
+            return Err(PlanetError::InstanceNotFound { building, next });
+        }
+        if building.number > building_config.get_max_instances() {
+            return Err(PlanetError::TooManyInstances {
+                building: building.type_id,
+                max: building_config.get_max_instances(),
+            });
+        }
+
+        self.buildings.insert(building, BuildingType::new_zero(building.type_id, building_config.clone()));
+        Ok(())
+    }
+
+    // Credits are kept by the player, so there is no planetary storage for them
+    fn get_storage_building_id(resource: Resource) -> Result<BuildingTypeId, PlanetError> {
+        match resource {
+            Resource::Energy => Ok(BuildingTypeId::BatteryArray),
+            Resource::Minerals => Ok(BuildingTypeId::MineralSilo),
+            Resource::Gas => Ok(BuildingTypeId::GasTank),
+            Resource::Credits => Err(PlanetError::BuildingNotBuilt),
+        }
     }
 
     fn get_mut_resource_storage(&mut self, resource: Resource) -> Result<&mut Storage, PlanetError> {
-        let building_id = match resource {
-           Resource::Energy => BuildingTypeId::BatteryArray,
-           Resource::Minerals => BuildingTypeId::MineralSilo,
-           Resource::Gas => BuildingTypeId::GasTank,
-        };
-        let building = self.get_mut_building(building_id)?;
+        let building_id = Self::get_storage_building_id(resource)?;
+        let building = self.get_mut_building(BuildingInstanceId::first(building_id))?;
         match building {
             BuildingType::BatteryArray(storage)
             | BuildingType::MineralSilo(storage)
@@ -102,7 +664,7 @@ impl Planet {
         let production = self.get_production_rates();
 
         for (resource, rate) in production.iter() {
-            if *rate > 0 {
+            if !rate.is_zero() && *resource != Resource::Credits {
                 let storage_building = self.get_mut_resource_storage(*resource)?;
                 storage_building.add_resource(*rate);
             }
@@ -111,12 +673,8 @@ impl Planet {
     }
 
     fn get_resource_storage_ref(&self, resource: Resource) -> Result<&Storage, PlanetError> {
-        let building_id = match resource {
-            Resource::Energy => BuildingTypeId::BatteryArray,
-            Resource::Minerals => BuildingTypeId::MineralSilo,
-            Resource::Gas => BuildingTypeId::GasTank,
-        };
-        let building = self.get_building(building_id)?;
+        let building_id = Self::get_storage_building_id(resource)?;
+        let building = self.get_building(BuildingInstanceId::first(building_id))?;
         match building {
             BuildingType::BatteryArray(storage)
             | BuildingType::MineralSilo(storage)
@@ -125,61 +683,486 @@ impl Planet {
         }
     }
 
-    pub fn build(
+    // Queues the next level of a building on the surface or on one of the planet's sub-locations.
+    // Levels already queued count as built, so the same building can be queued several times in
+    // a row. Naming the next number of a type, e.g. MineralMine#2, adds another building of it.
+    // A surface building's first level needs a free building slot.
+    pub fn queue_construction(
         &mut self,
-        building_id: BuildingTypeId,
+        building_id: BuildingInstanceId,
         building_config: &BuildingConfig,
-    ) -> Result<(), PlanetError> {
-        if let Some(building) = self.buildings.get(&building_id) {
-            self.has_enough_resources(Some(building), building_config)?;
+        economy_config: &EconomyConfig,
+        sub_location: Option<&str>,
+    ) -> Result<&Construction, PlanetError> {
+        let is_new_instance = sub_location.is_none() && !self.buildings.contains_key(&building_id);
+        if is_new_instance {
+            self.add_instance(building_id, building_config)?;
+        }
+        let current_level = match self.get_building_level_at(building_id, sub_location) {
+            Ok(level) => level,
+            Err(err) => {
+                self.remove_unused_instance(building_id);
+                return Err(err);
+            }
+        };
+        // Stored with the id's configured case
+        let sub_location = sub_location
+            .map(|id| self.get_sub_location(id).map(|sub_location| sub_location.get_id().to_string()))
+            .transpose()?;
+        let queued_levels = self.construction_queue
+            .iter()
+            .filter(|construction| construction.building == building_id && construction.sub_location == sub_location)
+            .count() as u8;
+        let from_level = current_level + queued_levels;
 
-            if let Some(existing_building) = self.buildings.get_mut(&building_id) {
-                existing_building.upgrade()?;
-                return Ok(());
+        let max_level = building_config.get_max_level();
+        if from_level >= max_level {
+            return Err(PlanetError::BuildingError(
+                BuildingError::MaxLevelReached { current: from_level, max: max_level }
+            ));
+        }
+        let (used, total) = match &sub_location {
+            Some(id) => {
+                let sub_location = self.get_sub_location(id)?;
+                (self.get_used_sub_location_slots(sub_location), sub_location.get_slots().unwrap_or(u32::MAX))
+            }
+            None => (self.get_used_building_slots(), self.get_building_slots(economy_config)),
+        };
+        let is_upfront = economy_config.construction.payment == ConstructionPayment::Upfront;
+        let affordable = if from_level == 0 && used >= total {
+            Err(PlanetError::NoFreeBuildingSlot { used, total })
+        } else if is_upfront {
+            self.has_enough_resources(building_id.type_id, from_level, building_config)
+        } else {
+            Ok(())
+        };
+        if let Err(err) = affordable {
+            // A building that was just added for this construction isn't kept without it
+            if is_new_instance {
+                self.remove_unused_instance(building_id);
             }
+            return Err(err);
         }
-        Err(PlanetError::BuildingNotBuilt)
+
+        let build_time = building_config.get_building_time().time_per_level
+            .get(from_level as usize)
+            .cloned()
+            .unwrap_or(1) as i32
+            + self.modifiers.resolve(ModifierStat::ConstructionTime);
+        let cost = self.get_upgrade_cost(building_id.type_id, from_level, building_config)?;
+        self.construction_queue.push_back(Construction {
+            building: building_id,
+            level: from_level + 1,
+            remaining_turns: build_time.max(1) as u32,
+            sub_location,
+            cost,
+            is_reserved: is_upfront,
+        });
+        Ok(self.construction_queue.back().expect("construction was just queued"))
     }
 
-    pub fn get_production_rates(&self) -> HashMap<Resource, u32> {
-        let mut rates = HashMap::new();
-        rates.insert(Resource::Energy, 0);
-        rates.insert(Resource::Minerals, 0);
-        rates.insert(Resource::Gas, 0);
+    // Describes the first inconsistency found, anything reported here is a bug in the game
+    pub fn check_invariants(&self, buildings_config: &BuildingsConfig, economy_config: &EconomyConfig) -> Result<(), String> {
+        let get_max_level = |building_id: BuildingTypeId| {
+            buildings_config.buildings.get(building_id.get_name()).map_or(0, |config| config.get_max_level())
+        };
+
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            let storage = self.get_resource_storage_ref(resource)
+                .map_err(|err| format!("{} has no storage for {}: {}", self.id, resource, err))?;
+            if storage.get_resource() != resource {
+                return Err(format!("{}'s storage for {} holds {}", self.id, resource, storage.get_resource()));
+            }
+            if storage.get_current_amount() > storage.get_capacity() {
+                return Err(format!(
+                    "{} stores {} {} over a capacity of {}",
+                    self.id, storage.get_current_amount(), resource, storage.get_capacity(),
+                ));
+            }
+        }
 
-        for building in self.buildings.values() {
-            match building {
-                BuildingType::FusionReactor(productor)
-                | BuildingType::GasExtractor(productor)
-                | BuildingType::MineralMine(productor) => {
-                    let resource = productor.get_resource();
-                    *rates.entry(*resource).or_insert(0) += productor.get_production_rate();
+        for (instance, building) in &self.buildings {
+            if building.get_id() != instance.type_id {
+                return Err(format!("{} has a {} built as {}", self.id, building.get_name(), instance));
+            }
+            if building.get_level() > get_max_level(instance.type_id) {
+                return Err(format!(
+                    "{} on {} is level {} over its maximum of {}",
+                    instance, self.id, building.get_level(), get_max_level(instance.type_id),
+                ));
+            }
+        }
+        let known = |instance: &BuildingInstanceId| BuildingTypeId::all().contains(&instance.type_id) && instance.number >= 1;
+        if !self.buildings.keys().all(known) {
+            return Err(format!("{} has a building without a valid type and number", self.id));
+        }
+        for sub_location in &self.sub_locations {
+            for &building_id in BuildingTypeId::all() {
+                let level = sub_location.get_building_level(building_id);
+                if level > get_max_level(building_id) {
+                    return Err(format!(
+                        "{} on {}/{} is level {} over its maximum of {}",
+                        building_id, self.id, sub_location.get_id(), level, get_max_level(building_id),
+                    ));
                 }
-                _ => {}
             }
         }
+
+        for construction in &self.construction_queue {
+            let location = construction.get_location(self.id.as_str());
+            self.get_building_level_at(construction.building, construction.sub_location.as_deref())
+                .map_err(|err| format!("{} is queued on {}, where it can't be: {}", construction.building, location, err))?;
+            if construction.level > get_max_level(construction.building.type_id) {
+                return Err(format!(
+                    "{} level {} is queued on {} over its maximum of {}",
+                    construction.building, construction.level, location, get_max_level(construction.building.type_id),
+                ));
+            }
+        }
+
+        if self.tax_rate > economy_config.taxes.max_rate {
+            return Err(format!(
+                "{} is taxed at {}% over the maximum of {}%",
+                self.id, self.tax_rate, economy_config.taxes.max_rate,
+            ));
+        }
+        if self.morale > 100 {
+            return Err(format!("{} has a morale of {}%", self.id, self.morale));
+        }
+        Ok(())
+    }
+
+    fn remove_unused_instance(&mut self, building: BuildingInstanceId) {
+        let is_queued = self.construction_queue.iter().any(|construction| construction.building == building);
+        let unbuilt = self.buildings.get(&building).is_some_and(|building| building.get_level() == 0);
+        if building.number > 1 && !is_queued && unbuilt {
+            self.buildings.remove(&building);
+        }
+    }
+
+    pub fn get_construction_queue(&self) -> &VecDeque<Construction> {
+        &self.construction_queue
+    }
+
+    // The earliest queued level of the building, which is the one a rush completes
+    pub fn get_construction(&self, building_id: BuildingInstanceId, sub_location: Option<&str>) -> Option<&Construction> {
+        self.construction_queue
+            .iter()
+            .find(|construction| Self::is_construction_at(construction, building_id, sub_location))
+    }
+
+    fn is_construction_at(construction: &Construction, building_id: BuildingInstanceId, sub_location: Option<&str>) -> bool {
+        construction.building == building_id
+            && match (&construction.sub_location, sub_location) {
+                (Some(queued), Some(id)) => queued.eq_ignore_ascii_case(id),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+
+    // Progresses the construction at the front of the queue by a turn, returning it once done
+    pub fn advance_construction(&mut self) -> Result<Option<Construction>, PlanetError> {
+        let Some(construction) = self.construction_queue.front() else {
+            return Ok(None);
+        };
+        if !construction.is_reserved {
+            if !self.can_afford(&construction.cost) {
+                return Ok(None);
+            }
+            self.construction_queue[0].is_reserved = true;
+        }
+
+        let construction = self.construction_queue.front_mut().expect("queue has a front construction");
+        construction.remaining_turns = construction.remaining_turns.saturating_sub(1);
+        if construction.remaining_turns > 0 {
+            return Ok(None);
+        }
+
+        let construction = construction.clone();
+        self.check_construction(&construction)?;
+        self.construction_queue.pop_front();
+        self.pay_construction(&construction)?;
+        self.upgrade_building_at(construction.building, construction.sub_location.as_deref())?;
+        Ok(Some(construction))
+    }
+
+    // Moves a construction to another position of the queue, both counted from 1. Levels of a
+    // building keep their order, a level that was reserved keeps its reservation.
+    pub fn move_construction(&mut self, position: usize, new_position: usize) -> Result<&Construction, PlanetError> {
+        let len = self.construction_queue.len();
+        for position in [position, new_position] {
+            if position == 0 || position > len {
+                return Err(PlanetError::QueuePositionNotFound { position, len });
+            }
+        }
+
+        let mut queue = self.construction_queue.clone();
+        let construction = queue.remove(position - 1).expect("position was just checked");
+        queue.insert(new_position - 1, construction);
+        for (idx, construction) in queue.iter().enumerate() {
+            let is_after_higher_level = queue.iter().skip(idx + 1).any(|later| {
+                later.building == construction.building
+                    && later.sub_location == construction.sub_location
+                    && later.level < construction.level
+            });
+            if is_after_higher_level {
+                return Err(PlanetError::ConstructionOrder { building: construction.building, level: construction.level });
+            }
+        }
+
+        self.construction_queue = queue;
+        Ok(&self.construction_queue[new_position - 1])
+    }
+
+    // Completes the earliest queued level of the building right away. A level that isn't reserved
+    // yet has to be affordable next to what the queue reserves.
+    pub fn rush_construction(
+        &mut self,
+        building_id: BuildingInstanceId,
+        sub_location: Option<&str>,
+    ) -> Result<Construction, PlanetError> {
+        let idx = self.construction_queue
+            .iter()
+            .position(|construction| Self::is_construction_at(construction, building_id, sub_location))
+            .ok_or(PlanetError::NotUnderConstruction(building_id))?;
+        let construction = self.construction_queue[idx].clone();
+        if !construction.is_reserved && !self.can_afford(&construction.cost) {
+            return Err(PlanetError::InsufficientResources);
+        }
+        self.check_construction(&construction)?;
+
+        self.construction_queue.remove(idx);
+        self.pay_construction(&construction)?;
+        self.upgrade_building_at(construction.building, construction.sub_location.as_deref())?;
+        Ok(construction)
+    }
+
+    // Takes the cost of a construction that left the queue from the stored resources, which
+    // releases what it reserved
+    fn pay_construction(&mut self, construction: &Construction) -> Result<(), PlanetError> {
+        for (resource, amount) in construction.cost {
+            self.remove_resource(resource, amount)?;
+        }
+        Ok(())
+    }
+
+    // Surface buildings that produce something. The first of each type gives a little even before
+    // it is built, an extra one only once its first level is.
+    fn get_producers(&self) -> Vec<(BuildingInstanceId, &BuildingType)> {
+        self.buildings
+            .iter()
+            .filter(|(instance, building)| instance.number == 1 || building.get_level() > 0)
+            .filter(|(_, building)| building.get_production().is_some())
+            .map(|(instance, building)| (*instance, building))
+            .collect()
+    }
+
+    pub fn get_production_rates(&self) -> HashMap<Resource, ResourceAmount> {
+        let mut rates: HashMap<Resource, ResourceAmount> =
+            Resource::all().iter().map(|resource| (*resource, ResourceAmount::ZERO)).collect();
+
+        for (_, building) in self.get_producers() {
+            if let Some((resource, rate)) = building.get_production() {
+                *rates.entry(resource).or_default() += rate;
+            }
+        }
+
+        for sub_location in &self.sub_locations {
+            for (resource, rate) in sub_location.get_production_rates() {
+                *rates.entry(resource).or_default() += rate;
+            }
+        }
+
+        for (resource, rate) in rates.iter_mut() {
+            *rate = if self.on_strike {
+                ResourceAmount::ZERO
+            } else {
+                let percent = self.modifiers.get_percent(ModifierStat::Production(Some(*resource)));
+                rate.get_scaled(percent) * self.production_ticks
+            };
+        }
         rates
     }
 
-    pub fn get_resource_amount(&self, resource: Resource) -> u32 {
+    pub fn get_production_breakdown(&self, resource: Resource) -> ProductionBreakdown {
+        let mut buildings: Vec<(String, u8, Option<String>, ResourceAmount)> = self.get_producers()
+            .iter()
+            .filter_map(|(instance, building)| {
+                let (produced, rate) = building.get_production()?;
+                let name = match instance.number {
+                    1 => building.get_name().to_string(),
+                    number => format!("{} #{}", building.get_name(), number),
+                };
+                (produced == resource).then_some((name, building.get_level(), None, rate))
+            })
+            .collect();
+        buildings.sort();
+        for sub_location in &self.sub_locations {
+            for building in sub_location.get_producers() {
+                if let Some((produced, rate)) = building.get_production()
+                    && produced == resource
+                {
+                    let location = Some(sub_location.get_name().to_string());
+                    buildings.push((building.get_name().to_string(), building.get_level(), location, rate));
+                }
+            }
+        }
+
+        let mut modifiers: Vec<(String, i32)> = self.modifiers
+            .get_matching(ModifierStat::Production(Some(resource)))
+            .iter()
+            .map(|modifier| (modifier.source.to_string(), modifier.magnitude))
+            .collect();
+        if self.production_ticks > 1 {
+            let ticks = self.production_ticks;
+            modifiers.push((format!("Game speed ({} ticks per turn)", ticks), (ticks as i32 - 1) * 100));
+        }
+
+        ProductionBreakdown {
+            buildings,
+            modifiers,
+            on_strike: self.on_strike,
+            total: self.get_production_rates().get(&resource).copied().unwrap_or_default(),
+        }
+    }
+
+    // Returns how much was actually stored, never more than fits
+    pub fn add_resource(&mut self, resource: Resource, amount: ResourceAmount) -> Result<ResourceAmount, PlanetError> {
+        Ok(self.get_mut_resource_storage(resource)?.add_resource(amount))
+    }
+
+    // Returns how much was actually removed, never more than is stored
+    pub fn remove_resource(&mut self, resource: Resource, amount: ResourceAmount) -> Result<ResourceAmount, PlanetError> {
+        Ok(self.get_mut_resource_storage(resource)?.remove_resource(amount))
+    }
+
+    // What is stored and not reserved for the construction queue
+    pub fn get_free_amount(&self, resource: Resource) -> ResourceAmount {
+        self.get_resource_amount(resource) - self.get_reserved_amount(resource)
+    }
+
+    pub fn get_resource_amount(&self, resource: Resource) -> ResourceAmount {
         self.get_resource_storage_ref(resource)
             .map(|storage| storage.get_current_amount()) //
-            .unwrap_or(0)
+            .unwrap_or_default()
+    }
+
+    // Turns until the storage of a resource is full at the current production, 0 if it is and
+    // none if it never fills
+    pub fn get_turns_until_full(&self, resource: Resource) -> Option<u32> {
+        let free = self.get_resource_capacity(resource) - self.get_resource_amount(resource);
+        let production = self.get_production_rates().get(&resource).copied().unwrap_or_default();
+        free.get_times_at(production)
+    }
+
+    // Resources kept aside for the queued constructions
+    pub fn get_reserved_amount(&self, resource: Resource) -> ResourceAmount {
+        self.construction_queue
+            .iter()
+            .filter(|construction| construction.is_reserved)
+            .flat_map(|construction| construction.cost.iter())
+            .filter(|(cost_resource, _)| *cost_resource == resource)
+            .map(|(_, amount)| *amount)
+            .sum()
+    }
+
+    // Turns until the stored resources cover the cost on top of what the queue reserves at the
+    // current production, none if the production or storage never gets there
+    pub fn get_turns_until_affordable(&self, cost: &[(Resource, ResourceAmount)]) -> Option<u32> {
+        let production = self.get_production_rates();
+        cost.iter()
+            .map(|(resource, amount)| {
+                if *amount > self.get_resource_capacity(*resource) {
+                    return None;
+                }
+                let missing = *amount + self.get_reserved_amount(*resource) - self.get_resource_amount(*resource);
+                missing.get_times_at(production.get(resource).copied().unwrap_or_default())
+            })
+            .try_fold(0, |turns, needed| needed.map(|needed| turns.max(needed)))
+    }
+
+    // The next level a construction of the building would add, counting queued levels, as
+    // queue_construction would pick it
+    pub fn forecast_construction(
+        &self,
+        building_id: BuildingInstanceId,
+        building_config: &BuildingConfig,
+        sub_location: Option<&str>,
+    ) -> Result<ConstructionForecast, PlanetError> {
+        let current_level = if sub_location.is_none() && !self.buildings.contains_key(&building_id) {
+            let next = self.get_next_instance_number(building_id.type_id);
+            if building_id.number != next {
+                return Err(PlanetError::InstanceNotFound { building: building_id, next });
+            }
+            0
+        } else {
+            self.get_building_level_at(building_id, sub_location)?
+        };
+        let queued_levels = self.construction_queue
+            .iter()
+            .filter(|construction| Self::is_construction_at(construction, building_id, sub_location))
+            .count() as u8;
+        let from_level = current_level + queued_levels;
+
+        let max_level = building_config.get_max_level();
+        if from_level >= max_level {
+            return Err(PlanetError::BuildingError(
+                BuildingError::MaxLevelReached { current: from_level, max: max_level }
+            ));
+        }
+
+        let cost = self.get_upgrade_cost(building_id.type_id, from_level, building_config)?;
+        Ok(ConstructionForecast { level: from_level + 1, cost, affordable_in: self.get_turns_until_affordable(&cost) })
     }
 
-    pub fn get_resource_capacity(&self, resource: Resource) -> u32 {
+    // Whether any construction could be queued right now with the stored resources
+    pub fn has_affordable_construction(&self, buildings_config: &BuildingsConfig, economy_config: &EconomyConfig) -> bool {
+        BuildingTypeId::all().iter().any(|&type_id| {
+            let Some(building_config) = buildings_config.buildings.get(type_id.get_name()) else {
+                return false;
+            };
+            let next = self.get_next_instance_number(type_id);
+            let mut sites: Vec<(BuildingInstanceId, Option<&str>)> = (1..next)
+                .map(|number| (BuildingInstanceId { type_id, number }, None))
+                .collect();
+            if next <= building_config.get_max_instances() {
+                sites.push((BuildingInstanceId { type_id, number: next }, None));
+            }
+            sites.extend(self.sub_locations
+                .iter()
+                .filter(|sub_location| sub_location.allows(type_id))
+                .map(|sub_location| (BuildingInstanceId::first(type_id), Some(sub_location.get_id()))));
+
+            sites.into_iter().any(|(building_id, sub_location)| {
+                self.forecast_construction(building_id, building_config, sub_location).is_ok_and(|forecast| {
+                    // Only a new building takes up a slot
+                    forecast.affordable_in == Some(0)
+                        && (forecast.level > 1 || self.has_free_slot_at(sub_location, economy_config))
+                })
+            })
+        })
+    }
+
+    pub fn get_resource_capacity(&self, resource: Resource) -> ResourceAmount {
         self.get_resource_storage_ref(resource)
            .map(|storage| storage.get_capacity()) //
-           .unwrap_or(0)
+           .unwrap_or_default()
     }
 
-    pub fn get_status(&self, total_planet_count: usize) -> PlanetStatus {
+    pub fn get_status(&self, total_planet_count: usize, economy_config: &EconomyConfig) -> PlanetStatus {
         let planet_name = self.get_name().to_string();
 
         let buildings_list: Vec<(String, u8)> = self
             .buildings
-            .values()
-            .map(|building| (building.get_name().to_string(), building.get_level())) 
+            .iter()
+            .filter(|(instance, _)| !self.is_orbital_building(instance.type_id))
+            .map(|(instance, building)| {
+                let name = match instance.number {
+                    1 => building.get_name().to_string(),
+                    number => format!("{} #{}", building.get_name(), number),
+                };
+                (name, building.get_level())
+            })
             .collect();
 
         let production_rates = self.get_production_rates();
@@ -201,18 +1184,45 @@ impl Planet {
 
         PlanetStatus {
             planet_name,
+            size: self.size.clone(),
+            building_slots: (self.get_used_building_slots(), self.get_building_slots(economy_config)),
             buildings: buildings_list,
             production: production_rates,
             storage: storage_map,
+            reserved: [Resource::Energy, Resource::Minerals, Resource::Gas]
+                .into_iter()
+                .map(|resource| (resource, self.get_reserved_amount(resource)))
+                .filter(|(_, amount)| !amount.is_zero())
+                .collect(),
+            turns_until_full: [Resource::Energy, Resource::Minerals, Resource::Gas]
+                .into_iter()
+                .filter_map(|resource| self.get_turns_until_full(resource).map(|turns| (resource, turns)))
+                .collect(),
+            population: self.population,
+            housing: self.get_housing(economy_config),
+            tax_rate: self.tax_rate,
+            morale: self.morale,
+            on_strike: self.on_strike,
+            governor: self.governor.as_ref().map(|governor| (governor.get_name().to_string(), governor.get_level())),
+            specialization: self.specialization
+                .as_ref()
+                .map(|specialization| (specialization.name.clone(), specialization.focus)),
+            construction_queue: self.construction_queue.iter().cloned().collect(),
+            sub_locations: self.sub_locations
+                .iter()
+                .map(|sub_location| sub_location.get_status(self.get_used_sub_location_slots(sub_location)))
+                .collect(),
             planet_count: total_planet_count,
         }
     }
 
-    fn init_all_buildings_zero(buildings_config: &BuildingsConfig) -> Result<HashMap<BuildingTypeId, BuildingType>, PlanetError> {
+    fn init_all_buildings_zero(
+        buildings_config: &BuildingsConfig,
+    ) -> Result<HashMap<BuildingInstanceId, BuildingType>, PlanetError> {
         let mut map = HashMap::new();
         for &building_id in BuildingTypeId::all() {
             if let Some(building_config) = buildings_config.buildings.get(building_id.get_name()) {
-                map.insert(building_id, BuildingType::new_zero(building_id, building_config.clone()));
+                map.insert(BuildingInstanceId::first(building_id), BuildingType::new_zero(building_id, building_config.clone()));
             } else {
                 Err(PlanetError::BuildingsConfigError(
                     BuildingsConfigError::BuildingNotFound(format!("Building config for {} not found", building_id.get_name()))
@@ -224,30 +1234,41 @@ impl Planet {
 
     fn get_building(
         &self,
-        building_id: BuildingTypeId,
+        building_id: BuildingInstanceId,
     ) -> Result<&BuildingType, PlanetError> {
         self.buildings
             .get(&building_id)
             .ok_or(PlanetError::BuildingNotBuilt)
     }
 
-    fn get_resource_storage(
+    fn has_enough_resources(
         &self,
-        resource: Resource,
-    ) -> Result<&BuildingType, PlanetError> {
-        match resource {
-            Resource::Energy => self.get_building(BuildingTypeId::BatteryArray),
-            Resource::Minerals => self.get_building(BuildingTypeId::MineralSilo),
-            Resource::Gas => self.get_building(BuildingTypeId::GasTank)
+        building_type: BuildingTypeId,
+        building_level: u8,
+        building_config: &BuildingConfig,
+    ) -> Result<(), PlanetError> {
+        let cost = self.get_upgrade_cost(building_type, building_level, building_config)?;
+        if self.can_afford(&cost) {
+            Ok(())
+        } else {
+            Err(PlanetError::InsufficientResources)
         }
     }
-    
-    fn has_enough_resources(
+
+    // Whether the stored resources cover the cost on top of what the queue reserves
+    fn can_afford(&self, cost: &[(Resource, ResourceAmount)]) -> bool {
+        cost.iter().all(|(resource, amount)| {
+            self.get_resource_amount(*resource) >= *amount + self.get_reserved_amount(*resource)
+        })
+    }
+
+    // Resources the level after the given one costs
+    fn get_upgrade_cost(
         &self,
-        building: Option<&BuildingType>,
+        building_type: BuildingTypeId,
+        building_level: u8,
         building_config: &BuildingConfig,
-    ) -> Result<(), PlanetError> {
-        let building_level = building.map_or(1, |b| b.get_level());
+    ) -> Result<[(Resource, ResourceAmount); 3], PlanetError> {
         let upgrade_cost = building_config.get_upgrade_cost();
 
         let energy_cost = upgrade_cost.energy.get(building_level as usize).ok_or(
@@ -272,14 +1293,50 @@ impl Planet {
             )
         )?;
 
-        if
-            self.get_resource_amount(Resource::Energy) >= *energy_cost &&
-            self.get_resource_amount(Resource::Minerals) >= *minerals_cost &&
-            self.get_resource_amount(Resource::Gas) >= *gas_cost 
-        {
-            Ok(())
-        } else {
-            Err(PlanetError::InsufficientResources)
+        let is_storage = [Resource::Energy, Resource::Minerals, Resource::Gas]
+            .into_iter()
+            .any(|resource| Self::get_storage_building_id(resource).is_ok_and(|storage| storage == building_type));
+        let percent = if is_storage { self.storage_cost_percent } else { 100 };
+        Ok([
+            (Resource::Energy, ResourceAmount::from(*energy_cost).get_scaled(percent)),
+            (Resource::Minerals, ResourceAmount::from(*minerals_cost).get_scaled(percent)),
+            (Resource::Gas, ResourceAmount::from(*gas_cost).get_scaled(percent)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn new_planet() -> (Planet, BuildingsConfig, EconomyConfig) {
+        let buildings_config = BuildingsConfig::load(Path::new("data/buildings.toml")).unwrap();
+        let economy_config = EconomyConfig::load(Path::new("data/economy.toml")).unwrap();
+        let sub_locations_config = SubLocationsConfig::load(Path::new("data/sub_locations.toml")).unwrap();
+        let planet = Planet::new("Testia", &buildings_config, &economy_config, &sub_locations_config).unwrap();
+        (planet, buildings_config, economy_config)
+    }
+
+    #[test]
+    fn completed_level_takes_its_cost_from_storage() {
+        let (mut planet, buildings_config, economy_config) = new_planet();
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            planet.add_resource(resource, planet.get_resource_capacity(resource)).unwrap();
         }
+        let stored = |planet: &Planet| planet.get_resource_amount(Resource::Minerals);
+        let before = stored(&planet);
+
+        let building = BuildingInstanceId::first(BuildingTypeId::MineralMine);
+        let building_config = &buildings_config.buildings[building.type_id.get_name()];
+        let cost = planet.queue_construction(building, building_config, &economy_config, None).unwrap().cost;
+        let minerals_cost = cost.iter().find(|(resource, _)| *resource == Resource::Minerals).unwrap().1;
+        assert!(!minerals_cost.is_zero());
+        assert_eq!(stored(&planet), before);
+
+        while planet.advance_construction().unwrap().is_none() {}
+        assert_eq!(stored(&planet), before - minerals_cost);
+        assert!(planet.get_reserved_amount(Resource::Minerals).is_zero());
     }
 }
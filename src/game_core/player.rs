@@ -1,46 +1,522 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use super::{
-    building::BuildingsConfig, planet::PlanetError, BuildingBase, BuildingConfig, BuildingType, BuildingTypeId, Planet
+    artifact::ArtifactConfig,
+    building::{BuildingTypeId, BuildingsConfig},
+    economy::{CreditsReport, EconomyConfig},
+    edict::{EdictConfig, Edicts},
+    governor::Governor,
+    modifier::{Modifier, ModifierScope, ModifierSet, ModifierSource, ModifierStat},
+    planet::{Construction, PlanetError, PlanetId},
+    specialization::SpecializationConfig,
+    sub_location::SubLocationsConfig,
+    synergy::SynergiesConfig,
+    Planet,
+    Resource,
+    ResourceAmount,
 };
 
+// Everything that happened to a player's colony at the end of a turn
+#[derive(Debug, Default)]
+pub struct TurnEndReport {
+    pub credits: CreditsReport,
+    // Planet and the construction that finished there
+    pub completed: Vec<(PlanetId, Construction)>,
+    // Planets whose workers strike during the next turn
+    pub strikes: Vec<PlanetId>,
+    // Governor name and the level they reached
+    pub promotions: Vec<(String, u32)>,
+    // Resource, amount and the planets it was moved from and to
+    pub shipments: Vec<(Resource, ResourceAmount, PlanetId, PlanetId)>,
+}
+
+// Shown with the player's name wherever it appears
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl PlayerColor {
+    pub fn all() -> &'static [PlayerColor] {
+        &[
+            PlayerColor::Red,
+            PlayerColor::Green,
+            PlayerColor::Yellow,
+            PlayerColor::Blue,
+            PlayerColor::Magenta,
+            PlayerColor::Cyan,
+            PlayerColor::White,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<PlayerColor> {
+        Self::all().iter().find(|color| color.to_string().eq_ignore_ascii_case(name)).copied()
+    }
+}
+
+impl fmt::Display for PlayerColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerColor::Red => write!(f, "Red"),
+            PlayerColor::Green => write!(f, "Green"),
+            PlayerColor::Yellow => write!(f, "Yellow"),
+            PlayerColor::Blue => write!(f, "Blue"),
+            PlayerColor::Magenta => write!(f, "Magenta"),
+            PlayerColor::Cyan => write!(f, "Cyan"),
+            PlayerColor::White => write!(f, "White"),
+        }
+    }
+}
+
 pub struct Player {
     name: String,
-    planets: HashMap<String, Planet>,
+    color: PlayerColor,
+    planets: HashMap<PlanetId, Planet>,
+    credits: ResourceAmount,
+    // Hired governors without a planet
+    idle_governors: Vec<Governor>,
+    edicts: Edicts,
+    // Unique relics, each adds its modifiers while held
+    artifacts: Vec<ArtifactConfig>,
+    modifiers: ModifierSet,
 }
 
 impl Player {
-    pub fn new(name: &str, planet_name: &str, buildings_config: &BuildingsConfig) -> Self {
-        let planet = Planet::new(planet_name, buildings_config).unwrap(); // TODO: Handle error
+    pub fn new(
+        name: &str,
+        planet_name: &str,
+        buildings_config: &BuildingsConfig,
+        economy_config: &EconomyConfig,
+        sub_locations_config: &SubLocationsConfig,
+    ) -> Self {
+        let planet = Planet::new(planet_name, buildings_config, economy_config, sub_locations_config)
+            .unwrap(); // TODO: Handle error
 
-        Player {
+        let mut player = Player {
             name: name.to_string(),
+            color: PlayerColor::Cyan,
             planets: HashMap::from([
                 (
-                    planet_name.to_string(),
+                    planet.get_id().clone(),
                     planet,
                 ),
             ]),
+            credits: economy_config.starting_credits,
+            idle_governors: Vec::new(),
+            edicts: Edicts::default(),
+            artifacts: Vec::new(),
+            modifiers: ModifierSet::default(),
+        };
+        player.update_unrest_modifiers(economy_config);
+        player
+    }
+
+    // Hands every planet the modifiers that apply to it, called after any modifier changed
+    fn sync_modifiers(&mut self) {
+        for (planet_name, planet) in self.planets.iter_mut() {
+            planet.set_modifiers(self.modifiers.for_planet(planet_name.as_str()));
+        }
+    }
+
+    fn update_unrest_modifiers(&mut self, economy_config: &EconomyConfig) {
+        for (planet_name, planet) in self.planets.iter() {
+            let source = ModifierSource::Morale(planet_name.clone());
+            let penalty = planet.get_unrest_penalty(economy_config);
+            let modifiers = if penalty < 0 {
+                vec![Modifier::new(
+                    source.clone(), ModifierScope::Planet(planet_name.clone()), ModifierStat::Production(None), penalty
+                )]
+            } else {
+                Vec::new()
+            };
+            self.modifiers.set_source(source, modifiers);
+        }
+        self.sync_modifiers();
+    }
+
+    // Grants every planet the synergies its buildings form and takes away those they no longer
+    // do. Returns the planet and name of each synergy that became active.
+    pub fn update_synergy_modifiers(&mut self, synergies_config: &SynergiesConfig) -> Vec<(PlanetId, String)> {
+        let mut activated = Vec::new();
+        for (planet_name, planet) in self.planets.iter() {
+            for synergy in &synergies_config.synergies {
+                let source = synergy.get_modifier_source(planet_name);
+                if !synergy.get_missing(planet).is_empty() {
+                    self.modifiers.remove_source(&source);
+                    continue;
+                }
+                if !self.modifiers.has_source(&source) {
+                    activated.push((planet_name.clone(), synergy.name.clone()));
+                }
+                self.modifiers.set_source(source, synergy.get_modifiers(planet_name));
+            }
+        }
+        self.sync_modifiers();
+        activated
+    }
+
+    // How many production ticks a turn brings on every planet
+    pub fn set_production_ticks(&mut self, ticks: u32) {
+        for planet in self.planets.values_mut() {
+            planet.set_production_ticks(ticks);
+        }
+    }
+
+    // What storage buildings cost on every planet compared to their configuration, in percent
+    pub fn set_storage_cost_percent(&mut self, percent: u32) {
+        for planet in self.planets.values_mut() {
+            planet.set_storage_cost_percent(percent);
+        }
+    }
+
+    pub fn get_credits(&self) -> ResourceAmount {
+        self.credits
+    }
+
+    pub fn set_credits(&mut self, credits: ResourceAmount) {
+        self.credits = credits;
+    }
+
+    pub fn add_credits(&mut self, amount: ResourceAmount) {
+        self.credits += amount;
+    }
+
+    // Returns false and leaves the treasury untouched when it holds less than the amount
+    pub fn spend_credits(&mut self, amount: ResourceAmount) -> bool {
+        if self.credits < amount {
+            return false;
+        }
+        self.credits -= amount;
+        true
+    }
+
+    // Credits the next turn end is expected to bring in (or cost, if negative)
+    pub fn get_credits_forecast(&self, economy_config: &EconomyConfig) -> CreditsReport {
+        let mut report = CreditsReport::default();
+        for planet in self.planets.values() {
+            report.taxes += planet.get_tax_income(economy_config);
+            report.trade += planet.get_production_rates().get(&Resource::Credits).copied().unwrap_or_default();
+            report.upkeep += planet.get_upkeep(economy_config);
         }
+        report
     }
 
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
+    // The name is the player's key in GameCore, rename them through it
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    pub fn get_color(&self) -> PlayerColor {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: PlayerColor) {
+        self.color = color;
+    }
+
+    pub fn get_edicts(&self) -> &Edicts {
+        &self.edicts
+    }
+
+    // Enacts or repeals an edict and applies the new modifiers to every planet, returns whether
+    // the edict is active now
+    pub fn toggle_edict(&mut self, edict: &EdictConfig, turn: u32) -> bool {
+        let is_active = self.edicts.toggle(edict, turn);
+        let modifiers = if is_active { edict.get_modifiers() } else { Vec::new() };
+        self.modifiers.set_source(edict.get_modifier_source(), modifiers);
+        self.sync_modifiers();
+        is_active
+    }
+
+    pub fn get_artifacts(&self) -> &[ArtifactConfig] {
+        &self.artifacts
+    }
+
+    pub fn has_artifact(&self, artifact_id: &str) -> bool {
+        self.artifacts.iter().any(|artifact| artifact.id.eq_ignore_ascii_case(artifact_id))
+    }
+
+    // TODO: Remove artifacts again when they are traded away in diplomacy deals
+    pub fn add_artifact(&mut self, artifact: &ArtifactConfig) {
+        self.modifiers.set_source(artifact.get_modifier_source(), artifact.get_modifiers());
+        self.artifacts.push(artifact.clone());
+        self.sync_modifiers();
+    }
+
+    // Modifiers of a rule of the game rather than anything the player holds, e.g. a challenge twist
+    pub fn set_rule_modifiers(&mut self, source: ModifierSource, modifiers: Vec<Modifier>) {
+        self.modifiers.set_source(source, modifiers);
+        self.sync_modifiers();
+    }
+
+    pub fn get_resource_total(&self, resource: Resource) -> ResourceAmount {
+        self.planets.values().map(|planet| planet.get_resource_amount(resource)).sum()
+    }
+
+    // What the planets store that their construction queues don't reserve
+    pub fn get_free_resource_total(&self, resource: Resource) -> ResourceAmount {
+        self.planets.values().map(|planet| planet.get_free_amount(resource)).sum()
+    }
+
+    // Takes resources the construction queues don't reserve from the planets one after another,
+    // returns false and takes nothing when all of them together have less than the amount free
+    // TODO: Limit moving resources between planets by the freight capacity of cargo ships once
+    // fleets exist, transfers beyond it should queue or fail
+    pub fn spend_resource(&mut self, resource: Resource, amount: ResourceAmount) -> Result<bool, PlanetError> {
+        if self.get_free_resource_total(resource) < amount {
+            return Ok(false);
+        }
+
+        let mut remaining = amount;
+        for planet in self.planets.values_mut() {
+            let free = planet.get_free_amount(resource);
+            remaining -= planet.remove_resource(resource, remaining.min(free))?;
+            if remaining.is_zero() {
+                break;
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn get_free_capacity(&self, resource: Resource) -> ResourceAmount {
+        self.planets
+            .values()
+            .map(|planet| planet.get_resource_capacity(resource) - planet.get_resource_amount(resource))
+            .sum()
+    }
+
+    // Fills the planets' storage one after another, returns false and stores nothing when the
+    // amount does not fit into all of them together
+    pub fn store_resource(&mut self, resource: Resource, amount: ResourceAmount) -> Result<bool, PlanetError> {
+        if self.get_free_capacity(resource) < amount {
+            return Ok(false);
+        }
+
+        let mut remaining = amount;
+        for planet in self.planets.values_mut() {
+            remaining -= planet.add_resource(resource, remaining)?;
+            if remaining.is_zero() {
+                break;
+            }
+        }
+        Ok(true)
+    }
+
+    // Moves resources from planets above their stockpile targets to those below theirs, the largest
+    // surplus to the largest deficit first, up to the freight capacity of each resource
+    fn balance_stockpiles(
+        &mut self,
+        economy_config: &EconomyConfig,
+    ) -> Result<Vec<(Resource, ResourceAmount, PlanetId, PlanetId)>, PlanetError> {
+        let mut shipments = Vec::new();
+        let mut planet_names: Vec<PlanetId> = self.planets.keys().cloned().collect();
+        planet_names.sort_by(|first, second| first.as_str().cmp(second.as_str()));
+
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            let mut freight = economy_config.logistics.freight_per_turn;
+            let mut balances: Vec<(PlanetId, ResourceAmount, ResourceAmount)> = planet_names
+                .iter()
+                .map(|name| {
+                    let (surplus, deficit) = self.planets[name].get_stockpile_balance(resource);
+                    (name.clone(), surplus, deficit)
+                })
+                .collect();
+
+            while !freight.is_zero() {
+                // Ties go to the first planet by name, as the balances are sorted by it
+                let from = balances.iter().enumerate().rev().max_by_key(|(_, (_, surplus, _))| *surplus);
+                let to = balances.iter().enumerate().rev().max_by_key(|(_, (_, _, deficit))| *deficit);
+                let (Some((from, (_, surplus, _))), Some((to, (_, _, deficit)))) = (from, to) else {
+                    break;
+                };
+                let amount = (*surplus).min(*deficit).min(freight);
+                if amount.is_zero() || from == to {
+                    break;
+                }
+
+                let removed = self.planets.get_mut(&balances[from].0)
+                    .ok_or_else(|| PlanetError::PlanetNotFound(balances[from].0.to_string()))?
+                    .remove_resource(resource, amount)?;
+                let added = self.planets.get_mut(&balances[to].0)
+                    .ok_or_else(|| PlanetError::PlanetNotFound(balances[to].0.to_string()))?
+                    .add_resource(resource, removed)?;
+                freight -= amount;
+                balances[from].1 -= amount;
+                balances[to].2 -= amount;
+                shipments.push((resource, added, balances[from].0.clone(), balances[to].0.clone()));
+            }
+        }
+        Ok(shipments)
+    }
+
+    // Every hired governor with the planet they govern
+    pub fn get_governors(&self) -> Vec<(&Governor, Option<&str>)> {
+        let mut governors: Vec<(&Governor, Option<&str>)> = self.idle_governors
+            .iter()
+            .map(|governor| (governor, None))
+            .collect();
+        for planet in self.planets.values() {
+            if let Some(governor) = planet.get_governor() {
+                governors.push((governor, Some(planet.get_name())));
+            }
+        }
+        governors
+    }
+
+    pub fn has_governor(&self, governor_id: &str) -> bool {
+        self.get_governors().iter().any(|(governor, _)| governor.get_id().eq_ignore_ascii_case(governor_id))
+    }
+
+    pub fn add_idle_governor(&mut self, governor: Governor) {
+        self.idle_governors.push(governor);
+    }
+
+    // Removes a hired governor from wherever they are, so they can be assigned elsewhere
+    pub fn take_governor(&mut self, governor_id: &str) -> Option<Governor> {
+        if let Some(idx) = self.idle_governors.iter().position(|governor| governor.get_id().eq_ignore_ascii_case(governor_id)) {
+            return Some(self.idle_governors.remove(idx));
+        }
+
+        let governor = self.planets.values_mut()
+            .find(|planet| planet.get_governor().is_some_and(|governor| governor.get_id().eq_ignore_ascii_case(governor_id)))
+            .and_then(|planet| planet.set_governor(None))?;
+        self.modifiers.remove_source(&governor.get_modifier_source());
+        self.sync_modifiers();
+        Some(governor)
+    }
+
+    // Puts the governor in charge of the planet, returns the governor who was replaced
+    pub fn assign_governor(&mut self, governor: Governor, planet_name: &str) -> Result<Option<Governor>, PlanetError> {
+        let planet = self.planets.get_mut(planet_name).ok_or(PlanetError::PlanetNotFound(planet_name.to_string()))?;
+
+        self.modifiers.set_source(governor.get_modifier_source(), governor.get_modifiers(planet.get_id()));
+        let replaced = planet.set_governor(Some(governor));
+        if let Some(replaced) = &replaced {
+            self.modifiers.remove_source(&replaced.get_modifier_source());
+        }
+        self.sync_modifiers();
+        Ok(replaced)
+    }
+
+    // Designates the planet for a specialization, or drops its designation when none is given, and
+    // locks it for the cooldown. Returns the designation that was replaced.
+    pub fn designate_planet(
+        &mut self,
+        planet_name: &str,
+        specialization: Option<&SpecializationConfig>,
+        available_from_turn: u32,
+    ) -> Result<Option<SpecializationConfig>, PlanetError> {
+        let planet = self.planets.get_mut(planet_name).ok_or(PlanetError::PlanetNotFound(planet_name.to_string()))?;
+
+        let replaced = planet.set_specialization(specialization.cloned(), available_from_turn);
+        if let Some(replaced) = &replaced {
+            self.modifiers.remove_source(&replaced.get_modifier_source(planet.get_id()));
+        }
+        if let Some(specialization) = specialization {
+            self.modifiers.set_source(
+                specialization.get_modifier_source(planet.get_id()),
+                specialization.get_modifiers(planet.get_id()),
+            );
+        }
+        self.sync_modifiers();
+        Ok(replaced)
+    }
+
+    // TODO: Take planets away when they are conquered or abandoned, a player without any is
+    // eliminated, see GameCore::end_turn
     pub fn get_planets_count(&self) -> usize {
         self.planets.len()
     }
 
     pub fn get_planet_names(&self) -> Vec<String> {
-        self.planets.keys().cloned().collect()
+        self.planets.keys().map(PlanetId::to_string).collect()
     }
 
-    pub fn process_turn_end(&mut self) -> Result<(), PlanetError> {
-        for planet in self.planets.values_mut() {
+    // Planet on which a wonder is built or under construction, an empire only builds each one once
+    pub fn find_wonder_planet(&self, building_id: BuildingTypeId) -> Option<&PlanetId> {
+        self.planets
+            .iter()
+            .find(|(_, planet)| {
+                planet.get_building_level(building_id) > 0
+                    || planet.get_construction_queue().iter().any(|construction| {
+                        construction.building.type_id == building_id
+                    })
+            })
+            .map(|(id, _)| id)
+    }
+
+    pub fn process_turn_end(&mut self, economy_config: &EconomyConfig) -> Result<TurnEndReport, PlanetError> {
+        // Income is based on the population before it grows this turn
+        let mut report = TurnEndReport {
+            credits: self.get_credits_forecast(economy_config),
+            completed: Vec::new(),
+            strikes: Vec::new(),
+            promotions: Vec::new(),
+            shipments: Vec::new(),
+        };
+
+        // TODO: Blockade planets with enemy fleets in orbit once fleets exist: cut their trade and
+        // transfers, add a production penalty through the modifiers and flag them in the status pane
+        for (planet_name, planet) in self.planets.iter_mut() {
+            planet.record_production(economy_config);
             planet.generate_resources()?;
+            planet.grow_population(economy_config);
+            // Finished levels start producing on the next turn
+            if let Some(construction) = planet.advance_construction()? {
+                report.completed.push((planet_name.clone(), construction));
+            }
+            if let Some(governor) = planet.get_mut_governor()
+                && governor.gain_experience()
+            {
+                report.promotions.push((governor.get_name().to_string(), governor.get_level()));
+                self.modifiers.set_source(governor.get_modifier_source(), governor.get_modifiers(planet_name));
+            }
+        }
+
+        report.shipments = self.balance_stockpiles(economy_config)?;
+
+        // TODO: Vassals should hand a share of their income to their overlord here and be barred from
+        // declaring war, once diplomacy exists
+        let credits = &mut report.credits;
+        let available = self.credits + credits.taxes + credits.trade;
+        credits.unpaid = credits.upkeep - available;
+        self.credits = available - credits.upkeep;
+
+        self.modifiers.tick();
+        if !credits.unpaid.is_zero() {
+            let morale_config = &economy_config.morale;
+            let source = ModifierSource::Event("Unpaid upkeep".to_string());
+            self.modifiers.set_source(
+                source.clone(),
+                vec![Modifier::new(
+                    source,
+                    ModifierScope::Empire,
+                    ModifierStat::Morale,
+                    -(morale_config.unpaid_upkeep_penalty as i32),
+                ).with_duration(morale_config.unpaid_upkeep_penalty_turns)],
+            );
         }
-        Ok(())
+        self.sync_modifiers();
+
+        for (planet_name, planet) in self.planets.iter_mut() {
+            planet.update_morale(economy_config);
+            if planet.is_on_strike() {
+                report.strikes.push(planet_name.clone());
+            }
+        }
+        self.update_unrest_modifiers(economy_config);
+        Ok(report)
     }
     
     pub fn get_planet(&self, planet_name: &str) -> Option<&Planet> {
@@ -50,4 +526,65 @@ impl Player {
     pub fn get_mut_planet(&mut self, planet_name: &str) -> Option<&mut Planet> {
         self.planets.get_mut(planet_name)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::super::building::BuildingInstanceId;
+    use super::*;
+
+    #[test]
+    fn spending_leaves_reserved_resources_alone() {
+        let buildings_config = BuildingsConfig::load(Path::new("data/buildings.toml")).unwrap();
+        let economy_config = EconomyConfig::load(Path::new("data/economy.toml")).unwrap();
+        let sub_locations_config = SubLocationsConfig::load(Path::new("data/sub_locations.toml")).unwrap();
+        let mut player = Player::new("Tester", "Testia", &buildings_config, &economy_config, &sub_locations_config);
+
+        let planet = player.get_mut_planet("Testia").unwrap();
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            planet.add_resource(resource, planet.get_resource_capacity(resource)).unwrap();
+        }
+        let building = BuildingInstanceId::first(BuildingTypeId::MineralMine);
+        let building_config = &buildings_config.buildings[building.type_id.get_name()];
+        planet.queue_construction(building, building_config, &economy_config, None).unwrap();
+        let reserved = planet.get_reserved_amount(Resource::Minerals);
+        assert!(!reserved.is_zero());
+
+        let free = player.get_free_resource_total(Resource::Minerals);
+        assert_eq!(free, player.get_resource_total(Resource::Minerals) - reserved);
+        assert!(!player.spend_resource(Resource::Minerals, free + ResourceAmount::new(1)).unwrap());
+        assert!(player.spend_resource(Resource::Minerals, free).unwrap());
+        assert_eq!(player.get_resource_total(Resource::Minerals), reserved);
+    }
+
+    #[test]
+    fn turn_end_completes_the_last_level_of_a_building() {
+        let buildings_config = BuildingsConfig::load(Path::new("data/buildings.toml")).unwrap();
+        let economy_config = EconomyConfig::load(Path::new("data/economy.toml")).unwrap();
+        let sub_locations_config = SubLocationsConfig::load(Path::new("data/sub_locations.toml")).unwrap();
+        let mut player = Player::new("Tester", "Testia", &buildings_config, &economy_config, &sub_locations_config);
+
+        let building = BuildingInstanceId::first(BuildingTypeId::BatteryArray);
+        let building_config = &buildings_config.buildings[building.type_id.get_name()];
+        let max_level = building_config.get_max_level();
+        for level in 1..=max_level {
+            let planet = player.get_mut_planet("Testia").unwrap();
+            for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+                planet.add_resource(resource, planet.get_resource_capacity(resource)).unwrap();
+            }
+            planet.queue_construction(building, building_config, &economy_config, None).unwrap();
+
+            loop {
+                let report = player.process_turn_end(&economy_config).unwrap();
+                if report.completed.iter().any(|(_, construction)| construction.level == level) {
+                    break;
+                }
+            }
+        }
+
+        let planet = player.get_planet("Testia").unwrap();
+        assert_eq!(planet.get_building_level(building.type_id), max_level);
+        assert!(planet.get_construction_queue().is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use super::stats::{format_playtime, GameStats};
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(io::Error),
+    TomlDe(toml::de::Error),
+    TomlSer(toml::ser::Error),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(e) => write!(f, "Failed to access profile file: {}", e),
+            ProfileError::TomlDe(e) => write!(f, "Failed to parse profile file (TOML): {}", e),
+            ProfileError::TomlSer(e) => write!(f, "Failed to serialize profile: {}", e),
+        }
+    }
+}
+
+impl Error for ProfileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProfileError::Io(e) => Some(e),
+            ProfileError::TomlDe(e) => Some(e),
+            ProfileError::TomlSer(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ProfileError {
+    fn from(err: io::Error) -> Self {
+        ProfileError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ProfileError {
+    fn from(err: toml::de::Error) -> Self {
+        ProfileError::TomlDe(err)
+    }
+}
+
+impl From<toml::ser::Error> for ProfileError {
+    fn from(err: toml::ser::Error) -> Self {
+        ProfileError::TomlSer(err)
+    }
+}
+
+// =================================================================================================
+
+// Lifetime statistics of the local player, kept across games
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Profile {
+    games_played: u32,
+    wins: u32,
+    total_turns: u32,
+    // Number of times each building was built or upgraded, keyed by building name
+    buildings_built: BTreeMap<String, u32>,
+    total_playtime_secs: u64,
+}
+
+impl Profile {
+    // A missing profile file is not an error, it simply means a fresh profile
+    pub fn load(path: &Path) -> Result<Self, ProfileError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(toml::from_str(&content)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Profile::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProfileError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn record_game(&mut self, stats: &GameStats) {
+        self.games_played += 1;
+        if stats.is_won() {
+            self.wins += 1;
+        }
+        self.total_turns += stats.get_turns_played();
+        self.total_playtime_secs += stats.get_playtime_secs();
+        for (building, count) in stats.get_buildings_built() {
+            *self.buildings_built.entry(building.get_name().to_string()).or_default() += count;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Profile::default();
+    }
+
+    pub fn get_games_played(&self) -> u32 {
+        self.games_played
+    }
+
+    pub fn get_favorite_building(&self) -> Option<&str> {
+        // Ties go to the alphabetically first building so the result is stable
+        self.buildings_built
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .max_by(|(name_a, count_a), (name_b, count_b)| count_a.cmp(count_b).then(name_b.cmp(name_a)))
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn get_summary(&self) -> String {
+        format!(
+            "Games played: {} | Wins: {} | Total turns: {} | Playtime: {} | Favorite building: {}",
+            self.games_played,
+            self.wins,
+            self.total_turns,
+            format_playtime(self.total_playtime_secs),
+            self.get_favorite_building().unwrap_or("none"),
+        )
+    }
+}
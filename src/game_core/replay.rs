@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fmt, fs, io};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    TomlDe(toml::de::Error),
+    TomlSer(toml::ser::Error),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "Failed to access replay file: {}", e),
+            ReplayError::TomlDe(e) => write!(f, "Failed to parse replay file (TOML): {}", e),
+            ReplayError::TomlSer(e) => write!(f, "Failed to serialize replay: {}", e),
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::Io(e) => Some(e),
+            ReplayError::TomlDe(e) => Some(e),
+            ReplayError::TomlSer(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ReplayError {
+    fn from(err: toml::de::Error) -> Self {
+        ReplayError::TomlDe(err)
+    }
+}
+
+impl From<toml::ser::Error> for ReplayError {
+    fn from(err: toml::ser::Error) -> Self {
+        ReplayError::TomlSer(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayEntry {
+    // Turn the command was entered on
+    pub turn: u32,
+    pub command: String,
+    // Whether the command failed when it was played, loading with other configs may change that
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub failed: bool,
+}
+
+// Every command the player entered during a game, failed ones included. The game has no
+// randomness, so running them again on a new game recreates it exactly.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Replay {
+    // Real time the game was played, carried over when a saved game is loaded
+    playtime_secs: u64,
+    // Unix time of the last save, 0 for replays from before it was recorded
+    saved_at_secs: u64,
+    // Seed of the names generated for the game, fixed names when not set
+    name_seed: Option<u64>,
+    // Version of the game and checksums of the configs deciding costs and levels it was played
+    // with, empty for replays from before they were stamped
+    version: String,
+    config_checksums: BTreeMap<String, String>,
+    entries: Vec<ReplayEntry>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let content = fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let replay = Replay {
+            saved_at_secs: get_unix_secs(),
+            ..self.clone()
+        };
+        fs::write(path, toml::to_string(&replay)?)?;
+        Ok(())
+    }
+
+    // Real time since the game was saved, unknown for older saves
+    pub fn get_secs_since_saved(&self) -> Option<u64> {
+        (self.saved_at_secs > 0).then(|| get_unix_secs().saturating_sub(self.saved_at_secs))
+    }
+
+    pub fn record(&mut self, turn: u32, command: &str) {
+        self.entries.push(ReplayEntry { turn, command: command.to_string(), failed: false });
+    }
+
+    // The command is recorded before it runs, so one crashing the game is still in the replay
+    pub fn set_last_failed(&mut self) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.failed = true;
+        }
+    }
+
+    pub fn stamp(&mut self, version: &str, config_checksums: BTreeMap<String, String>) {
+        self.version = version.to_string();
+        self.config_checksums = config_checksums;
+    }
+
+    // What differs from the game the replay is loaded into, nothing for unstamped replays
+    pub fn get_compatibility_warnings(&self, current: &Replay) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.version.is_empty() && self.version != current.version {
+            warnings.push(format!(
+                "The game was saved with version {}, this is version {}.",
+                self.version, current.version,
+            ));
+        }
+        for (config, checksum) in &self.config_checksums {
+            if current.config_checksums.get(config).is_some_and(|current| current != checksum) {
+                warnings.push(format!(
+                    "The {} config changed since the game was saved, costs and levels may differ.",
+                    config,
+                ));
+            }
+        }
+        warnings
+    }
+
+    // Commands that failed in one replay but not in the other, for a game rebuilt from this one
+    pub fn count_desynced(&self, rebuilt: &Replay) -> usize {
+        self.entries
+            .iter()
+            .zip(&rebuilt.entries)
+            .filter(|(entry, rebuilt_entry)| entry.failed != rebuilt_entry.failed)
+            .count()
+    }
+
+    pub fn set_name_seed(&mut self, name_seed: Option<u64>) {
+        self.name_seed = name_seed;
+    }
+
+    pub fn get_name_seed(&self) -> Option<u64> {
+        self.name_seed
+    }
+
+    pub fn get_entries(&self) -> &[ReplayEntry] {
+        &self.entries
+    }
+
+    pub fn add_playtime(&mut self, secs: u64) {
+        self.playtime_secs += secs;
+    }
+
+    pub fn get_playtime_secs(&self) -> u64 {
+        self.playtime_secs
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+fn get_unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
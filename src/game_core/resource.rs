@@ -1,4 +1,6 @@
 use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 
 use serde::Deserialize;
 
@@ -8,6 +10,21 @@ pub enum Resource {
     Minerals,
     Gas,
     Energy,
+    // Not stored on planets but in the player's treasury
+    Credits,
+}
+
+impl Resource {
+    pub fn all() -> &'static [Resource] {
+        &[Resource::Energy, Resource::Minerals, Resource::Gas, Resource::Credits]
+    }
+
+    pub fn from_name(name: &str) -> Option<Resource> {
+        Self::all()
+            .iter()
+            .find(|resource| resource.to_string().eq_ignore_ascii_case(name))
+            .copied()
+    }
 }
 
 impl fmt::Display for Resource {
@@ -16,6 +33,131 @@ impl fmt::Display for Resource {
             Resource::Minerals => write!(f, "Minerals"),
             Resource::Gas => write!(f, "Gas"),
             Resource::Energy => write!(f, "Energy"),
+            Resource::Credits => write!(f, "Credits"),
+        }
+    }
+}
+
+// =================================================================================================
+
+// A quantity of a resource, stored, produced or paid. Arithmetic saturates instead of
+// overflowing, compare amounts to find out whether there is enough.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct ResourceAmount(u32);
+
+impl ResourceAmount {
+    pub const ZERO: ResourceAmount = ResourceAmount(0);
+
+    pub const fn new(amount: u32) -> Self {
+        ResourceAmount(amount)
+    }
+
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    // Share of the total in whole percent, 0 of nothing
+    pub fn get_percent_of(self, total: ResourceAmount) -> u32 {
+        match total.0 {
+            0 => 0,
+            total => (self.0 as u64 * 100 / total as u64) as u32,
+        }
+    }
+
+    // The percent of the amount, rounded down
+    pub fn get_scaled(self, percent: u32) -> ResourceAmount {
+        ResourceAmount::new((self.0 as u64 * percent as u64 / 100).min(u32::MAX as u64) as u32)
+    }
+
+    // Times the amount has to be made at the rate to add up to it, none at a rate of nothing
+    pub fn get_times_at(self, rate: ResourceAmount) -> Option<u32> {
+        match (self.0, rate.0) {
+            (0, _) => Some(0),
+            (_, 0) => None,
+            (amount, rate) => Some(amount.div_ceil(rate)),
+        }
+    }
+
+    // Short form for narrow places, exact below ten thousand, e.g. 9,500, 12.5k or 3.2M
+    pub fn get_abbreviated(self) -> String {
+        match self.0 {
+            0..10_000 => self.to_string(),
+            10_000..1_000_000 => format!("{:.1}k", self.0 as f64 / 1_000.0),
+            _ => format!("{:.1}M", self.0 as f64 / 1_000_000.0),
+        }
+    }
+}
+
+impl From<u32> for ResourceAmount {
+    fn from(amount: u32) -> Self {
+        ResourceAmount(amount)
+    }
+}
+
+impl From<ResourceAmount> for i64 {
+    fn from(amount: ResourceAmount) -> Self {
+        amount.0 as i64
+    }
+}
+
+impl Add for ResourceAmount {
+    type Output = ResourceAmount;
+
+    fn add(self, other: ResourceAmount) -> ResourceAmount {
+        ResourceAmount(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for ResourceAmount {
+    type Output = ResourceAmount;
+
+    fn sub(self, other: ResourceAmount) -> ResourceAmount {
+        ResourceAmount(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul<u32> for ResourceAmount {
+    type Output = ResourceAmount;
+
+    fn mul(self, factor: u32) -> ResourceAmount {
+        ResourceAmount(self.0.saturating_mul(factor))
+    }
+}
+
+impl AddAssign for ResourceAmount {
+    fn add_assign(&mut self, other: ResourceAmount) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for ResourceAmount {
+    fn sub_assign(&mut self, other: ResourceAmount) {
+        *self = *self - other;
+    }
+}
+
+impl Sum for ResourceAmount {
+    fn sum<I: Iterator<Item = ResourceAmount>>(iter: I) -> Self {
+        iter.fold(ResourceAmount::ZERO, Add::add)
+    }
+}
+
+// With thousands separators, e.g. 12,345
+impl fmt::Display for ResourceAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.0.to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (idx, digit) in digits.chars().enumerate() {
+            if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
         }
+        f.pad(&grouped)
     }
 }
@@ -0,0 +1,125 @@
+use std::fmt;
+
+use super::modifier::{Modifier, ModifierScope, ModifierSource, ModifierStat};
+use super::CommandError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFrequency {
+    Rare,
+    Normal,
+    Frequent,
+}
+
+impl EventFrequency {
+    pub fn all() -> [EventFrequency; 3] {
+        [EventFrequency::Rare, EventFrequency::Normal, EventFrequency::Frequent]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|frequency| frequency.to_string().eq_ignore_ascii_case(name))
+    }
+
+    // Turns between two events compared to the configured ones, in percent
+    pub fn get_interval_percent(&self) -> u32 {
+        match self {
+            EventFrequency::Rare => 200,
+            EventFrequency::Normal => 100,
+            EventFrequency::Frequent => 50,
+        }
+    }
+}
+
+impl fmt::Display for EventFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventFrequency::Rare => write!(f, "rare"),
+            EventFrequency::Normal => write!(f, "normal"),
+            EventFrequency::Frequent => write!(f, "frequent"),
+        }
+    }
+}
+
+// Rules a game is created with, changed with `rules <rule> <value>` on the first turn only.
+// Being set through commands, saved games and replays keep them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRules {
+    // TODO: Keep other empires from attacking and hide fleet commands once combat exists
+    pub builder_mode: bool,
+    pub double_production: bool,
+    // Storage buildings cost twice as much
+    pub expensive_storage: bool,
+    // How often merchants come by
+    pub event_frequency: EventFrequency,
+    // TODO: Add an energy grid rule once the galaxy map has lanes: planets within a few lanes of
+    // each other share surplus energy through Relay buildings, solved as a small network flow at
+    // the end of the turn before the stockpile logistics run
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules {
+            builder_mode: false,
+            double_production: false,
+            expensive_storage: false,
+            event_frequency: EventFrequency::Normal,
+        }
+    }
+}
+
+impl GameRules {
+    const RULES: [&'static str; 4] = ["builder_mode", "double_production", "expensive_storage", "event_frequency"];
+
+    pub fn set(&mut self, rule: &str, value: &str) -> Result<String, CommandError> {
+        let rule = rule.to_lowercase();
+        if rule == "event_frequency" {
+            self.event_frequency = EventFrequency::from_name(value).ok_or_else(|| {
+                let frequencies: Vec<String> = EventFrequency::all().iter().map(EventFrequency::to_string).collect();
+                CommandError::new(&format!("Unknown event frequency '{}'. Choose one of: {}.", value, frequencies.join(", ")))
+            })?;
+            return Ok(format!("Rule event_frequency set to {}.", self.event_frequency));
+        }
+
+        let enabled = match value.to_lowercase().as_str() {
+            "on" => true,
+            "off" => false,
+            _ => return Err(CommandError::new(&format!("Rule {} is either on or off.", rule))),
+        };
+        match rule.as_str() {
+            "builder_mode" => self.builder_mode = enabled,
+            "double_production" => self.double_production = enabled,
+            "expensive_storage" => self.expensive_storage = enabled,
+            _ => return Err(CommandError::new(&format!(
+                "Unknown rule '{}'. Rules: {}.", rule, Self::RULES.join(", ")
+            ))),
+        }
+        Ok(format!("Rule {} turned {}.", rule, if enabled { "on" } else { "off" }))
+    }
+
+    pub fn get_modifier_source() -> ModifierSource {
+        ModifierSource::Event("Rule: Double production".to_string())
+    }
+
+    pub fn get_modifiers(&self) -> Vec<Modifier> {
+        if !self.double_production {
+            return Vec::new();
+        }
+        vec![Modifier::new(Self::get_modifier_source(), ModifierScope::Empire, ModifierStat::Production(None), 100)]
+    }
+
+    // What storage buildings cost compared to their configured cost, in percent
+    pub fn get_storage_cost_percent(&self) -> u32 {
+        if self.expensive_storage { 200 } else { 100 }
+    }
+
+    pub fn get_listing(&self) -> String {
+        let switch = |enabled: bool| if enabled { "on" } else { "off" };
+        format!(
+            "Rules: builder_mode {}, double_production {}, expensive_storage {}, event_frequency {}. \
+            Change them on the first turn with 'rules <rule> <value>'.",
+            switch(self.builder_mode),
+            switch(self.double_production),
+            switch(self.expensive_storage),
+            self.event_frequency,
+        )
+    }
+}
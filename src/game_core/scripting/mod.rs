@@ -0,0 +1,5 @@
+pub mod script_api;
+pub mod script_engine;
+
+pub use script_api::{GameSnapshot, PlanetSnapshot, ScriptOutput};
+pub use script_engine::{ScriptEngine, ScriptError};
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine};
+
+use crate::game_core::{Resource, ResourceAmount};
+
+#[derive(Debug, Clone, Default)]
+pub struct PlanetSnapshot {
+    pub building_levels: HashMap<String, u8>,
+    pub production: HashMap<Resource, ResourceAmount>,
+    pub storage: HashMap<Resource, (ResourceAmount, ResourceAmount)>,
+    pub population: u32,
+    pub tax_rate: u8,
+    pub morale: u8,
+}
+
+// Read-only view of the game handed to scripts, so they can never mutate state directly
+#[derive(Debug, Clone, Default)]
+pub struct GameSnapshot {
+    pub turn: u32,
+    pub player_name: String,
+    pub credits: ResourceAmount,
+    // Ids of the artifacts the player holds
+    pub artifacts: Vec<String>,
+    pub planets: HashMap<String, PlanetSnapshot>,
+}
+
+#[derive(Debug, Default)]
+pub struct ScriptOutput {
+    pub logs: Vec<String>,
+    pub commands: Vec<String>,
+    // Artifact ids to grant the player, the only way to obtain them until anomalies exist
+    pub artifacts: Vec<String>,
+}
+
+// The `game` object scripts receive. Anything a script wants to change goes through
+// queued commands, which are executed by the game core like player input.
+#[derive(Debug, Clone)]
+pub struct ScriptApi {
+    snapshot: Rc<GameSnapshot>,
+    output: Rc<RefCell<ScriptOutput>>,
+}
+
+impl ScriptApi {
+    pub fn new(snapshot: GameSnapshot) -> Self {
+        ScriptApi {
+            snapshot: Rc::new(snapshot),
+            output: Rc::new(RefCell::new(ScriptOutput::default())),
+        }
+    }
+
+    pub fn take_output(&self) -> ScriptOutput {
+        self.output.take()
+    }
+
+    pub fn register(engine: &mut Engine) {
+        engine
+            .register_type_with_name::<ScriptApi>("Game")
+            .register_fn("turn", |api: &mut ScriptApi| api.snapshot.turn as i64)
+            .register_fn("player", |api: &mut ScriptApi| api.snapshot.player_name.clone())
+            .register_fn("credits", |api: &mut ScriptApi| i64::from(api.snapshot.credits))
+            .register_fn("artifacts", |api: &mut ScriptApi| {
+                api.snapshot.artifacts.iter().map(|id| Dynamic::from(id.clone())).collect::<Array>()
+            })
+            .register_fn("planets", |api: &mut ScriptApi| {
+                let mut names: Vec<&String> = api.snapshot.planets.keys().collect();
+                names.sort();
+                names.into_iter().map(|name| Dynamic::from(name.clone())).collect::<Array>()
+            })
+            .register_fn("level", |api: &mut ScriptApi, planet: &str, building: &str| {
+                api.get_planet(planet)
+                    .and_then(|planet| planet.building_levels.get(building))
+                    .map_or(0, |level| *level as i64)
+            })
+            .register_fn("population", |api: &mut ScriptApi, planet: &str| {
+                api.get_planet(planet).map_or(0, |planet| planet.population as i64)
+            })
+            .register_fn("tax_rate", |api: &mut ScriptApi, planet: &str| {
+                api.get_planet(planet).map_or(0, |planet| planet.tax_rate as i64)
+            })
+            .register_fn("morale", |api: &mut ScriptApi, planet: &str| {
+                api.get_planet(planet).map_or(0, |planet| planet.morale as i64)
+            })
+            .register_fn("resource", |api: &mut ScriptApi, planet: &str, resource: &str| {
+                i64::from(api.get_storage(planet, resource).0)
+            })
+            .register_fn("capacity", |api: &mut ScriptApi, planet: &str, resource: &str| {
+                i64::from(api.get_storage(planet, resource).1)
+            })
+            .register_fn("production", |api: &mut ScriptApi, planet: &str, resource: &str| {
+                let resource = Resource::from_name(resource);
+                api.get_planet(planet)
+                    .zip(resource)
+                    .and_then(|(planet, resource)| planet.production.get(&resource))
+                    .map_or(0, |rate| i64::from(*rate))
+            })
+            .register_fn("log", |api: &mut ScriptApi, message: &str| {
+                api.output.borrow_mut().logs.push(message.to_string());
+            })
+            .register_fn("run", |api: &mut ScriptApi, command: &str| {
+                api.output.borrow_mut().commands.push(command.to_string());
+            })
+            .register_fn("grant_artifact", |api: &mut ScriptApi, artifact: &str| {
+                api.output.borrow_mut().artifacts.push(artifact.to_string());
+            });
+    }
+
+    fn get_planet(&self, planet: &str) -> Option<&PlanetSnapshot> {
+        self.snapshot.planets.get(planet)
+    }
+
+    fn get_storage(&self, planet: &str, resource: &str) -> (ResourceAmount, ResourceAmount) {
+        let resource = Resource::from_name(resource);
+        self.get_planet(planet)
+            .zip(resource)
+            .and_then(|(planet, resource)| planet.storage.get(&resource))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
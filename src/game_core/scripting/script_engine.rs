@@ -0,0 +1,314 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::rc::Rc;
+use std::{fmt, fs, io};
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, FuncArgs, Scope, AST};
+
+use crate::game_core::event::GameEvent;
+
+use super::script_api::{GameSnapshot, ScriptApi, ScriptOutput};
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(io::Error),
+    Compile { script: String, message: String },
+    Runtime { script: String, message: String },
+    CommandConflict(String),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Io(e) => write!(f, "Failed to read scripts: {}", e),
+            ScriptError::Compile { script, message } => write!(
+                f, "Failed to compile script '{}': {}", script, message
+            ),
+            ScriptError::Runtime { script, message } => write!(
+                f, "Script '{}' failed: {}", script, message
+            ),
+            ScriptError::CommandConflict(name) => write!(
+                f, "Script command '{}' is already defined", name
+            ),
+        }
+    }
+}
+
+impl Error for ScriptError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScriptError::Io(e) => Some(e),
+            ScriptError::Compile { .. } => None,
+            ScriptError::Runtime { .. } => None,
+            ScriptError::CommandConflict(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScriptError {
+    fn from(err: io::Error) -> Self {
+        ScriptError::Io(err)
+    }
+}
+
+// =================================================================================================
+
+// Keeps a runaway script from freezing the game
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+
+const SCRIPT_EXTENSION: &str = "rhai";
+
+// A command declared by a script file through `register_command`
+#[derive(Debug, Clone)]
+pub struct ScriptCommand {
+    pub name: String,
+    pub description: String,
+    pub expected_args: usize,
+    pub handler: String,
+}
+
+impl ScriptCommand {
+    // Command body calling the declared handler function
+    pub fn get_body(&self) -> String {
+        format!("{}(game, args)", self.handler)
+    }
+}
+
+#[derive(Debug)]
+struct LoadedScript {
+    name: String,
+    ast: AST,
+}
+
+// Command name, description, expected args and handler function as passed to `register_command`
+type CommandRegistration = (String, String, usize, String);
+
+pub struct ScriptEngine {
+    engine: Engine,
+    scripts: Vec<LoadedScript>,
+    // Functions of all loaded scripts, callable from command bodies
+    library: AST,
+    declared_commands: Vec<ScriptCommand>,
+    // Compiled command bodies keyed by command name and argument count
+    commands: HashMap<(String, usize), AST>,
+    registered: Rc<RefCell<Vec<CommandRegistration>>>,
+    printed: Rc<RefCell<Vec<String>>>,
+}
+
+impl ScriptEngine {
+    pub fn new() -> Self {
+        let registered: Rc<RefCell<Vec<CommandRegistration>>> = Rc::default();
+        let printed: Rc<RefCell<Vec<String>>> = Rc::default();
+
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+        engine.disable_symbol("eval");
+
+        // print/debug would write straight into the terminal, route them to the game log instead
+        let print_target = printed.clone();
+        engine.on_print(move |text| print_target.borrow_mut().push(text.to_string()));
+        let debug_target = printed.clone();
+        engine.on_debug(move |text, _, _| debug_target.borrow_mut().push(text.to_string()));
+
+        ScriptApi::register(&mut engine);
+
+        let registration_target = registered.clone();
+        engine.register_fn(
+            "register_command",
+            move |name: &str, description: &str, expected_args: i64, handler: &str| {
+                registration_target.borrow_mut().push((
+                    name.to_lowercase(),
+                    description.to_string(),
+                    expected_args.max(0) as usize,
+                    handler.to_string(),
+                ));
+            },
+        );
+
+        ScriptEngine {
+            engine,
+            scripts: Vec::new(),
+            library: AST::empty(),
+            declared_commands: Vec::new(),
+            commands: HashMap::new(),
+            registered,
+            printed,
+        }
+    }
+
+    // Loads every .rhai file in the directory. Scripts declare commands by calling
+    // `register_command(name, description, expected_args, handler)` at the top level
+    // and react to events by defining `on_turn_end(game, turn)` or
+    // `on_building_complete(game, planet, building, level)`. Function names are shared
+    // between scripts, so commands may call helpers from any of them.
+    pub fn load(scripts_dir: &Path) -> Result<Self, ScriptError> {
+        let mut script_engine = Self::new();
+        if !scripts_dir.is_dir() {
+            return Ok(script_engine);
+        }
+
+        let mut paths: Vec<_> = fs::read_dir(scripts_dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let source = fs::read_to_string(&path)?;
+            script_engine.add_script(&name, &source)?;
+        }
+
+        Ok(script_engine)
+    }
+
+    pub fn add_script(&mut self, name: &str, source: &str) -> Result<(), ScriptError> {
+        let ast = self.engine.compile(source).map_err(|err| ScriptError::Compile {
+            script: name.to_string(),
+            message: err.to_string(),
+        })?;
+
+        // Commands are registered while the top level of the script runs
+        let result = self.engine.run_ast(&ast);
+        let registered = self.registered.take();
+        self.printed.take();
+        result.map_err(|err| ScriptError::Runtime {
+            script: name.to_string(),
+            message: err.to_string(),
+        })?;
+
+        for (command_name, description, expected_args, handler) in registered {
+            if self.declared_commands.iter().any(|command| command.name == command_name) {
+                return Err(ScriptError::CommandConflict(command_name));
+            }
+            self.declared_commands.push(ScriptCommand {
+                name: command_name,
+                description,
+                expected_args,
+                handler,
+            });
+        }
+
+        self.library.combine(ast.clone_functions_only());
+        self.scripts.push(LoadedScript { name: name.to_string(), ast });
+        Ok(())
+    }
+
+    pub fn get_script_count(&self) -> usize {
+        self.scripts.len()
+    }
+
+    pub fn get_declared_commands(&self) -> &[ScriptCommand] {
+        &self.declared_commands
+    }
+
+    // Compiles the body of a scripted command. The body sees `game` and `args` (an array of
+    // strings) and may call any function defined in the loaded scripts.
+    pub fn add_command(&mut self, command_name: &str, expected_args: usize, body: &str) -> Result<(), ScriptError> {
+        let ast = self.engine.compile(body).map_err(|err| ScriptError::Compile {
+            script: command_name.to_string(),
+            message: err.to_string(),
+        })?;
+
+        self.commands.insert((command_name.to_string(), expected_args), ast);
+        Ok(())
+    }
+
+    // Runs a scripted command. A string the body evaluates to becomes the command result message.
+    pub fn run_command(
+        &self,
+        command_name: &str,
+        args: &[String],
+        snapshot: GameSnapshot,
+    ) -> Result<(Option<String>, ScriptOutput), ScriptError> {
+        let body = self.commands.get(&(command_name.to_string(), args.len())).ok_or_else(|| ScriptError::Runtime {
+            script: command_name.to_string(),
+            message: "command has no script".to_string(),
+        })?;
+
+        let api = ScriptApi::new(snapshot);
+        let script_args: Array = args.iter().map(|arg| Dynamic::from(arg.clone())).collect();
+        let mut scope = Scope::new();
+        scope.push("game", api.clone());
+        scope.push("args", script_args);
+
+        let result = self.engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.library.merge(body))
+            .map_err(|err| ScriptError::Runtime {
+                script: command_name.to_string(),
+                message: err.to_string(),
+            })?;
+
+        let message = result.into_string().ok().filter(|message| !message.is_empty());
+        Ok((message, self.collect_output(&api)))
+    }
+
+    // Calls the matching hook in every script that defines it. A failing script does not
+    // prevent the others from running, its error is returned alongside the output.
+    pub fn handle_event(
+        &self,
+        event: &GameEvent,
+        snapshot: GameSnapshot,
+    ) -> (ScriptOutput, Vec<ScriptError>) {
+        let api = ScriptApi::new(snapshot);
+        let mut errors = Vec::new();
+
+        for script in &self.scripts {
+            let result = match event {
+                GameEvent::TurnEnded { turn } => {
+                    self.call_hook(script, "on_turn_end", (api.clone(), *turn as i64))
+                }
+                GameEvent::BuildingCompleted { planet, building, level } => self.call_hook(
+                    script,
+                    "on_building_complete",
+                    (api.clone(), planet.clone(), building.get_name().to_string(), *level as i64),
+                ),
+                GameEvent::CommandExecuted { .. } => Ok(()),
+            };
+
+            if let Err(err) = result {
+                errors.push(err);
+            }
+        }
+
+        (self.collect_output(&api), errors)
+    }
+
+    fn call_hook(&self, script: &LoadedScript, hook: &str, args: impl FuncArgs) -> Result<(), ScriptError> {
+        if !script.ast.iter_functions().any(|function| function.name == hook) {
+            return Ok(());
+        }
+
+        self.engine
+            .call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false),
+                &mut Scope::new(),
+                &script.ast,
+                hook,
+                args,
+            )
+            .map(|_| ())
+            .map_err(|err| ScriptError::Runtime {
+                script: script.name.clone(),
+                message: err.to_string(),
+            })
+    }
+
+    fn collect_output(&self, api: &ScriptApi) -> ScriptOutput {
+        let mut output = api.take_output();
+        let mut printed = self.printed.take();
+        printed.append(&mut output.logs);
+        output.logs = printed;
+        output
+    }
+}
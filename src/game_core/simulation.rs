@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use super::bot::PlayerView;
+use super::{AiConfig, BotRunner, BuildingsConfig, BuildingTypeId, EconomyBot, GameCore, GameCoreError, GameDataPaths, Resource, ResourceAmount};
+
+const CURVE_RESOURCES: [Resource; 4] = [Resource::Energy, Resource::Minerals, Resource::Gas, Resource::Credits];
+
+// How a simulated game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationOutcome {
+    Won,
+    // Quit before the turn limit
+    Ended,
+    TurnLimit,
+}
+
+impl SimulationOutcome {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SimulationOutcome::Won => "won",
+            SimulationOutcome::Ended => "ended",
+            SimulationOutcome::TurnLimit => "turn_limit",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulatedGame {
+    pub outcome: SimulationOutcome,
+    // Turn each building first reached its maximum level on any planet
+    pub turns_to_max: HashMap<BuildingTypeId, u32>,
+    // Stored amount of each resource across all planets at the start of every turn, credits
+    // being the player's purse
+    pub resource_curves: HashMap<Resource, Vec<u32>>,
+}
+
+// AI-only games played without an interface, summarised to guide the tuning of buildings.toml.
+// Every game seats a differently seeded economy AI, taking turns through the personalities of
+// ai.toml, so the same seeds give the same report.
+// TODO: Seat several AIs per game and count their victories once there are more players and
+// victory conditions
+pub struct BalanceSimulation {
+    paths: GameDataPaths,
+    max_levels: HashMap<BuildingTypeId, u8>,
+    ai_config: AiConfig,
+}
+
+impl BalanceSimulation {
+    pub fn new(paths: &GameDataPaths) -> Result<Self, GameCoreError> {
+        let buildings_config = BuildingsConfig::load(GameDataPaths::get_or(&paths.buildings, "data/buildings.toml"))?;
+        let max_levels = BuildingTypeId::all()
+            .iter()
+            .filter_map(|&id| buildings_config.buildings.get(id.get_name()).map(|config| (id, config.get_max_level())))
+            .collect();
+        let ai_config = AiConfig::load(GameDataPaths::get_or(&paths.ai, "data/ai.toml"))?;
+        Ok(BalanceSimulation { paths: paths.clone(), max_levels, ai_config })
+    }
+
+    pub fn run(&self, games: u64, turns: u32) -> Result<BalanceReport, GameCoreError> {
+        let games = (0..games)
+            .map(|seed| self.play_game(seed, turns))
+            .collect::<Result<Vec<SimulatedGame>, GameCoreError>>()?;
+        Ok(BalanceReport { turns, games })
+    }
+
+    fn play_game(&self, seed: u64, turns: u32) -> Result<SimulatedGame, GameCoreError> {
+        let mut game_core = GameCore::new_with_name_seed(&self.paths, seed)?;
+        let mut bot_runner = BotRunner::new();
+        let personality = self.ai_config.get_personality_for_seat(seed);
+        bot_runner.seat(
+            &game_core.get_current_player_name(),
+            Box::new(EconomyBot::with_seed(&self.ai_config, personality, seed)),
+        );
+
+        let mut game = SimulatedGame {
+            outcome: SimulationOutcome::TurnLimit,
+            turns_to_max: HashMap::new(),
+            resource_curves: HashMap::new(),
+        };
+        while game_core.is_running() && game_core.get_current_turn() <= turns {
+            if let Some(view) = game_core.get_player_view(&game_core.get_current_player_name()) {
+                self.record_turn(&mut game, &view);
+            }
+
+            if bot_runner.play(&mut game_core).is_empty() {
+                break;
+            }
+        }
+
+        if game_core.is_won() {
+            game.outcome = SimulationOutcome::Won;
+        } else if !game_core.is_running() {
+            game.outcome = SimulationOutcome::Ended;
+        }
+        Ok(game)
+    }
+
+    fn record_turn(&self, game: &mut SimulatedGame, view: &PlayerView) {
+        for resource in CURVE_RESOURCES {
+            let amount = match resource {
+                Resource::Credits => view.credits.get(),
+                _ => view.planets
+                    .iter()
+                    .map(|planet| planet.storage.get(&resource).map_or(ResourceAmount::ZERO, |(amount, _)| *amount))
+                    .sum::<ResourceAmount>()
+                    .get(),
+            };
+            game.resource_curves.entry(resource).or_default().push(amount);
+        }
+
+        for (&building_id, &max_level) in &self.max_levels {
+            let name = building_id.to_string();
+            let maxed = view.planets
+                .iter()
+                .flat_map(|planet| planet.buildings.iter())
+                .any(|(building, level)| *building == name && *level >= max_level);
+            if maxed {
+                game.turns_to_max.entry(building_id).or_insert(view.turn);
+            }
+        }
+    }
+}
+
+// =================================================================================================
+
+#[derive(Debug, Clone)]
+pub struct BalanceReport {
+    pub turns: u32,
+    pub games: Vec<SimulatedGame>,
+}
+
+impl BalanceReport {
+    // Average turn a building got maxed in the games it did, and in how many of them
+    pub fn get_turns_to_max(&self) -> Vec<(BuildingTypeId, Option<f64>, usize)> {
+        BuildingTypeId::all()
+            .iter()
+            .map(|&building_id| {
+                let turns: Vec<u32> = self.games.iter().filter_map(|game| game.turns_to_max.get(&building_id).copied()).collect();
+                let average = (!turns.is_empty()).then(|| turns.iter().sum::<u32>() as f64 / turns.len() as f64);
+                (building_id, average, turns.len())
+            })
+            .collect()
+    }
+
+    // Amount of the resource averaged over the games still running at each turn
+    pub fn get_resource_curve(&self, resource: Resource) -> Vec<f64> {
+        (0..self.turns as usize)
+            .map_while(|turn| {
+                let amounts: Vec<u32> = self.games
+                    .iter()
+                    .filter_map(|game| game.resource_curves.get(&resource).and_then(|curve| curve.get(turn).copied()))
+                    .collect();
+                (!amounts.is_empty()).then(|| amounts.iter().sum::<u32>() as f64 / amounts.len() as f64)
+            })
+            .collect()
+    }
+
+    pub fn get_outcomes(&self) -> Vec<(SimulationOutcome, usize)> {
+        [SimulationOutcome::Won, SimulationOutcome::Ended, SimulationOutcome::TurnLimit]
+            .into_iter()
+            .map(|outcome| (outcome, self.games.iter().filter(|game| game.outcome == outcome).count()))
+            .collect()
+    }
+
+    // One row per value, `metric,key,turn,value`
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec!["metric,key,turn,value".to_string()];
+        for (building_id, average, reached) in self.get_turns_to_max() {
+            let average = average.map_or(String::new(), |average| format!("{:.1}", average));
+            lines.push(format!("turns_to_max,{},,{}", building_id.get_name(), average));
+            lines.push(format!("games_maxed,{},,{}", building_id.get_name(), reached));
+        }
+        for resource in CURVE_RESOURCES {
+            for (turn, amount) in self.get_resource_curve(resource).iter().enumerate() {
+                lines.push(format!("resource_curve,{},{},{:.1}", resource, turn + 1, amount));
+            }
+        }
+        for (outcome, count) in self.get_outcomes() {
+            lines.push(format!("outcome,{},,{}", outcome.get_name(), count));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    pub fn to_json(&self) -> String {
+        let turns_to_max: Vec<String> = self.get_turns_to_max()
+            .iter()
+            .map(|(building_id, average, reached)| {
+                let average = average.map_or("null".to_string(), |average| format!("{:.1}", average));
+                format!("\"{}\":{{\"average_turn\":{},\"games_maxed\":{}}}", building_id.get_name(), average, reached)
+            })
+            .collect();
+        let curves: Vec<String> = CURVE_RESOURCES
+            .iter()
+            .map(|&resource| {
+                let amounts: Vec<String> = self.get_resource_curve(resource)
+                    .iter()
+                    .map(|amount| format!("{:.1}", amount))
+                    .collect();
+                format!("\"{}\":[{}]", resource, amounts.join(","))
+            })
+            .collect();
+        let outcomes: Vec<String> = self.get_outcomes()
+            .iter()
+            .map(|(outcome, count)| format!("\"{}\":{}", outcome.get_name(), count))
+            .collect();
+
+        format!(
+            "{{\"games\":{},\"turns\":{},\"turns_to_max\":{{{}}},\"resource_curves\":{{{}}},\"outcomes\":{{{}}}}}\n",
+            self.games.len(),
+            self.turns,
+            turns_to_max.join(","),
+            curves.join(","),
+            outcomes.join(","),
+        )
+    }
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+use super::planet::PlanetId;
+use super::Resource;
+
+#[derive(Debug)]
+pub enum SpecializationsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateSpecialization(String),
+}
+
+impl fmt::Display for SpecializationsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecializationsConfigError::Io(e) => write!(f, "Failed to read specializations file: {}", e),
+            SpecializationsConfigError::Toml(e) => write!(f, "Failed to parse specializations file (TOML): {}", e),
+            SpecializationsConfigError::DuplicateSpecialization(id) => {
+                write!(f, "Specialization '{}' is defined twice", id)
+            }
+        }
+    }
+}
+
+impl Error for SpecializationsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpecializationsConfigError::Io(e) => Some(e),
+            SpecializationsConfigError::Toml(e) => Some(e),
+            SpecializationsConfigError::DuplicateSpecialization(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for SpecializationsConfigError {
+    fn from(err: io::Error) -> Self {
+        SpecializationsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for SpecializationsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        SpecializationsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpecializationConfig {
+    pub id: String,
+    pub name: String,
+    // Resource the AI favors when it builds on the planet
+    pub focus: Option<Resource>,
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+impl SpecializationConfig {
+    pub fn get_modifier_source(&self, planet: &PlanetId) -> ModifierSource {
+        ModifierSource::Specialization { name: self.name.clone(), planet: planet.clone() }
+    }
+
+    pub fn get_modifiers(&self, planet: &PlanetId) -> Vec<Modifier> {
+        self.modifiers
+            .iter()
+            .map(|modifier| {
+                modifier.to_modifier(self.get_modifier_source(planet), ModifierScope::Planet(planet.clone()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpecializationsConfig {
+    // Turns after a designation before the planet can be designated again
+    pub respec_cooldown_turns: u32,
+    pub specializations: Vec<SpecializationConfig>,
+}
+
+impl SpecializationsConfig {
+    pub fn load(path: &Path) -> Result<Self, SpecializationsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: SpecializationsConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for specialization in &config.specializations {
+            let id = specialization.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(SpecializationsConfigError::DuplicateSpecialization(specialization.id.clone()));
+            }
+            seen.push(id);
+        }
+
+        Ok(config)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&SpecializationConfig> {
+        self.specializations.iter().find(|specialization| specialization.id.eq_ignore_ascii_case(id))
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use super::event::GameEvent;
+use super::{BuildingTypeId, Resource, ResourceAmount};
+
+// A major moment of the game, kept for the timeline
+#[derive(Debug, Clone)]
+pub struct Milestone {
+    pub turn: u32,
+    pub text: String,
+}
+
+// An empire at the end of a turn, kept for the statistics export
+#[derive(Debug, Clone)]
+pub struct TurnRecord {
+    pub turn: u32,
+    pub player: String,
+    pub credits: ResourceAmount,
+    // Stored on all planets together
+    pub resources: Vec<(Resource, ResourceAmount)>,
+    // Net production per turn of all planets together, credits after upkeep
+    pub production: Vec<(Resource, i64)>,
+    // Scored like the daily challenge
+    pub score: u32,
+    pub planet_count: usize,
+}
+
+// Statistics of the game in progress, aggregated from game events
+// TODO: Export them together with the timeline once the game has an end-of-game statistics screen
+#[derive(Debug, Clone, Default)]
+pub struct GameStats {
+    turns_played: u32,
+    buildings_built: HashMap<BuildingTypeId, u32>,
+    // TODO: Set once the game has victory conditions, counting shared victories of allied teams
+    won: bool,
+    // Set when the player surrendered
+    defeated: bool,
+    // In the order they happened
+    timeline: Vec<Milestone>,
+    // Real time spent playing, including sessions before the game was loaded
+    playtime_secs: u64,
+    // Real time each turn took in this session, by turn number
+    turn_times: Vec<(u32, u64)>,
+}
+
+impl GameStats {
+    pub fn record(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::TurnEnded { .. } => self.turns_played += 1,
+            GameEvent::BuildingCompleted { building, .. } => {
+                *self.buildings_built.entry(*building).or_default() += 1;
+            }
+            GameEvent::CommandExecuted { .. } => {}
+        }
+    }
+
+    pub fn get_turns_played(&self) -> u32 {
+        self.turns_played
+    }
+
+    pub fn get_buildings_built(&self) -> &HashMap<BuildingTypeId, u32> {
+        &self.buildings_built
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    pub fn set_defeated(&mut self) {
+        self.defeated = true;
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.defeated
+    }
+
+    pub fn add_milestone(&mut self, turn: u32, text: &str) {
+        self.timeline.push(Milestone { turn, text: text.to_string() });
+    }
+
+    pub fn get_timeline(&self) -> &[Milestone] {
+        &self.timeline
+    }
+
+    pub fn add_playtime(&mut self, secs: u64) {
+        self.playtime_secs += secs;
+    }
+
+    pub fn get_playtime_secs(&self) -> u64 {
+        self.playtime_secs
+    }
+
+    pub fn record_turn_time(&mut self, turn: u32, secs: u64) {
+        self.turn_times.push((turn, secs));
+    }
+
+    // The turn that took the most real time, the earliest one on ties
+    pub fn get_longest_turn(&self) -> Option<(u32, u64)> {
+        self.turn_times.iter().copied().rev().max_by_key(|(_, secs)| *secs)
+    }
+}
+
+// Formats seconds as h:mm:ss, or m:ss below an hour
+pub fn format_playtime(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
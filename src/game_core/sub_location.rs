@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::building::building::Building;
+use super::{BuildingType, BuildingTypeId, BuildingsConfig, BuildingsConfigError, Resource, ResourceAmount};
+
+#[derive(Debug)]
+pub enum SubLocationsConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateKind(String),
+    UnknownKind(String),
+    UnknownBuilding { kind: String, building: String },
+}
+
+impl fmt::Display for SubLocationsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubLocationsConfigError::Io(e) => write!(f, "Failed to read sub-locations file: {}", e),
+            SubLocationsConfigError::Toml(e) => write!(f, "Failed to parse sub-locations file (TOML): {}", e),
+            SubLocationsConfigError::DuplicateKind(id) => write!(f, "Sub-location '{}' is defined twice", id),
+            SubLocationsConfigError::UnknownKind(id) => write!(f, "Starting sub-location '{}' is not defined", id),
+            SubLocationsConfigError::UnknownBuilding { kind, building } => {
+                write!(f, "Sub-location '{}' allows unknown building '{}'", kind, building)
+            }
+        }
+    }
+}
+
+impl Error for SubLocationsConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SubLocationsConfigError::Io(e) => Some(e),
+            SubLocationsConfigError::Toml(e) => Some(e),
+            SubLocationsConfigError::DuplicateKind(_) => None,
+            SubLocationsConfigError::UnknownKind(_) => None,
+            SubLocationsConfigError::UnknownBuilding { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for SubLocationsConfigError {
+    fn from(err: io::Error) -> Self {
+        SubLocationsConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for SubLocationsConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        SubLocationsConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SubLocationKindConfig {
+    // Also the name the body is addressed by, e.g. Planet1/Moon
+    pub id: String,
+    pub name: String,
+    // Building ids the body can host
+    pub buildings: Vec<String>,
+    // The planet's orbit, its buildings can't be built on the surface
+    #[serde(default)]
+    pub orbital: bool,
+    // How many different buildings can be built or queued, any number if not set
+    pub slots: Option<u32>,
+}
+
+impl SubLocationKindConfig {
+    pub fn allows(&self, building_id: BuildingTypeId) -> bool {
+        self.buildings.iter().any(|building| building == building_id.get_name())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SubLocationsConfig {
+    // Kinds of bodies every new colony starts with
+    pub starting: Vec<String>,
+    pub kinds: Vec<SubLocationKindConfig>,
+}
+
+impl SubLocationsConfig {
+    pub fn load(path: &Path) -> Result<Self, SubLocationsConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: SubLocationsConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for kind in &config.kinds {
+            let id = kind.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(SubLocationsConfigError::DuplicateKind(kind.id.clone()));
+            }
+            seen.push(id);
+
+            if let Some(building) = kind.buildings
+                .iter()
+                .find(|building| !BuildingTypeId::all().iter().any(|id| id.get_name() == building.as_str()))
+            {
+                return Err(SubLocationsConfigError::UnknownBuilding {
+                    kind: kind.id.clone(),
+                    building: building.clone(),
+                });
+            }
+        }
+
+        if let Some(kind) = config.starting.iter().find(|kind| config.find(kind).is_none()) {
+            return Err(SubLocationsConfigError::UnknownKind(kind.clone()));
+        }
+
+        Ok(config)
+    }
+
+    pub fn find(&self, id: &str) -> Option<&SubLocationKindConfig> {
+        self.kinds.iter().find(|kind| kind.id.eq_ignore_ascii_case(id))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SubLocationStatus {
+    pub id: String,
+    pub name: String,
+    pub buildings: Vec<(String, u8)>,
+    // Used and total slots, if limited
+    pub slots: Option<(u32, u32)>,
+}
+
+// A moon or asteroid belt attached to a planet. It hosts a few extraction buildings of its own
+// and delivers what they produce to the planet's storage.
+// TODO: Let constructor ships build stations, lane gates and mining outposts at map locations away
+// from planets once there are fleets and a galaxy map, as sites like these that need the ship in
+// place instead of a planet to belong to
+#[derive(Debug, Clone)]
+pub struct SubLocation {
+    kind: SubLocationKindConfig,
+    buildings: HashMap<BuildingTypeId, BuildingType>,
+}
+
+impl SubLocation {
+    pub fn new(kind: &SubLocationKindConfig, buildings_config: &BuildingsConfig) -> Result<Self, BuildingsConfigError> {
+        let mut buildings = HashMap::new();
+        for &building_id in BuildingTypeId::all().iter().filter(|&&id| kind.allows(id)) {
+            let building_config = buildings_config.buildings.get(building_id.get_name()).ok_or_else(|| {
+                BuildingsConfigError::BuildingNotFound(format!("Building config for {} not found", building_id.get_name()))
+            })?;
+            buildings.insert(building_id, BuildingType::new_zero(building_id, building_config.clone()));
+        }
+
+        Ok(SubLocation { kind: kind.clone(), buildings })
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.kind.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.kind.name
+    }
+
+    pub fn allows(&self, building_id: BuildingTypeId) -> bool {
+        self.kind.allows(building_id)
+    }
+
+    pub fn is_orbital(&self) -> bool {
+        self.kind.orbital
+    }
+
+    pub fn get_slots(&self) -> Option<u32> {
+        self.kind.slots
+    }
+
+    // Buildings that have a level built, those queued are counted by the planet
+    pub fn get_built(&self) -> Vec<BuildingTypeId> {
+        self.buildings
+            .iter()
+            .filter(|(_, building)| building.get_level() > 0)
+            .map(|(&building_id, _)| building_id)
+            .collect()
+    }
+
+    pub fn get_building_level(&self, building_id: BuildingTypeId) -> u8 {
+        self.buildings.get(&building_id).map_or(0, |building| building.get_level())
+    }
+
+    pub fn get_building_levels(&self) -> u32 {
+        self.buildings.values().map(|building| building.get_level() as u32).sum()
+    }
+
+    pub fn get_mut_building(&mut self, building_id: BuildingTypeId) -> Option<&mut BuildingType> {
+        self.buildings.get_mut(&building_id)
+    }
+
+    // Bodies start unworked, so their buildings only produce once they have been built
+    pub fn get_production_rates(&self) -> HashMap<Resource, ResourceAmount> {
+        let mut rates = HashMap::new();
+        for (resource, rate) in self.get_producers().into_iter().filter_map(|building| building.get_production()) {
+            *rates.entry(resource).or_default() += rate;
+        }
+        rates
+    }
+
+    // Built buildings that produce something
+    pub fn get_producers(&self) -> Vec<&BuildingType> {
+        self.buildings
+            .values()
+            .filter(|building| building.get_level() > 0 && building.get_production().is_some())
+            .collect()
+    }
+
+    pub fn get_status(&self, used_slots: u32) -> SubLocationStatus {
+        let mut buildings: Vec<(String, u8)> = self.buildings
+            .values()
+            .map(|building| (building.get_name().to_string(), building.get_level()))
+            .collect();
+        buildings.sort();
+
+        SubLocationStatus {
+            id: self.kind.id.clone(),
+            name: self.kind.name.clone(),
+            buildings,
+            slots: self.kind.slots.map(|total| (used_slots, total)),
+        }
+    }
+}
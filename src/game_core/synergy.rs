@@ -0,0 +1,134 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::modifier::{Modifier, ModifierConfig, ModifierScope, ModifierSource};
+use super::planet::PlanetId;
+use super::{BuildingTypeId, Planet};
+
+#[derive(Debug)]
+pub enum SynergiesConfigError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    DuplicateSynergy(String),
+    UnknownBuilding { synergy: String, building: String },
+}
+
+impl fmt::Display for SynergiesConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SynergiesConfigError::Io(e) => write!(f, "Failed to read synergies file: {}", e),
+            SynergiesConfigError::Toml(e) => write!(f, "Failed to parse synergies file (TOML): {}", e),
+            SynergiesConfigError::DuplicateSynergy(id) => write!(f, "Synergy '{}' is defined twice", id),
+            SynergiesConfigError::UnknownBuilding { synergy, building } => {
+                write!(f, "Synergy '{}' requires unknown building '{}'", synergy, building)
+            }
+        }
+    }
+}
+
+impl Error for SynergiesConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SynergiesConfigError::Io(e) => Some(e),
+            SynergiesConfigError::Toml(e) => Some(e),
+            SynergiesConfigError::DuplicateSynergy(_) => None,
+            SynergiesConfigError::UnknownBuilding { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for SynergiesConfigError {
+    fn from(err: io::Error) -> Self {
+        SynergiesConfigError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for SynergiesConfigError {
+    fn from(err: toml::de::Error) -> Self {
+        SynergiesConfigError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// Buildings that work better together. A synergy is active on a planet while all of the
+// buildings it requires stand on its surface, and grants its modifiers to that planet only.
+// TODO: Require the buildings to be adjacent once planets have a tile layout
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SynergyConfig {
+    pub id: String,
+    pub name: String,
+    // Building ids
+    pub requires: Vec<String>,
+    // Level every required building needs at least
+    #[serde(default = "SynergyConfig::default_min_level")]
+    pub min_level: u8,
+    pub modifiers: Vec<ModifierConfig>,
+}
+
+impl SynergyConfig {
+    fn default_min_level() -> u8 {
+        1
+    }
+
+    pub fn get_modifier_source(&self, planet: &PlanetId) -> ModifierSource {
+        ModifierSource::Synergy { name: self.name.clone(), planet: planet.clone() }
+    }
+
+    pub fn get_modifiers(&self, planet: &PlanetId) -> Vec<Modifier> {
+        self.modifiers
+            .iter()
+            .map(|modifier| {
+                modifier.to_modifier(self.get_modifier_source(planet), ModifierScope::Planet(planet.clone()))
+            })
+            .collect()
+    }
+
+    // Required buildings below the minimum level on the planet, none if the synergy is active
+    pub fn get_missing(&self, planet: &Planet) -> Vec<BuildingTypeId> {
+        BuildingTypeId::all()
+            .iter()
+            .filter(|id| self.requires.iter().any(|building| building == id.get_name()))
+            .filter(|&&id| planet.get_building_level(id) < self.min_level)
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SynergiesConfig {
+    pub synergies: Vec<SynergyConfig>,
+}
+
+impl SynergiesConfig {
+    pub fn load(path: &Path) -> Result<Self, SynergiesConfigError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: SynergiesConfig = toml::from_str(&toml_content)?;
+
+        let mut seen = Vec::new();
+        for synergy in &config.synergies {
+            let id = synergy.id.to_lowercase();
+            if seen.contains(&id) {
+                return Err(SynergiesConfigError::DuplicateSynergy(synergy.id.clone()));
+            }
+            seen.push(id);
+
+            if let Some(building) = synergy.requires
+                .iter()
+                .find(|building| !BuildingTypeId::all().iter().any(|id| id.get_name() == building.as_str()))
+            {
+                return Err(SynergiesConfigError::UnknownBuilding {
+                    synergy: synergy.id.clone(),
+                    building: building.clone(),
+                });
+            }
+        }
+
+        Ok(config)
+    }
+}
@@ -14,8 +14,4 @@ impl Turn {
     pub fn next_turn(&mut self) {
         self.turn_number += 1;
     }
-
-    pub fn reset_turn(&mut self) {
-        self.turn_number = 1;
-    }
 }
\ No newline at end of file
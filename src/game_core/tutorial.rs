@@ -0,0 +1,152 @@
+use std::error::Error;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+use serde::Deserialize;
+
+use super::event::GameEvent;
+use super::BuildingTypeId;
+
+const PLANET_PLACEHOLDER: &str = "{planet}";
+
+#[derive(Debug)]
+pub enum TutorialError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    UnknownBuilding(String),
+}
+
+impl fmt::Display for TutorialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TutorialError::Io(e) => write!(f, "Failed to read tutorial file: {}", e),
+            TutorialError::Toml(e) => write!(f, "Failed to parse tutorial file (TOML): {}", e),
+            TutorialError::UnknownBuilding(name) => write!(f, "Tutorial refers to unknown building '{}'", name),
+        }
+    }
+}
+
+impl Error for TutorialError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TutorialError::Io(e) => Some(e),
+            TutorialError::Toml(e) => Some(e),
+            TutorialError::UnknownBuilding(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for TutorialError {
+    fn from(err: io::Error) -> Self {
+        TutorialError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for TutorialError {
+    fn from(err: toml::de::Error) -> Self {
+        TutorialError::Toml(err)
+    }
+}
+
+// =================================================================================================
+
+// Action the player has to perform to complete a tutorial step
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TutorialGoal {
+    Command { name: String },
+    BuildingCompleted { building: String },
+    TurnEnded,
+}
+
+impl TutorialGoal {
+    fn is_met_by(&self, event: &GameEvent) -> bool {
+        match (self, event) {
+            (TutorialGoal::Command { name }, GameEvent::CommandExecuted { command }) => name == command,
+            (TutorialGoal::BuildingCompleted { building }, GameEvent::BuildingCompleted { building: built, .. }) => {
+                building == built.get_name()
+            }
+            (TutorialGoal::TurnEnded, GameEvent::TurnEnded { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TutorialStep {
+    pub instruction: String,
+    pub goal: TutorialGoal,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TutorialConfig {
+    pub steps: Vec<TutorialStep>,
+}
+
+impl TutorialConfig {
+    pub fn load(path: &Path) -> Result<Self, TutorialError> {
+        let toml_content = fs::read_to_string(path)?;
+        let config: TutorialConfig = toml::from_str(&toml_content)?;
+
+        for step in &config.steps {
+            if let TutorialGoal::BuildingCompleted { building } = &step.goal
+                && !BuildingTypeId::all().iter().any(|id| id.get_name() == building)
+            {
+                return Err(TutorialError::UnknownBuilding(building.clone()));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+// What the UI shows of the tutorial in progress
+#[derive(Debug, Clone)]
+pub struct TutorialStatus {
+    pub step: usize,
+    pub step_count: usize,
+    pub instruction: String,
+}
+
+#[derive(Debug)]
+pub struct Tutorial {
+    steps: Vec<TutorialStep>,
+    current_step: usize,
+    // Planet substituted for the placeholder in instructions
+    planet: String,
+}
+
+impl Tutorial {
+    pub fn new(config: &TutorialConfig, planet: &str) -> Self {
+        Tutorial {
+            steps: config.steps.clone(),
+            current_step: 0,
+            planet: planet.to_string(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_step >= self.steps.len()
+    }
+
+    // Advances to the next step when the event completes the current one
+    pub fn handle_event(&mut self, event: &GameEvent) -> bool {
+        match self.steps.get(self.current_step) {
+            Some(step) if step.goal.is_met_by(event) => {
+                self.current_step += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_status(&self) -> Option<TutorialStatus> {
+        self.steps.get(self.current_step).map(|step| TutorialStatus {
+            step: self.current_step + 1,
+            step_count: self.steps.len(),
+            instruction: step.instruction.replace(PLANET_PLACEHOLDER, &self.planet),
+        })
+    }
+}
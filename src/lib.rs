@@ -0,0 +1,3 @@
+pub mod game_core;
+pub mod app;
+pub mod server;
@@ -1,15 +1,165 @@
-mod game_core;
-mod app;
+use std::path::Path;
+use std::str::FromStr;
 
-use crate::app::App;
+use terminal_colony::app::{list_profiles, App};
+use terminal_colony::game_core::{
+    AiConfig, BalanceSimulation, BotRunner, BuildingsConfig, EconomyBot, GameCore, GameCoreError, GameDataPaths
+};
+use terminal_colony::server::{ServerError, TextServer};
 
 fn main() {
-    // TODO: Handle error
-    let _ = App::new().unwrap().run();
+    // `--replay <file>` watches a recorded game instead of starting a new one, `--serve <address>`
+    // hosts a game for telnet clients instead of showing the interface,
+    // `--autoplay <turns> [ai] [--thoughts]` lets the built-in AI play a game on its own with a
+    // personality of data/ai.toml, printing its moves and with `--thoughts` why it made them, and
+    // `--simulate <games> [csv|json]` prints a balance report of that many AI-only games.
+    // `--check-buildings [file]` prints the building tables as evaluated from formulas and bases.
+    // `--challenge [YYYY-MM-DD]` starts the daily challenge of that date, today's by default.
+    // `--profile <name>` plays with that profile's own settings, history, statistics and saves in
+    // user/profiles/<name>, together with any of the above, and `--profiles` lists the profiles.
+    // TODO: Serve the interface over SSH (e.g. with russh) as another Frontend, with a session per
+    // connected player or spectator
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let profile = match args.iter().position(|arg| arg == "--profile") {
+        Some(idx) if idx + 1 < args.len() => Some(args.drain(idx..=idx + 1).nth(1).unwrap_or_default()),
+        _ => None,
+    };
+    let profile = profile.as_deref();
+    let app = match args.as_slice() {
+        [flag] if flag == "--profiles" => {
+            match list_profiles() {
+                Ok(profiles) if profiles.is_empty() => println!("No profiles yet, start one with --profile <name>."),
+                Ok(profiles) => println!("{}", profiles.join("\n")),
+                Err(err) => {
+                    eprintln!("Failed to list profiles: {}", err);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        [flag, address] if flag == "--serve" => {
+            if let Err(err) = serve(address) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        [flag, turns, options @ ..] if flag == "--autoplay" => {
+            let thoughts = options.iter().any(|option| option == "--thoughts");
+            let personality = options.iter().find(|option| !option.starts_with("--")).map(String::as_str);
+            if let Err(err) = autoplay(parse_count("turns", turns), personality, thoughts) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        [flag, games, format @ ..] if flag == "--simulate" => {
+            if let Err(err) = simulate(parse_count("games", games), format.first().map(String::as_str)) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        [flag, path @ ..] if flag == "--check-buildings" => {
+            let path = path.first().map_or("data/buildings.toml", String::as_str);
+            match BuildingsConfig::load(Path::new(path)) {
+                Ok(buildings_config) => println!("{}", buildings_config.get_level_tables_report()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        [flag, path] if flag == "--replay" => App::new_replay(profile, Path::new(path)),
+        [flag, date @ ..] if flag == "--challenge" => App::new_challenge(profile, date.first().map(String::as_str)),
+        _ => App::new(profile),
+    };
+
+    let mut app = match app {
+        Ok(app) => app,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    // Errors while running are printed by the app, with a crash report if the game broke
+    if app.run().is_err() {
+        std::process::exit(1);
+    }
+}
+
+// The number given to a flag, anything else stops before a game is played
+fn parse_count<T: FromStr>(name: &str, text: &str) -> T {
+    text.parse().unwrap_or_else(|_| {
+        eprintln!("Expected a number of {}, got '{}'.", name, text);
+        std::process::exit(1);
+    })
+}
+
+fn serve(address: &str) -> Result<(), ServerError> {
+    let mut server = TextServer::bind(address)?;
+    println!("Serving TerminalColony on {}, connect with telnet or netcat.", server.get_address()?);
+    server.run()
+}
+
+// Plays the first personality unless another one is named, its thoughts are printed with a `?`
+fn autoplay(turns: u32, personality: Option<&str>, thoughts: bool) -> Result<(), GameCoreError> {
+    let mut game_core = GameCore::new_with_name_seed(&GameDataPaths::default(), 0)?;
+    let ai_config = AiConfig::load(Path::new("data/ai.toml"))?;
+    let personality = match personality {
+        Some(id) => ai_config.find_personality(id).unwrap_or_else(|| {
+            eprintln!("Unknown AI personality '{}', playing the first one.", id);
+            ai_config.get_personality_for_seat(0)
+        }),
+        None => ai_config.get_personality_for_seat(0),
+    };
+    println!("{} plays {}.", personality, game_core.get_current_player_name());
+
+    let mut bot = EconomyBot::new(&ai_config, personality);
+    if thoughts {
+        bot = bot.with_thoughts();
+    }
+    let mut bot_runner = BotRunner::new();
+    bot_runner.seat(&game_core.get_current_player_name(), Box::new(bot));
+
+    while game_core.is_running() && game_core.get_current_turn() <= turns {
+        let player_name = game_core.get_current_player_name();
+        let moves = bot_runner.play(&mut game_core);
+        if moves.is_empty() {
+            break;
+        }
+
+        for thought in bot_runner.take_thoughts(&player_name) {
+            println!("? {}", thought);
+        }
+
+        for bot_move in moves {
+            let result = match bot_move.result {
+                Ok(message) => message.unwrap_or_else(|| "Command executed successfully.".to_string()),
+                Err(err) => format!("Error: {}", err),
+            };
+            println!("{} > {}\n{}", bot_move.player_name, bot_move.command, result);
+        }
+        for notification in game_core.take_notifications() {
+            println!("* {}", notification);
+        }
+    }
+    Ok(())
+}
+
+// Turns every simulated game is played for
+const SIMULATED_TURNS: u32 = 100;
+
+fn simulate(games: u64, format: Option<&str>) -> Result<(), GameCoreError> {
+    let report = BalanceSimulation::new(&GameDataPaths::default())?.run(games, SIMULATED_TURNS)?;
+    match format {
+        Some("json") => print!("{}", report.to_json()),
+        _ => print!("{}", report.to_csv()),
+    }
+    Ok(())
 }
 
-// TODO: Building a building does not consume resources
-// TODO: Figure out how to handle building time
-// TODO: Change help command for question mark which will show help for all commands
 // TODO: Change quit command for exiting the app and ask for confirmation
-// TODO: Main menu - new game, load game, settings
\ No newline at end of file
+// TODO: Main menu - new game, load game, settings
+// TODO: New game setup in the main menu - reroll the generated names, toggle the rules
\ No newline at end of file
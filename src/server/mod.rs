@@ -0,0 +1,3 @@
+mod text_server;
+
+pub use text_server::{ServerError, TextServer};
@@ -0,0 +1,398 @@
+use std::error::Error;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use std::{fmt, thread};
+
+use crate::game_core::{GameCore, GameCoreError, GameDataPaths, PlanetStatus, Resource};
+
+#[derive(Debug)]
+pub enum ServerError {
+    Io(io::Error),
+    GameCoreError(GameCoreError),
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerError::Io(err) => write!(f, "Server IO error: {}", err),
+            ServerError::GameCoreError(err) => write!(f, "GameCore error: {}", err),
+        }
+    }
+}
+
+impl Error for ServerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ServerError::Io(err) => Some(err),
+            ServerError::GameCoreError(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ServerError {
+    fn from(err: io::Error) -> Self {
+        ServerError::Io(err)
+    }
+}
+
+impl From<GameCoreError> for ServerError {
+    fn from(err: GameCoreError) -> Self {
+        ServerError::GameCoreError(err)
+    }
+}
+
+// =================================================================================================
+
+// Telnet commands start with this byte, a doubled one stands for the byte itself
+const TELNET_IAC: u8 = 255;
+// Starts and ends the option data of a subnegotiation, e.g. the terminal type
+const TELNET_SB: u8 = 250;
+const TELNET_SE: u8 = 240;
+// WILL, WONT, DO and DONT are followed by the option they are about
+const TELNET_WILL: u8 = 251;
+const TELNET_DONT: u8 = 254;
+// Time to wait when no client had anything to say
+const IDLE_SLEEP: Duration = Duration::from_millis(20);
+// A client sending a longer line, or not reading this much output, is dropped
+const MAX_LINE_SIZE: usize = 4096;
+const MAX_UNSENT_SIZE: usize = 1024 * 1024;
+// Time the clients get to receive the last output when the game ends
+const FINAL_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
+const WELCOME: &str = "Welcome to TerminalColony.\r\n\
+    Every line you send runs as a command, just like in the command input. Type 'help' for the \
+    commands, 'status [planet]' for your empire and 'bye' or 'quit' to disconnect.\r\n";
+const PROMPT: &str = "> ";
+
+// Where in a telnet command the bytes received so far ended
+#[derive(Clone, Copy)]
+enum TelnetState {
+    Data,
+    // After IAC
+    Command,
+    // After IAC and WILL, WONT, DO or DONT
+    Option,
+    // Between IAC SB and IAC SE
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+struct Client {
+    stream: TcpStream,
+    address: SocketAddr,
+    // Received bytes not forming a complete line yet, without telnet commands
+    pending: Vec<u8>,
+    telnet_state: TelnetState,
+    // Output the socket didn't take yet, sent as it becomes writable
+    unsent: Vec<u8>,
+    connected: bool,
+    // Set after 'bye', the client is dropped once its output is sent
+    leaving: bool,
+}
+
+impl Client {
+    fn new(stream: TcpStream, address: SocketAddr) -> Self {
+        Client {
+            stream,
+            address,
+            pending: Vec::new(),
+            telnet_state: TelnetState::Data,
+            unsent: Vec::new(),
+            connected: true,
+            leaving: false,
+        }
+    }
+
+    fn send(&mut self, text: &str) {
+        self.unsent.extend_from_slice(text.as_bytes());
+        self.flush();
+        // Holding on to everything a client never reads would only grow
+        if self.unsent.len() > MAX_UNSENT_SIZE {
+            self.connected = false;
+        }
+    }
+
+    // Writes as much of the unsent output as the socket takes without blocking
+    fn flush(&mut self) {
+        while !self.unsent.is_empty() && self.connected {
+            match self.stream.write(&self.unsent) {
+                Ok(0) => self.connected = false,
+                Ok(count) => {
+                    self.unsent.drain(..count);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                // A client that can't be written to is dropped on the next turn of the loop
+                Err(_) => self.connected = false,
+            }
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        !self.connected || (self.leaving && self.unsent.is_empty())
+    }
+
+    // Complete lines received since the last call, without telnet commands
+    fn read_lines(&mut self) -> Vec<String> {
+        if self.leaving {
+            return Vec::new();
+        }
+
+        let mut buffer = [0u8; 1024];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    self.connected = false;
+                    break;
+                }
+                Ok(count) => {
+                    self.receive(&buffer[..count]);
+                    // The rest waits for the next call, after the complete lines were taken
+                    if self.pending.len() > MAX_LINE_SIZE {
+                        break;
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => {
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim().to_string());
+        }
+        if self.pending.len() > MAX_LINE_SIZE {
+            self.pending.clear();
+            self.send("Error: Line too long, disconnecting.\r\n");
+            self.leaving = true;
+        }
+        lines
+    }
+
+    // Keeps the text of the received bytes, telnet commands may be split across reads
+    fn receive(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.telnet_state = match (self.telnet_state, byte) {
+                (TelnetState::Data, TELNET_IAC) => TelnetState::Command,
+                (TelnetState::Data, _) => {
+                    self.pending.push(byte);
+                    TelnetState::Data
+                }
+                (TelnetState::Command, TELNET_IAC) => {
+                    self.pending.push(byte);
+                    TelnetState::Data
+                }
+                (TelnetState::Command, TELNET_SB) => TelnetState::Subnegotiation,
+                (TelnetState::Command, TELNET_WILL..=TELNET_DONT) => TelnetState::Option,
+                // Commands without an option, e.g. NOP or Go Ahead
+                (TelnetState::Command, _) | (TelnetState::Option, _) => TelnetState::Data,
+                (TelnetState::Subnegotiation, TELNET_IAC) => TelnetState::SubnegotiationIac,
+                (TelnetState::Subnegotiation, _) => TelnetState::Subnegotiation,
+                (TelnetState::SubnegotiationIac, TELNET_SE) => TelnetState::Data,
+                // Including an escaped IAC inside the option data
+                (TelnetState::SubnegotiationIac, _) => TelnetState::Subnegotiation,
+            };
+        }
+    }
+}
+
+// Plain-text frontend serving one game to any number of telnet or netcat clients. All of them
+// command the same empire, results go to the client that sent the command while notifications
+// go to everyone.
+// TODO: Seat every connection as its own player once the game has several
+pub struct TextServer {
+    listener: TcpListener,
+    game_core: GameCore,
+    clients: Vec<Client>,
+}
+
+impl TextServer {
+    pub fn bind(address: &str) -> Result<Self, ServerError> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(TextServer {
+            listener,
+            game_core: GameCore::new(&GameDataPaths::default())?,
+            clients: Vec::new(),
+        })
+    }
+
+    pub fn get_address(&self) -> Result<SocketAddr, ServerError> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    // Serves the game until it ends, clients quitting only disconnect themselves
+    pub fn run(&mut self) -> Result<(), ServerError> {
+        while self.game_core.is_running() {
+            let accepted = self.accept_clients()?;
+
+            let mut received = false;
+            for idx in 0..self.clients.len() {
+                for line in self.clients[idx].read_lines() {
+                    if self.clients[idx].leaving {
+                        break;
+                    }
+                    received = true;
+                    self.handle_line(idx, &line);
+                }
+            }
+
+            let notifications = self.game_core.take_notifications();
+            if !notifications.is_empty() {
+                let text: String = notifications.iter().map(|notification| format!("* {}\r\n", notification)).collect();
+                self.broadcast(&text);
+            }
+
+            for client in &mut self.clients {
+                client.flush();
+            }
+            for client in self.clients.iter().filter(|client| client.is_done()) {
+                println!("{} disconnected.", client.address);
+            }
+            self.clients.retain(|client| !client.is_done());
+            if !accepted && !received {
+                thread::sleep(IDLE_SLEEP);
+            }
+        }
+
+        self.broadcast("The game has ended.\r\n");
+        let deadline = Instant::now() + FINAL_FLUSH_TIMEOUT;
+        while Instant::now() < deadline && self.clients.iter().any(|client| client.connected && !client.unsent.is_empty()) {
+            for client in &mut self.clients {
+                client.flush();
+            }
+            thread::sleep(IDLE_SLEEP);
+        }
+        Ok(())
+    }
+
+    // Returns whether anyone connected
+    fn accept_clients(&mut self) -> Result<bool, ServerError> {
+        let mut accepted = false;
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    stream.set_nonblocking(true)?;
+                    let mut client = Client::new(stream, address);
+                    println!("{} connected.", address);
+                    client.send(WELCOME);
+                    client.send(PROMPT);
+                    self.clients.push(client);
+                    accepted = true;
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(accepted),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    fn handle_line(&mut self, client_idx: usize, line: &str) {
+        let reply = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] => String::new(),
+            // Quitting ends the session of the client only, the game goes on for the others
+            ["bye"] | ["quit"] => {
+                let client = &mut self.clients[client_idx];
+                client.send("Goodbye.\r\n");
+                client.leaving = true;
+                return;
+            }
+            ["status"] => self.get_status_text(None),
+            ["status", planet] => self.get_status_text(Some(planet)),
+            _ => match self.game_core.execute_command(line) {
+                Ok(Some(message)) => format!("{}\r\n", message.replace('\n', "\r\n")),
+                Ok(None) => "Command executed successfully.\r\n".to_string(),
+                Err(err) => format!("Error: {}\r\n", err.to_string().replace('\n', "\r\n")),
+            },
+        };
+
+        let client = &mut self.clients[client_idx];
+        client.send(&reply);
+        client.send(PROMPT);
+    }
+
+    fn broadcast(&mut self, text: &str) {
+        for client in &mut self.clients {
+            client.send(text);
+        }
+    }
+
+    fn get_status_text(&self, planet: Option<&str>) -> String {
+        let mut lines = vec![format!(
+            "Turn {} | {} | Credits {} ({:+}/t)",
+            self.game_core.get_current_turn(),
+            self.game_core.get_current_player_name(),
+            self.game_core.get_current_player_credits(),
+            self.game_core.get_current_player_credits_income(),
+        )];
+
+        let planet_names = self.game_core.get_current_player_planet_names();
+        let shown: Vec<&String> = match planet {
+            Some(planet) => planet_names.iter().filter(|name| name.eq_ignore_ascii_case(planet)).collect(),
+            None => planet_names.iter().collect(),
+        };
+        if shown.is_empty() {
+            return format!("Error: Planet '{}' not found.\r\n", planet.unwrap_or_default());
+        }
+
+        for planet_name in shown {
+            if let Some(status) = self.game_core.get_current_player_planet_status(planet_name) {
+                lines.extend(Self::format_planet_status(&status));
+            }
+        }
+        lines.iter().map(|line| format!("{}\r\n", line)).collect()
+    }
+
+    fn format_planet_status(status: &PlanetStatus) -> Vec<String> {
+        let mut lines = vec![
+            format!("{} ({}):", status.planet_name, status.size),
+            format!(
+                "  Population {}/{} | Tax {}% | Morale {}%{} | Slots {}/{}",
+                status.population,
+                status.housing,
+                status.tax_rate,
+                status.morale,
+                if status.on_strike { " (on strike)" } else { "" },
+                status.building_slots.0,
+                status.building_slots.1,
+            ),
+        ];
+
+        for resource in [Resource::Energy, Resource::Minerals, Resource::Gas] {
+            let production = status.production.get(&resource).copied().unwrap_or_default();
+            let (amount, capacity) = status.storage.get(&resource).copied().unwrap_or_default();
+            let reserved = match status.reserved.get(&resource) {
+                Some(reserved) => format!(", {} reserved", reserved),
+                None => String::new(),
+            };
+            lines.push(format!("  {}: +{}/t | {}/{}{}", resource, production, amount, capacity, reserved));
+        }
+
+        let mut buildings: Vec<String> = status.buildings
+            .iter()
+            .filter(|(_, level)| *level > 0)
+            .map(|(name, level)| format!("{} {}", name, level))
+            .collect();
+        buildings.sort();
+        if !buildings.is_empty() {
+            lines.push(format!("  Buildings: {}", buildings.join(", ")));
+        }
+
+        for construction in &status.construction_queue {
+            lines.push(format!(
+                "  Building {} level {} on {}, {} turn(s) left{}",
+                construction.building.get_name(),
+                construction.level,
+                construction.get_location(&status.planet_name),
+                construction.remaining_turns,
+                if construction.is_reserved { "" } else { " once it can be afforded" },
+            ));
+        }
+        lines
+    }
+}